@@ -4,17 +4,17 @@ use crate::Renderer;
 use baseview::{Window, WindowScalePolicy};
 use femtovg::Canvas;
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
-use tuix_core::TreeExt;
-use tuix_core::{MouseButton, MouseButtonState};
+use std::time::Instant;
+use tuix_core::{FemtovgRenderer, Renderer as _};
+use tuix_core::{ModifiersState, MouseButton, MouseButtonState};
 use tuix_core::WindowWidget;
 use tuix_core::{
-    Event, Propagation,
+    EventProxy, EventReceiver, InputEvent, InputState,
     WindowDescription,
     BoundingBox
 };
 use tuix_core::{
-    Entity, EventManager, Tree, PropSet, WindowSize, State, Units, Visibility,
-    WindowEvent,
+    Entity, EventManager, PropSet, WindowSize, State, Units,
 };
 
 pub struct Application<F>
@@ -25,6 +25,8 @@ where
     app: F,
     window_description: WindowDescription,
     on_idle: Option<Box<dyn Fn(&mut State) + Send>>,
+    event_proxy: EventProxy,
+    event_receiver: EventReceiver,
 }
 
 impl<F> Application<F>
@@ -33,13 +35,25 @@ where
     F: 'static + Send,
 {
     pub fn new(window_description: WindowDescription, app: F) -> Self {
+        // The state is created on the thread of the window, so the proxy is created here to be available before then
+        let (event_proxy, event_receiver) = EventProxy::channel();
+
         Self {
             app,
             window_description,
             on_idle: None,
+            event_proxy,
+            event_receiver,
         }
     }
 
+    /// Returns a proxy which sends events into the application from other threads, e.g. the audio thread of a plugin.
+    ///
+    /// The events are received at the start of the next frame of the window.
+    pub fn event_proxy(&self) -> EventProxy {
+        self.event_proxy.clone()
+    }
+
     /// Open a new window that blocks the current thread until the window is destroyed.
     ///
     /// Do **not** use this in the context of audio plugins, unless it is compiled as a
@@ -47,7 +61,7 @@ where
     ///
     /// * `app` - The Tuix application builder.
     pub fn run(self) {
-        TuixWindow::open_blocking(self.window_description, self.app, self.on_idle)
+        TuixWindow::open_blocking(self.window_description, self.app, self.on_idle, (self.event_proxy, self.event_receiver))
     }
 
     /// Open a new child window.
@@ -58,7 +72,7 @@ where
     /// * `parent` - The parent window.
     /// * `app` - The Tuix application builder.
     pub fn open_parented<P: HasRawWindowHandle>(self, parent: &P) {
-        TuixWindow::open_parented(parent, self.window_description, self.app, self.on_idle)
+        TuixWindow::open_parented(parent, self.window_description, self.app, self.on_idle, (self.event_proxy, self.event_receiver))
     }
 
     /// Open a new window as if it had a parent window.
@@ -68,7 +82,7 @@ where
     ///
     /// * `app` - The Tuix application builder.
     pub fn open_as_if_parented(self) -> RawWindowHandle {
-        TuixWindow::open_as_if_parented(self.window_description, self.app, self.on_idle)
+        TuixWindow::open_as_if_parented(self.window_description, self.app, self.on_idle, (self.event_proxy, self.event_receiver))
     }
 
 
@@ -100,8 +114,8 @@ where
 pub(crate) struct ApplicationRunner {
     state: State,
     event_manager: EventManager,
-    canvas: Canvas<Renderer>,
-    tree: Tree,
+    canvas: FemtovgRenderer<Renderer>,
+    input_state: InputState,
     pos: (f32, f32),
     should_redraw: bool,
     scale_policy: WindowScalePolicy,
@@ -112,7 +126,7 @@ impl ApplicationRunner {
     pub fn new(mut state: State, win_desc: WindowDescription, renderer: Renderer) -> Self {
         let event_manager = EventManager::new();
 
-        let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
+        let mut canvas = FemtovgRenderer::new(Canvas::new(renderer).expect("Cannot create canvas"));
 
         // TODO: Get scale policy from `win_desc`.
         let scale_policy = WindowScalePolicy::SystemScaleFactor;
@@ -143,7 +157,7 @@ impl ApplicationRunner {
         state.add_font_mem("emoji", emoji_font);
         state.add_font_mem("arabic", arabic_font);
 
-        canvas.scale(scale as f32, scale as f32);
+        state.set_scale_factor(scale as f32);
 
         state
             .style
@@ -156,10 +170,10 @@ impl ApplicationRunner {
 
         state
             .data
-            .set_width(Entity::root(), physical_size.width as f32);
+            .set_width(Entity::root(), logical_size.width as f32);
         state
             .data
-            .set_height(Entity::root(), physical_size.height as f32);
+            .set_height(Entity::root(), logical_size.height as f32);
         state.data.set_opacity(Entity::root(), 1.0);
 
         let mut bounding_box = BoundingBox::default();
@@ -175,15 +189,13 @@ impl ApplicationRunner {
         root.restyle(&mut state);
         root.relayout(&mut state);
 
-        let tree = state.tree.clone();
-
         //tuix_core::systems::apply_styles(&mut state, &tree);
 
         ApplicationRunner {
             event_manager,
             state,
             canvas,
-            tree,
+            input_state: InputState::new(),
             pos: (0.0, 0.0),
             should_redraw: true,
             scale_policy,
@@ -206,17 +218,19 @@ impl ApplicationRunner {
     */
 
     pub fn on_frame_update(&mut self) {
+        self.state.scheduler.begin_frame(Instant::now());
+        self.state.update_timers(Instant::now());
+        self.state.receive_proxied_events();
 
-        
+        // Animated entities are marked for redrawing by apply_animations, so the whole window isn't redrawn
         if self.state.apply_animations() {
             Entity::root().restyle(&mut self.state);
             Entity::root().relayout(&mut self.state);
-            Entity::root().redraw(&mut self.state);
         }
 
-        while !self.state.event_queue.is_empty() {
+        if !self.state.event_queue.is_empty() {
             self.event_manager.flush_events(&mut self.state);
-        } 
+        }
 
         if self.state.needs_redraw {
         //     // TODO - Move this to EventManager
@@ -226,6 +240,17 @@ impl ApplicationRunner {
 
     }
 
+    /// Returns true if the window has changed since it was last drawn
+    pub fn should_redraw(&self) -> bool {
+        self.should_redraw
+    }
+
+    /// Records the statistics of the frame, after it has been drawn if it needed drawing
+    pub fn end_frame(&mut self) {
+        self.state.scheduler.end_frame(Instant::now());
+        self.state.profiler.end_frame();
+    }
+
     pub fn render(&mut self) {
         let tree = self.state.tree.clone();
         tuix_core::apply_clipping(&mut self.state, &tree);
@@ -235,169 +260,32 @@ impl ApplicationRunner {
 
     pub fn handle_event(&mut self, event: baseview::Event, should_quit: &mut bool) {
         if requests_exit(&event) {
-            self.state
-                .insert_event(Event::new(WindowEvent::WindowClose));
+            self.input_state.handle(&mut self.state, InputEvent::CloseRequested);
             *should_quit = true;
         }
 
         match event {
             baseview::Event::Mouse(event) => match event {
                 baseview::MouseEvent::CursorMoved { position } => {
-                    let cursorx = (position.x) as f32;
-                    let cursory = (position.y) as f32;
-
-                    self.state.mouse.cursorx = cursorx;
-                    self.state.mouse.cursory = cursory;
-
-                    tuix_core::apply_hover(&mut self.state);
-
-                    if self.state.captured != Entity::null() {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseMove(cursorx, cursory))
-                                .target(self.state.captured)
-                                .propagate(Propagation::Direct),
-                        );
-                    } else if self.state.hovered != Entity::root() {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseMove(cursorx, cursory))
-                                .target(self.state.hovered),
-                        );
-                    }
+                    // The position is in the logical pixels of the window, which differ from those of the
+                    // layout if the scale factor is overridden
+                    let scale = self.scale_factor as f32 / self.state.scale_factor();
+                    let cursorx = (position.x) as f32 * scale;
+                    let cursory = (position.y) as f32 * scale;
+
+                    self.input_state.handle(&mut self.state, InputEvent::PointerMoved(cursorx, cursory));
                 }
                 baseview::MouseEvent::ButtonPressed(button) => {
-                    let b = match button {
-                        baseview::MouseButton::Left => MouseButton::Left,
-                        baseview::MouseButton::Right => MouseButton::Right,
-                        baseview::MouseButton::Middle => MouseButton::Middle,
-                        baseview::MouseButton::Other(id) => MouseButton::Other(id as u16),
-                        baseview::MouseButton::Back => MouseButton::Other(4),
-                        baseview::MouseButton::Forward => MouseButton::Other(5),
-                    };
-
-                    match b {
-                        MouseButton::Left => {
-                            self.state.mouse.left.state = MouseButtonState::Pressed;
-                        }
-                        MouseButton::Right => {
-                            self.state.mouse.right.state = MouseButtonState::Pressed;
-                        }
-                        MouseButton::Middle => {
-                            self.state.mouse.middle.state = MouseButtonState::Pressed;
-                        }
-                        _ => {}
-                    };
-
-                    // if self.state.hovered != Entity::null()
-                    //     && self.state.active != self.state.hovered
-                    // {
-                    //     self.state.active = self.state.hovered;
-                    //     self.state.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
-                    //     self.state.needs_restyle = true;
-                    // }
-
-                    let target = if self.state.captured != Entity::null() {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseDown(b))
-                                .target(self.state.captured)
-                                .propagate(Propagation::Direct),
-                        );
-                        self.state.captured
-                    } else {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseDown(b))
-                                .target(self.state.hovered),
-                        );
-                        self.state.hovered
-                    };
-
-                    // if let Some(event_handler) = self.event_manager.event_handlers.get_mut(&target) {
-                    //     if let Some(callback) = self.event_manager.callbacks.get_mut(&target) {
-                    //         (callback)(event_handler, &mut self.state, target);
-                    //     }
-                    // }
-
-                    match b {
-                        MouseButton::Left => {
-                            self.state.mouse.left.pos_down =
-                                (self.state.mouse.cursorx, self.state.mouse.cursory);
-                            self.state.mouse.left.pressed = self.state.hovered;
-                        }
-
-                        MouseButton::Middle => {
-                            self.state.mouse.middle.pos_down =
-                                (self.state.mouse.cursorx, self.state.mouse.cursory);
-                            self.state.mouse.left.pressed = self.state.hovered;
-                        }
-
-                        MouseButton::Right => {
-                            self.state.mouse.right.pos_down =
-                                (self.state.mouse.cursorx, self.state.mouse.cursory);
-                            self.state.mouse.left.pressed = self.state.hovered;
-                        }
-
-                        _ => {}
-                    }
+                    self.input_state.handle(
+                        &mut self.state,
+                        InputEvent::Button(translate_button(button), MouseButtonState::Pressed),
+                    );
                 }
                 baseview::MouseEvent::ButtonReleased(button) => {
-                    let b = match button {
-                        baseview::MouseButton::Left => MouseButton::Left,
-                        baseview::MouseButton::Right => MouseButton::Right,
-                        baseview::MouseButton::Middle => MouseButton::Middle,
-                        baseview::MouseButton::Other(id) => MouseButton::Other(id as u16),
-                        baseview::MouseButton::Back => MouseButton::Other(4),
-                        baseview::MouseButton::Forward => MouseButton::Other(5),
-                    };
-
-                    match b {
-                        MouseButton::Left => {
-                            self.state.mouse.left.state = MouseButtonState::Released;
-                        }
-                        MouseButton::Right => {
-                            self.state.mouse.right.state = MouseButtonState::Released;
-                        }
-                        MouseButton::Middle => {
-                            self.state.mouse.middle.state = MouseButtonState::Released;
-                        }
-                        _ => {}
-                    };
-
-                    // self.state.active = Entity::null();
-                    // self.state.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
-                    // self.state.needs_restyle = true;
-
-                    if self.state.captured != Entity::null() {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseUp(b))
-                                .target(self.state.captured)
-                                .propagate(Propagation::Direct),
-                        );
-                    } else {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseUp(b)).target(self.state.hovered),
-                        );
-                    }
-
-                    match b {
-                        MouseButton::Left => {
-                            self.state.mouse.left.pos_up =
-                                (self.state.mouse.cursorx, self.state.mouse.cursory);
-                            self.state.mouse.left.released = self.state.hovered;
-                        }
-
-                        MouseButton::Middle => {
-                            self.state.mouse.middle.pos_up =
-                                (self.state.mouse.cursorx, self.state.mouse.cursory);
-                            self.state.mouse.left.released = self.state.hovered;
-                        }
-
-                        MouseButton::Right => {
-                            self.state.mouse.right.pos_up =
-                                (self.state.mouse.cursorx, self.state.mouse.cursory);
-                            self.state.mouse.left.released = self.state.hovered;
-                        }
-
-                        _ => {}
-                    }
+                    self.input_state.handle(
+                        &mut self.state,
+                        InputEvent::Button(translate_button(button), MouseButtonState::Released),
+                    );
                 }
                 baseview::MouseEvent::WheelScrolled(scroll_delta) => {
                     let (lines_x, lines_y) = match scroll_delta {
@@ -420,145 +308,40 @@ impl ApplicationRunner {
                         ),
                     };
 
-                    if self.state.captured != Entity::null() {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseScroll(lines_x, lines_y))
-                                .target(self.state.captured)
-                                .propagate(Propagation::Direct),
-                        );
-                    } else {
-                        self.state.insert_event(
-                            Event::new(WindowEvent::MouseScroll(lines_x, lines_y))
-                                .target(self.state.hovered),
-                        );
-                    }
+                    self.input_state.handle(&mut self.state, InputEvent::Wheel(lines_x, lines_y));
                 }
                 _ => {}
             },
             baseview::Event::Keyboard(event) => {
-                use keyboard_types::Code;
+                use keyboard_types::Modifiers;
 
-                let (s, pressed) = match event.state {
-                    keyboard_types::KeyState::Down => (MouseButtonState::Pressed, true),
-                    keyboard_types::KeyState::Up => (MouseButtonState::Released, false),
+                let s = match event.state {
+                    keyboard_types::KeyState::Down => MouseButtonState::Pressed,
+                    keyboard_types::KeyState::Up => MouseButtonState::Released,
                 };
 
-                match event.code {
-                    Code::ShiftLeft | Code::ShiftRight => self.state.modifiers.shift = pressed,
-                    Code::ControlLeft | Code::ControlRight => self.state.modifiers.ctrl = pressed,
-                    Code::AltLeft | Code::AltRight => self.state.modifiers.alt = pressed,
-                    Code::MetaLeft | Code::MetaRight => self.state.modifiers.logo = pressed,
-                    _ => (),
-                }
-
-                if event.code == Code::F5 && s == MouseButtonState::Pressed {
-                    self.state.reload_styles().unwrap();
-                }
-
-                if event.code == Code::Tab && s == MouseButtonState::Pressed {
-                    let next_focus = self
-                        .state
-                        .style
-                        .focus_order
-                        .get(self.state.focused)
-                        .cloned()
-                        .unwrap_or_default()
-                        .next;
-                    let prev_focus = self
-                        .state
-                        .style
-                        .focus_order
-                        .get(self.state.focused)
-                        .cloned()
-                        .unwrap_or_default()
-                        .prev;
-
-                    if self.state.modifiers.shift {
-                        if prev_focus != Entity::null() {
-                            self.state.focused.set_focus(&mut self.state, false);
-                            self.state.focused = prev_focus;
-                            self.state.focused.set_focus(&mut self.state, true);
-                        } else {
-                            // TODO impliment reverse iterator for tree
-                            // state.focused = match state.focused.into_iter(&state.tree).next() {
-                            //     Some(val) => val,
-                            //     None => Entity::root(),
-                            // };
-                        }
-                    } else {
-                        if next_focus != Entity::null() {
-                            self.state.focused.set_focus(&mut self.state, false);
-                            self.state.focused = next_focus;
-                            self.state.focused.set_focus(&mut self.state, true);
-                        } else {
-                            self.state.focused.set_focus(&mut self.state, false);
-                            self.state.focused =
-                                match self.state.focused.tree_iter(&self.tree).next() {
-                                    Some(val) => val,
-                                    None => Entity::root(),
-                                };
-                            self.state.focused.set_focus(&mut self.state, true);
-                        }
-                    }
-
-                    Entity::root().restyle(&mut self.state);
-                }
-
-                match s {
-                    MouseButtonState::Pressed => {
-                        if self.state.focused != Entity::null() {
-                            self.state.insert_event(
-                                Event::new(WindowEvent::KeyDown(
-                                    event.code,
-                                    Some(event.key.clone()),
-                                ))
-                                .target(self.state.focused)
-                                .propagate(Propagation::DownUp),
-                            );
-                        } else {
-                            self.state.insert_event(
-                                Event::new(WindowEvent::KeyDown(
-                                    event.code,
-                                    Some(event.key.clone()),
-                                ))
-                                .target(self.state.hovered)
-                                .propagate(Propagation::DownUp),
-                            );
-                        }
-
-                        if let keyboard_types::Key::Character(written) = &event.key {
-                            for chr in written.chars() {
-                                self.state.insert_event(
-                                    Event::new(WindowEvent::CharInput(chr))
-                                        .target(self.state.focused)
-                                        .propagate(Propagation::Down),
-                                );
-                            }
-                        }
-                    }
-
-                    MouseButtonState::Released => {
-                        if self.state.focused != Entity::null() {
-                            self.state.insert_event(
-                                Event::new(WindowEvent::KeyUp(event.code, Some(event.key)))
-                                    .target(self.state.focused)
-                                    .propagate(Propagation::DownUp),
-                            );
-                        } else {
-                            self.state.insert_event(
-                                Event::new(WindowEvent::KeyUp(event.code, Some(event.key)))
-                                    .target(self.state.hovered)
-                                    .propagate(Propagation::DownUp),
-                            );
-                        }
+                self.input_state.handle(
+                    &mut self.state,
+                    InputEvent::Modifiers(ModifiersState {
+                        shift: event.modifiers.contains(Modifiers::SHIFT),
+                        ctrl: event.modifiers.contains(Modifiers::CONTROL),
+                        alt: event.modifiers.contains(Modifiers::ALT),
+                        logo: event.modifiers.contains(Modifiers::META),
+                    }),
+                );
+
+                self.input_state.handle(&mut self.state, InputEvent::Key(event.code, Some(event.key.clone()), s));
+
+                // Baseview has no separate text event so the characters of a pressed key are sent as text
+                if let (MouseButtonState::Pressed, keyboard_types::Key::Character(written)) = (s, &event.key) {
+                    for chr in written.chars() {
+                        self.input_state.handle(&mut self.state, InputEvent::Text(chr));
                     }
                 }
             }
             baseview::Event::Window(event) => match event {
                 baseview::WindowEvent::Focused => {
-                    Entity::root().restyle(&mut self.state);
-                    Entity::root().relayout(&mut self.state);
-                    Entity::root().redraw(&mut self.state);
+                    self.input_state.handle(&mut self.state, InputEvent::Focused(true));
                 }
                 baseview::WindowEvent::Resized(window_info) => {
                     self.scale_factor = match self.scale_policy {
@@ -566,49 +349,18 @@ impl ApplicationRunner {
                         WindowScalePolicy::SystemScaleFactor => window_info.scale(),
                     };
 
-                    let logical_size = (
-                        (window_info.physical_size().width as f64 / self.scale_factor),
-                        (window_info.physical_size().height as f64 / self.scale_factor),
-                    );
-
-                    let physical_size = (
-                        window_info.physical_size().width,
-                        window_info.physical_size().height,
-                    );
-
-                    self.state
-                        .style
-                        .width
-                        .insert(Entity::root(), Units::Pixels(logical_size.0 as f32));
-                    self.state
-                        .style
-                        .height
-                        .insert(Entity::root(), Units::Pixels(logical_size.1 as f32));
-
-                    self.state
-                        .data
-                        .set_width(Entity::root(), physical_size.0 as f32);
-                    self.state
-                        .data
-                        .set_height(Entity::root(), physical_size.1 as f32);
+                    self.input_state.handle(&mut self.state, InputEvent::ScaleFactor(self.scale_factor as f32));
 
-                    let mut bounding_box = BoundingBox::default();
-                    bounding_box.w = physical_size.0 as f32;
-                    bounding_box.h = physical_size.1 as f32;
+                    let logical_width = window_info.physical_size().width as f32 / self.state.scale_factor();
+                    let logical_height = window_info.physical_size().height as f32 / self.state.scale_factor();
 
-                    self.state.data.set_clip_region(Entity::root(), bounding_box);
-
-                    Entity::root().restyle(&mut self.state);
-                    Entity::root().relayout(&mut self.state);
-                    Entity::root().redraw(&mut self.state);
-
-                }
-                baseview::WindowEvent::WillClose => {
-                    self.state
-                        .insert_event(Event::new(WindowEvent::WindowClose));
+                    self.input_state.handle(&mut self.state, InputEvent::Resized(logical_width, logical_height));
                 }
+                // WillClose requests the application to exit, so the close event has already been sent
                 _ => {}
             },
+            // This version of baseview doesn't report files dragged onto the window, so there are no
+            // FileHovered or FileDropped events to translate
         }
     }
 
@@ -619,6 +371,18 @@ impl ApplicationRunner {
     }
 }
 
+// Converts a baseview mouse button to a tuix mouse button
+fn translate_button(button: baseview::MouseButton) -> MouseButton {
+    match button {
+        baseview::MouseButton::Left => MouseButton::Left,
+        baseview::MouseButton::Right => MouseButton::Right,
+        baseview::MouseButton::Middle => MouseButton::Middle,
+        baseview::MouseButton::Other(id) => MouseButton::Other(id as u16),
+        baseview::MouseButton::Back => MouseButton::Other(4),
+        baseview::MouseButton::Forward => MouseButton::Other(5),
+    }
+}
+
 /// Returns true if the provided event should cause an [`Application`] to
 /// exit.
 pub fn requests_exit(event: &baseview::Event) -> bool {
@@ -1,7 +1,7 @@
 use crate::{application::ApplicationRunner, Renderer};
 use baseview::{Event, EventStatus, Window, WindowHandler, WindowOpenOptions, WindowScalePolicy};
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
-use tuix_core::{Entity, State, WindowDescription};
+use tuix_core::{Entity, EventProxy, EventReceiver, State, WindowDescription};
 
 /// Handles an tuix_baseview application
 pub(crate) struct TuixWindow {
@@ -29,7 +29,7 @@ impl TuixWindow {
     ///
     /// * `parent` - The parent window.
     /// * `app` - The Tuix application builder.
-    pub fn open_parented<P, F>(parent: &P, win_desc: WindowDescription, mut app: F, on_idle: Option<Box<dyn Fn(&mut State) + Send>>)
+    pub fn open_parented<P, F>(parent: &P, win_desc: WindowDescription, mut app: F, on_idle: Option<Box<dyn Fn(&mut State) + Send>>, channel: (EventProxy, EventReceiver))
     where
         P: HasRawWindowHandle,
         F: FnOnce(&mut State, Entity),
@@ -49,6 +49,7 @@ impl TuixWindow {
             window_settings,
             move |window: &mut baseview::Window<'_>| -> TuixWindow {
                 let mut state = State::new();
+                state.set_event_channel(channel.0, channel.1);
 
                 let root = Entity::root();
                 //state.tree.add(Entity::root(), None);
@@ -63,7 +64,7 @@ impl TuixWindow {
     /// Open a new window as if it had a parent window.
     ///
     /// * `app` - The Tuix application builder.
-    pub fn open_as_if_parented<F>(win_desc: WindowDescription, mut app: F, on_idle: Option<Box<dyn Fn(&mut State) + Send>>) -> RawWindowHandle
+    pub fn open_as_if_parented<F>(win_desc: WindowDescription, mut app: F, on_idle: Option<Box<dyn Fn(&mut State) + Send>>, channel: (EventProxy, EventReceiver)) -> RawWindowHandle
     where
         F: FnOnce(&mut State, Entity),
         F: 'static + Send,
@@ -81,6 +82,7 @@ impl TuixWindow {
             window_settings,
             move |window: &mut baseview::Window<'_>| -> TuixWindow {
                 let mut state = State::new();
+                state.set_event_channel(channel.0, channel.1);
 
                 let root = Entity::root();
                 //state.tree.add(Entity::root(), None);
@@ -95,7 +97,7 @@ impl TuixWindow {
     /// Open a new window that blocks the current thread until the window is destroyed.
     ///
     /// * `app` - The Tuix application builder.
-    pub fn open_blocking<F>(win_desc: WindowDescription, mut app: F, on_idle: Option<Box<dyn Fn(&mut State) + Send>>)
+    pub fn open_blocking<F>(win_desc: WindowDescription, mut app: F, on_idle: Option<Box<dyn Fn(&mut State) + Send>>, channel: (EventProxy, EventReceiver))
     where
         F: FnOnce(&mut State, Entity),
         F: 'static + Send,
@@ -113,6 +115,7 @@ impl TuixWindow {
             window_settings,
             move |window: &mut baseview::Window<'_>| -> TuixWindow {
                 let mut state = State::new();
+                state.set_event_channel(channel.0, channel.1);
 
                 let root = Entity::root();
                 //state.tree.add(Entity::root(), None);
@@ -130,13 +133,17 @@ impl WindowHandler for TuixWindow {
     fn on_frame(&mut self, _window: &mut Window) {
         self.application.on_frame_update();
 
-        self.context.make_current();
+        // The host calls this at its own rate, so the window is only drawn when something has changed
+        if self.application.should_redraw() {
+            self.context.make_current();
 
-        self.application.render();
-        self.context.swap_buffers();
-        
+            self.application.render();
+            self.context.swap_buffers();
 
-        self.context.make_not_current();
+            self.context.make_not_current();
+        }
+
+        self.application.end_frame();
     }
 
     fn on_event(&mut self, _window: &mut Window<'_>, event: Event) -> EventStatus {
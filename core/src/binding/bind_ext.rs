@@ -1,4 +1,4 @@
-use crate::{AsEntity, BindEvent, PropSet, State};
+use crate::{AsEntity, BindEvent, Event, Propagation, State};
 
 
 
@@ -8,7 +8,9 @@ pub trait BindExt
 where Self: Sized + AsEntity,
 {
     fn update(self, state: &mut State) -> Self {
-        self.entity().emit(state, BindEvent::Update);
+        // Updating an entity several times before the events are flushed only needs one update
+        let entity = self.entity();
+        state.insert_event(Event::new(BindEvent::Update).target(entity).origin(entity).propagate(Propagation::Up).unique());
 
         self
     } 
@@ -1,7 +1,9 @@
 use crate::Entity;
 
 use std::any::{Any, TypeId};
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 
 /// Determines how the event propagates through the tree
 #[derive(Debug, Clone, PartialEq)]
@@ -108,14 +110,23 @@ pub struct Event {
     pub consumable: bool,
     // Determines whether the event should continue to be propagated
     pub(crate) consumed: bool,
-    // Whether the event is unique (only the latest copy can exist in a queue at a time)
+    // Whether the event is unique (only the latest copy in a queue is dispatched)
     pub unique: bool,
     // Specifies an order index which is used to sort the event queue
     pub order: i32,
     // The event message
     pub message: Box<dyn Message>,
+    // The type of the message and a hash of its enum variant, which identify copies of a unique event
+    kind: (TypeId, u64),
+    // The name of the type of the message, for tracing
+    message_name: &'static str,
+    // Counts the copies of a unique event inserted into a queue, so that superseded copies can be dropped
+    pub(crate) copy: usize,
 }
 
+/// The type and variant of the message, and the target and origin, which identify copies of a unique event
+pub(crate) type UniqueKey = (TypeId, u64, Entity, Entity);
+
 // // Allows events to be compared for equality
 // impl PartialEq for Event {
 //     fn eq(&self, other: &Event) -> bool {
@@ -131,6 +142,9 @@ impl Event {
     where
         M: Message,
     {
+        let mut hasher = DefaultHasher::new();
+        std::mem::discriminant(&message).hash(&mut hasher);
+
         Event {
             origin: Entity::null(),
             target: Entity::null(),
@@ -139,7 +153,10 @@ impl Event {
             consumed: false,
             unique: false,
             order: 0,
+            kind: (TypeId::of::<M>(), hasher.finish()),
+            message_name: std::any::type_name::<M>(),
             message: Box::new(message),
+            copy: 0,
         }
     }
 
@@ -157,11 +174,31 @@ impl Event {
 
     /// Specifies that the event is unique
     /// (only one of this event type should exist in the event queue at once)
+    ///
+    /// Only the latest queued copy of a unique event, with a message of the same type and enum variant and the same
+    /// target and origin, is dispatched, e.g. only the latest `WindowEvent::Redraw` of an entity.
     pub fn unique(mut self) -> Self {
         self.unique = true;
         self
     }
 
+    /// Sets the order of the event. Events are dispatched in increasing order, and events with the same order
+    /// in the order they were inserted. Defaults to 0.
+    pub fn order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Returns the name of the type of the message, e.g. `tuix_core::window::window_event::WindowEvent`
+    pub fn message_name(&self) -> &'static str {
+        self.message_name
+    }
+
+    /// Returns the key shared by unique copies of the event, see [unique](Event::unique)
+    pub(crate) fn unique_key(&self) -> UniqueKey {
+        (self.kind.0, self.kind.1, self.target, self.origin)
+    }
+
     /// Sets the propagation of the event
     pub fn propagate(mut self, propagation: Propagation) -> Self {
         self.propagation = propagation;
@@ -1,11 +1,30 @@
 
 
-use crate::{BindEvent, Display, Entity, Event, FontOrId, Propagation, State, Tree, TreeExt, Visibility, WindowEvent, entity};
-
-
-use femtovg::{
-    renderer::OpenGl, Canvas,
-};
+use crate::{BindEvent, BoundingBox, Color, Display, Entity, Event, EventTracer, FontOrId, ImageOrId, ProfiledSystem, PropSet, Propagation, RoundedClip, State, Tree, TreeExt, Visibility, WindowEvent, entity};
+use crate::{intersect_bounds, merge_rects, visual_bounds};
+use crate::systems::invalidate_layers;
+
+use crate::{BlendFactor, BlendMode, Canvas, CompositeOperation, ImageFlags, ImageId, Paint, Path, PixelFormat, RenderTarget, Solidity};
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+// Above this many damage rectangles, they are merged into one
+const MAX_DAMAGE_RECTS: usize = 8;
+
+// The most batches of events caused by other events which are dispatched in one flush
+const MAX_EVENT_CASCADE: usize = 64;
+
+// A group of entities which is being drawn into an offscreen image, see [Group](crate::Group)
+struct ActiveGroup {
+    entity: Entity,
+    // The image the group is drawn into, or None if it couldn't be created and the group is drawn directly
+    image: Option<ImageId>,
+    // The render target which the group is composited into
+    return_target: RenderTarget,
+    // Whether the group began inside the clip layer, in which case it is composited before the clip layer
+    clipped: bool,
+}
 
 
 /// Dispatches events to widgets.
@@ -23,7 +42,32 @@ pub struct EventManager {
 
     prev_width: f32,
     prev_height: f32,
-    prev_dpi_factor: f64,
+    prev_scale_factor: f32,
+    // The ratio of physical to logical pixels of the frame being drawn
+    scale_factor: f32,
+
+    // Offscreen image used to draw entities which are clipped by a rounded clip widget
+    clip_layer: Option<ImageId>,
+    // The render target which was interrupted by the clip layer
+    clip_return_target: RenderTarget,
+
+    // Offscreen images used to draw groups, indexed by how deeply the group is nested in other groups
+    group_layers: Vec<Option<ImageId>>,
+
+    // Offscreen image which the window is drawn into, kept between frames so that only damaged regions are redrawn
+    frame: Option<ImageId>,
+    frame_target: RenderTarget,
+    // The region of the window and the z-index each entity was last drawn with
+    drawn: HashMap<Entity, (BoundingBox, i32)>,
+    // Counts the frames with flashed regions, to cycle the flash color
+    flash_count: usize,
+    // The number of entities drawn in the frame being drawn
+    drawn_count: usize,
+
+    // Logs the dispatched events, if tracing is enabled
+    tracer: Option<EventTracer>,
+    // The number of flushes which left a cascade of events for the next frame
+    unsettled_cascades: u64,
 }
 
 impl EventManager {
@@ -35,11 +79,71 @@ impl EventManager {
 
             prev_width: 0.0,
             prev_height: 0.0,
-            prev_dpi_factor: 1.0,
+            prev_scale_factor: 1.0,
+            scale_factor: 1.0,
+
+            clip_layer: None,
+            clip_return_target: RenderTarget::Screen,
+
+            group_layers: Vec::new(),
+
+            frame: None,
+            frame_target: RenderTarget::Screen,
+            drawn: HashMap::new(),
+            flash_count: 0,
+            drawn_count: 0,
+
+            tracer: None,
+            unsettled_cascades: 0,
         }
     }
 
+    /// Starts logging every dispatched event with the tracer, replacing any previous tracer
+    pub fn set_tracer(&mut self, tracer: EventTracer) {
+        self.tracer = Some(tracer);
+    }
+
+    /// Stops tracing, returning the tracer
+    pub fn take_tracer(&mut self) -> Option<EventTracer> {
+        self.tracer.take()
+    }
+
+    /// Returns the number of times a cascade of events didn't settle within a flush and was continued in the next
+    /// frame. A count which keeps growing means that widgets are sending each other events endlessly.
+    pub fn unsettled_cascades(&self) -> u64 {
+        self.unsettled_cascades
+    }
+
+    /// Dispatches the events in the event queue of state to widgets, returning true if the window needs to be redrawn.
+    ///
+    /// The events sent while handling the queued events are dispatched too, in batches, until the queue is empty.
+    /// A cascade of events which hasn't settled after a number of batches is left in the queue for the next frame,
+    /// so that events which keep sending each other don't stop the application from responding.
     pub fn flush_events(&mut self, state: &mut State) -> bool {
+        let profile_start = state.profiler.start();
+        let mut needs_redraw = false;
+        let mut count = 0;
+
+        for _ in 0..MAX_EVENT_CASCADE {
+            needs_redraw |= self.dispatch_events(state);
+            count += self.event_queue.len();
+
+            if state.event_queue.is_empty() {
+                break;
+            }
+        }
+
+        if !state.event_queue.is_empty() {
+            self.unsettled_cascades += 1;
+            state.scheduler.request_frame();
+        }
+
+        state.profiler.record(ProfiledSystem::Events, profile_start, count);
+
+        needs_redraw
+    }
+
+    fn dispatch_events(&mut self, state: &mut State) -> bool {
         let mut needs_redraw = false;
         let mut needs_restyle = false;
         let mut needs_relayout = false;
@@ -60,6 +164,10 @@ impl EventManager {
             state.data.remove(*entity);
             state.style.remove(*entity);
             state.event_handlers.remove(entity);
+            state.shortcuts.unbind_entity(*entity);
+            state.drag.remove_entity(*entity);
+            state.timers.remove_entity(*entity);
+            state.tasks.remove_entity(*entity);
             state.entity_manager.destroy(*entity);
             state.tree.changed = true;
         }
@@ -67,6 +175,7 @@ impl EventManager {
         if state.tree.changed {
             self.tree = state.tree.clone();
             state.tree.changed = false;
+            state.damage.mark_all();
         }
 
         // Clear the event queue in the event manager
@@ -75,15 +184,19 @@ impl EventManager {
         state.removed_entities.clear();
 
         // Move events from state to event manager
-        self.event_queue.extend(state.event_queue.drain(0..));
+        self.event_queue.extend(state.take_events());
 
-        // Sort the events by order
-        //self.event_queue.sort_by_cached_key(|event| event.order);
+        // Sort the events by order, keeping the order of insertion for events with the same order
+        self.event_queue.sort_by_key(|event| event.order);
 
         // Loop over the events in the event queue
         'events: for event in self.event_queue.iter_mut() {
             //println!("Event: {:?}", event);
 
+            if let Some(tracer) = self.tracer.as_mut() {
+                tracer.begin(event);
+            }
+
             // Send events to any listeners
             let listeners = state.listeners.iter().map(|(entity, _)| *entity).collect::<Vec<Entity>>();
             for entity in listeners {
@@ -91,6 +204,10 @@ impl EventManager {
                     if let Some(mut event_handler) = state.event_handlers.remove(&entity) {
                         (listener)(event_handler.as_mut(), state, entity, event);
 
+                        if let Some(tracer) = self.tracer.as_mut() {
+                            tracer.handled(entity, event.consumed);
+                        }
+
                         state.event_handlers.insert(entity, event_handler);
                     }
                     
@@ -103,30 +220,49 @@ impl EventManager {
                 }
             }
 
-            // Skip events with no target unless they are set to propagate to all entities
-            if event.target == Entity::null() && event.propagation != Propagation::All {
-                continue 'events;
-            }
-
             if let Some(window_event) = event.message.downcast::<WindowEvent>() {
-                match window_event {
+                let skipped = match window_event {
                     WindowEvent::Redraw => {
+                        // Only the region of the entity to be redrawn needs repainting
+                        let damaged = if event.target != Entity::null() { event.target } else { event.origin };
+                        state.damage.mark(damaged);
                         needs_redraw = true;
-                        continue 'events;
+                        Some("redraw")
                     }
 
                     WindowEvent::Relayout => {
                         needs_relayout = true;
-                        continue 'events;
+                        Some("relayout")
                     }
 
                     WindowEvent::Restyle => {
                         needs_restyle = true;
-                        continue 'events;
+                        Some("restyle")
                     }
 
-                    _ => {}
+                    _ => None,
+                };
+
+                if let Some(reason) = skipped {
+                    if let Some(tracer) = self.tracer.as_mut() {
+                        tracer.skipped(reason);
+                    }
+
+                    continue 'events;
+                }
+            }
+
+            // Skip events with no target unless they are set to propagate to all entities
+            if event.target == Entity::null() && event.propagation != Propagation::All {
+                if let Some(tracer) = self.tracer.as_mut() {
+                    tracer.skipped("null target");
                 }
+
+                continue 'events;
+            }
+
+            if let Some(tracer) = self.tracer.as_mut() {
+                tracer.path(propagation_path(&self.tree, event));
             }
 
             // if let Some(redraw_event) = event.message.downcast::<RedrawEvent>() {
@@ -141,6 +277,9 @@ impl EventManager {
                 for entity in self.tree.into_iter() {
                     if let Some(mut event_handler) = state.event_handlers.remove(&entity) {
                         event_handler.on_event_(state, entity, event);
+                        if let Some(tracer) = self.tracer.as_mut() {
+                            tracer.handled(entity, event.consumed);
+                        }
 
                         state.event_handlers.insert(entity, event_handler);
 
@@ -175,6 +314,9 @@ impl EventManager {
                     // Send event to all ancestors before the target
                     if let Some(mut event_handler) = state.event_handlers.remove(&entity) {
                         event_handler.on_event_(state, *entity, event);
+                        if let Some(tracer) = self.tracer.as_mut() {
+                            tracer.handled(*entity, event.consumed);
+                        }
 
                         state.event_handlers.insert(*entity, event_handler);
 
@@ -191,6 +333,9 @@ impl EventManager {
                 // Send event to target
                 if let Some(mut event_handler) = state.event_handlers.remove(&event.target) {
                     event_handler.on_event_(state, event.target, event);
+                    if let Some(tracer) = self.tracer.as_mut() {
+                        tracer.handled(event.target, event.consumed);
+                    }
 
                     state.event_handlers.insert(event.target, event_handler);
                     // if let Some(test) = self.callbacks.get_mut(&event.target) {
@@ -215,6 +360,9 @@ impl EventManager {
                     // Send event to all entities before the target
                     if let Some(mut event_handler) = state.event_handlers.remove(&entity) {
                         event_handler.on_event_(state, entity, event);
+                        if let Some(tracer) = self.tracer.as_mut() {
+                            tracer.handled(entity, event.consumed);
+                        }
 
                         state.event_handlers.insert(entity, event_handler);
                         // Skip to the next event if the current event is consumed
@@ -237,6 +385,9 @@ impl EventManager {
                     // Send event to all entities after the target on the same branch
                     if let Some(mut event_handler) = state.event_handlers.remove(&entity) {
                         event_handler.on_event_(state, entity, event);
+                        if let Some(tracer) = self.tracer.as_mut() {
+                            tracer.handled(entity, event.consumed);
+                        }
 
                         state.event_handlers.insert(entity, event_handler);
                         // Skip to the next event if the current event is consumed
@@ -248,6 +399,10 @@ impl EventManager {
             }
         }
 
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.end_batch();
+        }
+
         if needs_restyle {
             if let Some(mut event_handler) = state.event_handlers.remove(&Entity::root()) {
                 event_handler.on_event_(state, Entity::root(), &mut Event::new(WindowEvent::Restyle));
@@ -275,7 +430,7 @@ impl EventManager {
         return needs_redraw;
     }
 
-    pub fn load_resources(&mut self, state: &mut State, canvas: &mut Canvas<OpenGl>) {
+    pub fn load_resources(&mut self, state: &mut State, canvas: &mut Canvas) {
         for (name, font) in state.resource_manager.fonts.iter_mut() {
             
             match font {
@@ -291,12 +446,69 @@ impl EventManager {
                 _=> {}
             }
         }
+
+        for image in state.resource_manager.unused_images.drain(..) {
+            canvas.delete_image(image);
+        }
+
+        let mut loaded = Vec::new();
+        let mut failed = Vec::new();
+        for (name, image) in state.resource_manager.images.iter_mut() {
+            if let ImageOrId::Image(data) = image {
+                let result = canvas.load_image_mem(data, ImageFlags::empty()).and_then(|id| match canvas.image_size(id) {
+                    Ok(size) => Ok((id, size)),
+                    Err(error) => {
+                        canvas.delete_image(id);
+                        Err(error)
+                    }
+                });
+
+                match result {
+                    Ok((id, size)) => {
+                        *image = ImageOrId::Id(id, size);
+                        loaded.push(name.clone());
+                    }
+
+                    // An image which can't be decoded is dropped, so widgets draw nothing for it
+                    Err(error) => {
+                        failed.push((name.clone(), error));
+                    }
+                }
+            }
+        }
+
+        for (name, error) in failed {
+            state.resource_manager.images.remove(&name);
+            state.insert_event(
+                Event::new(WindowEvent::ImageLoadFailed(name, error))
+                    .target(Entity::root())
+                    .propagate(Propagation::All),
+            );
+        }
+
+        // Widgets which size themselves to an image need the image dimensions
+        for name in loaded {
+            state.insert_event(
+                Event::new(WindowEvent::ImageLoaded(name))
+                    .target(Entity::root())
+                    .propagate(Propagation::All),
+            );
+        }
     }
 
-    pub fn draw(&mut self, state: &mut State, canvas: &mut Canvas<OpenGl>) {
-        //let dpi_factor = window.handle.window().scale_factor();
-        //let size = window.handle.window().inner_size();
+    /// Draws the damaged regions of the window, recording the time taken in the frame statistics of the [FrameScheduler](crate::FrameScheduler).
+    pub fn draw(&mut self, state: &mut State, canvas: &mut Canvas) {
+        let start = Instant::now();
+        let profile_start = state.profiler.start();
+        self.drawn_count = 0;
+
+        self.draw_frame(state, canvas);
 
+        state.scheduler.record_draw(start.elapsed());
+        state.profiler.record(ProfiledSystem::Draw, profile_start, self.drawn_count);
+    }
+
+    fn draw_frame(&mut self, state: &mut State, canvas: &mut Canvas) {
         self.load_resources(state, canvas);
 
         // for (resource, image_or_id) in state.resource_manager.image_ids.iter_mut() {
@@ -324,47 +536,218 @@ impl EventManager {
         //         }
         //     });
 
+        // The size of the window in logical pixels, which widgets draw in
         let width = state.data.get_width(Entity::root());
         let height = state.data.get_height(Entity::root());
-        // TODO: Move this to the window widget
-        let dpi_factor = 1.0;
+
+        // Drawing is scaled to the physical pixels of the window
+        self.scale_factor = state.scale_factor();
+        let (physical_width, physical_height) = self.physical_size(width, height);
 
         // Set the canvas size
         if self.prev_width != width
             || self.prev_height != height
-            || self.prev_dpi_factor != dpi_factor
+            || self.prev_scale_factor != self.scale_factor
         {
-            canvas.set_size(width as u32, height as u32, dpi_factor as f32);
+            canvas.set_size(physical_width, physical_height, self.scale_factor);
+            self.prev_width = width;
+            self.prev_height = height;
+            self.prev_scale_factor = self.scale_factor;
         }
 
         // Get the desired window background color
-        let background_color: femtovg::Color = state
+        let background_color = state
             .style
             .background_color
             .get(Entity::root())
             .cloned()
-            .unwrap_or_default()
-            .into();
-
-        // Clear the canvas
-        canvas.clear_rect(0, 0, width as u32, height as u32, background_color);
-
-        //canvas.save();
-        // Reset any canvas transforms
-        canvas.reset();
+            .unwrap_or_default();
 
         // Sort the tree by z order
         let mut draw_tree: Vec<Entity> = self.tree.into_iter().collect();
         draw_tree.sort_by_cached_key(|entity| state.data.get_z_index(*entity));
 
+        // Delete the images of layers which no longer have any entities
+        state.layers.retain(|_, layer| {
+            if layer.count == 0 {
+                if let Some(image) = layer.image {
+                    canvas.delete_image(image);
+                }
+            }
+
+            layer.count != 0
+        });
+
+        // The window is drawn into an image which is kept between frames, so that only damaged regions need redrawing
+        let (frame, frame_created) = match Self::window_image(canvas, self.frame, physical_width, physical_height) {
+            Some(frame) => frame,
+            None => {
+                self.frame = None;
+                state.damage.clear();
+                canvas.flush();
+                return;
+            }
+        };
+
+        self.frame = Some(frame);
+
+        let window_bounds = BoundingBox { x: 0.0, y: 0.0, w: width, h: height };
+        let full = frame_created || state.damage.is_full();
+
+        // Work out which regions of the window need to be redrawn
+        let regions = if full {
+            // Retained layers are composited from their images unless one of their entities changed
+            let damaged: Vec<Entity> = state.damage.entities().cloned().collect();
+            for entity in damaged {
+                if let Some((_, z_index)) = self.drawn.get(&entity) {
+                    if let Some(layer) = state.layers.get_mut(z_index) {
+                        layer.dirty = true;
+                    }
+                }
+
+                if self.tree.get_parent(entity).is_some() {
+                    invalidate_layers(state, entity);
+                }
+            }
+
+            self.drawn.clear();
+            vec![window_bounds]
+        } else {
+            let mut rects = Vec::new();
+            let damaged: Vec<Entity> = state.damage.entities().cloned().collect();
+            for entity in damaged {
+                // The region the entity was drawn to before it changed
+                if let Some((previous, z_index)) = self.drawn.remove(&entity) {
+                    rects.push(previous);
+                    if let Some(layer) = state.layers.get_mut(&z_index) {
+                        layer.dirty = true;
+                    }
+                }
+
+                // The region the entity will be drawn to now, unless it has been removed
+                if self.tree.get_parent(entity).is_some() {
+                    if let Some(current) = visual_bounds(state, entity) {
+                        rects.push(current);
+                        self.drawn.insert(entity, (current, state.data.get_z_index(entity)));
+                    }
+
+                    invalidate_layers(state, entity);
+                }
+            }
+
+            let rects = rects.into_iter().filter_map(|rect| intersect_bounds(rect, window_bounds)).collect();
+            merge_rects(rects, MAX_DAMAGE_RECTS)
+                .into_iter()
+                .map(|region| self.snap_to_pixels(region))
+                .collect()
+        };
+
+        state.damage.clear();
+
+        self.frame_target = RenderTarget::Image(frame);
+        state.render_target = self.frame_target;
+        canvas.set_render_target(state.render_target);
+
+        for region in regions.iter() {
+            // Clear the region
+            let (x, y, w, h) = self.physical_rect(*region);
+            canvas.clear_rect(x, y, w, h, background_color);
+
+            //canvas.save();
+            // Reset any canvas transforms
+            self.reset_canvas(canvas);
+
+            self.draw_region(state, canvas, &draw_tree, *region, full);
+        }
+
+        // Copy the frame to the window
+        state.render_target = RenderTarget::Screen;
+        canvas.set_render_target(state.render_target);
+        canvas.clear_rect(0, 0, physical_width, physical_height, background_color);
+        self.reset_canvas(canvas);
+
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, width, height);
+        canvas.fill_path(&mut path, Paint::image(frame, 0.0, 0.0, width, height, 0.0, 1.0));
+
+        // Flash the repainted regions, changing color each frame
+        if state.damage.is_debug() {
+            self.flash_count = self.flash_count.wrapping_add(1);
+            let flash_color = Color::hsla((self.flash_count % 6) as f32 / 6.0, 1.0, 0.5, 0.3);
+
+            for region in regions.iter() {
+                let mut path = Path::new();
+                path.rect(region.x, region.y, region.w, region.h);
+                canvas.fill_path(&mut path, Paint::color(flash_color));
+            }
+        }
+
+        //canvas.restore();
+
+        // Send the canvas to the GPU to draw
+        canvas.flush();
+    }
+
+    // Draws the entities which overlap a region of the window, or every entity if the whole window is being drawn
+    fn draw_region(&mut self, state: &mut State, canvas: &mut Canvas, draw_tree: &[Entity], region: BoundingBox, full: bool) {
+        let width = state.data.get_width(Entity::root());
+        let height = state.data.get_height(Entity::root());
+        let window = BoundingBox { x: 0.0, y: 0.0, w: width, h: height };
+
+        // Entities clipped by a clip widget with rounded corners are drawn into a layer
+        // which is then drawn to the screen through the clip shape
+        let mut active_clip: Option<RoundedClip> = None;
+
+        // The groups being drawn, from the outermost
+        let mut active_groups: Vec<ActiveGroup> = Vec::new();
+
+        // The z-index of the entities being drawn, and the image of its layer if the layer is retained
+        let mut current_z_index: Option<i32> = None;
+        let mut active_layer: Option<ImageId> = None;
+        // Whether the entities of the current layer are being drawn, or only the cached image is needed
+        let mut paint_layer = true;
+
         // Call the on_draw() method for each widget
-        for entity in draw_tree.into_iter() {
+        for entity in draw_tree.iter().cloned() {
 
             // Skip window
             if entity == Entity::root() {
                 continue;
             }
 
+            // Entities are sorted by z-index, so each layer is a run of entities with the same z-index
+            let z_index = state.data.get_z_index(entity);
+            if current_z_index != Some(z_index) {
+                let group_region = if active_layer.is_some() { window } else { region };
+                let unclipped = active_groups.iter().position(|group| group.clipped).unwrap_or(active_groups.len());
+                self.end_groups(state, canvas, &mut active_groups, unclipped, group_region, window);
+
+                if let Some(clip) = active_clip.take() {
+                    self.end_clip_layer(state, canvas, &clip, width, height);
+                }
+
+                self.end_groups(state, canvas, &mut active_groups, 0, group_region, window);
+
+                if let Some(image) = active_layer.take() {
+                    self.end_layer(state, canvas, image, region, width, height);
+                }
+
+                current_z_index = Some(z_index);
+                paint_layer = true;
+
+                if state.layers.get(&z_index).map_or(false, |layer| layer.retained) {
+                    if let Some((image, repaint)) = self.begin_layer(state, canvas, z_index, width, height) {
+                        active_layer = Some(image);
+                        paint_layer = repaint;
+                    }
+                }
+            }
+
+            // The layer is drawn from its cached image
+            if !paint_layer {
+                continue;
+            }
+
             // Skip invisible widgets
             if state.data.get_visibility(entity) == Visibility::Invisible {
                 continue;
@@ -386,6 +769,63 @@ impl EventManager {
             //     continue;
             // }
 
+            // A retained layer which is being repainted is drawn in full, otherwise only the region is drawn
+            let clip_to_region = active_layer.is_none();
+
+            let visual = visual_bounds(state, entity);
+            if full {
+                if let Some(visual) = visual {
+                    self.drawn.insert(entity, (visual, z_index));
+                }
+            } else if clip_to_region && visual.and_then(|visual| intersect_bounds(visual, region)).is_none() {
+                continue;
+            }
+
+            // The groups the entity is drawn in, from the outermost
+            let mut groups: Vec<Entity> = entity
+                .parent_iter(&self.tree)
+                .filter(|ancestor| state.data.get_group(*ancestor).is_some())
+                .collect();
+            groups.reverse();
+
+            let shared = active_groups
+                .iter()
+                .zip(groups.iter())
+                .take_while(|(active, group)| active.entity == **group)
+                .count();
+
+            let group_region = if clip_to_region { region } else { window };
+
+            // The clip layer has to be composited before any group which was active when it began,
+            // and the groups which began inside it have to be composited before the clip layer
+            let rounded_clip = state.data.get_rounded_clip(entity);
+            let ends_group_outside_clip = active_groups[shared..].iter().any(|group| !group.clipped);
+            if active_clip.is_some() && (rounded_clip != active_clip || ends_group_outside_clip) {
+                let unclipped = active_groups.iter().position(|group| group.clipped).unwrap_or(active_groups.len());
+                self.end_groups(state, canvas, &mut active_groups, unclipped, group_region, window);
+
+                if let Some(clip) = active_clip.take() {
+                    self.end_clip_layer(state, canvas, &clip, width, height);
+                }
+            }
+
+            // Composite the groups which the entity isn't in, and begin the groups which it is in
+            let shared = shared.min(active_groups.len());
+            self.end_groups(state, canvas, &mut active_groups, shared, group_region, window);
+
+            for group in groups[shared..].iter() {
+                let active_group = self.begin_group(state, canvas, *group, active_groups.len(), active_clip.is_some(), window);
+                active_groups.push(active_group);
+            }
+
+            if rounded_clip != active_clip {
+                if let Some(clip) = rounded_clip {
+                    if self.begin_clip_layer(state, canvas, width, height) {
+                        active_clip = Some(clip);
+                    }
+                }
+            }
+
             let clip_region = state.data.get_clip_region(entity);
             canvas.scissor(
                 clip_region.x,
@@ -393,6 +833,10 @@ impl EventManager {
                 clip_region.w,
                 clip_region.h,
             );
+
+            if clip_to_region {
+                canvas.intersect_scissor(region.x, region.y, region.w, region.h);
+            }
     
             // Apply transformations
             let transform = state.data.get_transform(entity);
@@ -405,6 +849,7 @@ impl EventManager {
             if let Some(mut event_handler) = state.event_handlers.remove(&entity) {
                 //let start = std::time::Instant::now();
                 event_handler.on_draw_(state, entity, canvas);
+                self.drawn_count += 1;
                 //println!("{:.2?} seconds for whatever you did.", start.elapsed());
                 state.event_handlers.insert(entity, event_handler);
             }
@@ -412,9 +857,443 @@ impl EventManager {
             canvas.restore();
         }
 
-        //canvas.restore();
+        let group_region = if active_layer.is_some() { window } else { region };
+        let unclipped = active_groups.iter().position(|group| group.clipped).unwrap_or(active_groups.len());
+        self.end_groups(state, canvas, &mut active_groups, unclipped, group_region, window);
 
-        // Send the canvas to the GPU to draw
-        canvas.flush();
+        if let Some(clip) = active_clip.take() {
+            self.end_clip_layer(state, canvas, &clip, width, height);
+        }
+
+        self.end_groups(state, canvas, &mut active_groups, 0, group_region, window);
+
+        if let Some(image) = active_layer.take() {
+            self.end_layer(state, canvas, image, region, width, height);
+        }
+    }
+
+    // Redirects drawing to an image for a group, nested inside `depth` other groups
+    fn begin_group(&mut self, state: &mut State, canvas: &mut Canvas, entity: Entity, depth: usize, clipped: bool, window: BoundingBox) -> ActiveGroup {
+        if self.group_layers.len() <= depth {
+            self.group_layers.resize(depth + 1, None);
+        }
+
+        let (physical_width, physical_height) = self.physical_size(window.w, window.h);
+        let image = Self::window_image(canvas, self.group_layers[depth], physical_width, physical_height).map(|(image, _)| image);
+        self.group_layers[depth] = image;
+
+        let return_target = state.render_target;
+
+        if let Some(image) = image {
+            state.render_target = RenderTarget::Image(image);
+            canvas.set_render_target(state.render_target);
+            canvas.clear_rect(0, 0, physical_width, physical_height, Color::rgba(0, 0, 0, 0));
+        }
+
+        ActiveGroup {
+            entity,
+            image,
+            return_target,
+            clipped,
+        }
+    }
+
+    // Composites the most recent groups until there are `len` groups left
+    fn end_groups(&mut self, state: &mut State, canvas: &mut Canvas, groups: &mut Vec<ActiveGroup>, len: usize, region: BoundingBox, window: BoundingBox) {
+        while groups.len() > len {
+            if let Some(group) = groups.pop() {
+                self.end_group(state, canvas, group, region, window);
+            }
+        }
+    }
+
+    // Draws the region of the image of a group into the target it interrupted, with the opacity and blend mode of the group
+    fn end_group(&mut self, state: &mut State, canvas: &mut Canvas, group: ActiveGroup, region: BoundingBox, window: BoundingBox) {
+        state.render_target = group.return_target;
+        canvas.set_render_target(state.render_target);
+
+        if let (Some(image), Some(composite)) = (group.image, state.data.get_group(group.entity)) {
+            canvas.save();
+            self.reset_canvas(canvas);
+            canvas.scissor(region.x, region.y, region.w, region.h);
+            set_blend_mode(canvas, composite.blend_mode);
+            let mut path = Path::new();
+            path.rect(window.x, window.y, window.w, window.h);
+            canvas.fill_path(&mut path, Paint::image(image, window.x, window.y, window.w, window.h, 0.0, composite.opacity));
+            canvas.restore();
+        }
+    }
+
+    // Resets the transform and scissor of the canvas, keeping the scale from logical to physical pixels
+    fn reset_canvas(&self, canvas: &mut Canvas) {
+        canvas.reset();
+        canvas.scale(self.scale_factor, self.scale_factor);
+    }
+
+    // Returns the size of the window in physical pixels
+    fn physical_size(&self, width: f32, height: f32) -> (u32, u32) {
+        ((width * self.scale_factor).round() as u32, (height * self.scale_factor).round() as u32)
+    }
+
+    // Returns the physical pixels covered by a rectangle in logical pixels
+    fn physical_rect(&self, rect: BoundingBox) -> (u32, u32, u32, u32) {
+        let x0 = (rect.x * self.scale_factor).floor().max(0.0);
+        let y0 = (rect.y * self.scale_factor).floor().max(0.0);
+        let x1 = ((rect.x + rect.w) * self.scale_factor).ceil().max(x0);
+        let y1 = ((rect.y + rect.h) * self.scale_factor).ceil().max(y0);
+
+        (x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32)
+    }
+
+    // Grows a rectangle in logical pixels to the edges of the physical pixels it covers,
+    // so that a region which is cleared is also fully scissored when it is redrawn
+    fn snap_to_pixels(&self, rect: BoundingBox) -> BoundingBox {
+        let (x, y, w, h) = self.physical_rect(rect);
+
+        BoundingBox {
+            x: x as f32 / self.scale_factor,
+            y: y as f32 / self.scale_factor,
+            w: w as f32 / self.scale_factor,
+            h: h as f32 / self.scale_factor,
+        }
+    }
+
+    // Creates an image the size of the window in physical pixels for drawing offscreen, or reuses the previous image
+    // if it is the right size. Returns the image and whether it was created, or None if it could not be created.
+    fn window_image(canvas: &mut Canvas, previous: Option<ImageId>, width: u32, height: u32) -> Option<(ImageId, bool)> {
+        let size = (width as usize, height as usize);
+
+        if size.0 == 0 || size.1 == 0 {
+            return None;
+        }
+
+        match previous {
+            Some(image) if canvas.image_size(image).ok() == Some(size) => Some((image, false)),
+
+            previous => {
+                if let Some(image) = previous {
+                    canvas.delete_image(image);
+                }
+
+                canvas
+                    .create_image_empty(
+                        size.0,
+                        size.1,
+                        PixelFormat::Rgba8,
+                        ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED,
+                    )
+                    .ok()
+                    .map(|image| (image, true))
+            }
+        }
+    }
+
+    // Prepares the image of a retained layer. If the layer is dirty, or its image had to be created,
+    // drawing is redirected to the image. Returns the image and whether the layer needs repainting.
+    fn begin_layer(&mut self, state: &mut State, canvas: &mut Canvas, z_index: i32, width: f32, height: f32) -> Option<(ImageId, bool)> {
+        let layer = state.layers.get_mut(&z_index)?;
+
+        let (physical_width, physical_height) = self.physical_size(width, height);
+        let (image, created) = match Self::window_image(canvas, layer.image, physical_width, physical_height) {
+            Some(image) => image,
+            None => {
+                layer.image = None;
+                return None;
+            }
+        };
+
+        layer.image = Some(image);
+
+        let repaint = layer.dirty || created;
+        layer.dirty = false;
+
+        if repaint {
+            state.render_target = RenderTarget::Image(image);
+            canvas.set_render_target(state.render_target);
+            canvas.clear_rect(0, 0, physical_width, physical_height, Color::rgba(0, 0, 0, 0));
+        }
+
+        Some((image, repaint))
+    }
+
+    // Draws the region of the image of a retained layer to the frame
+    fn end_layer(&mut self, state: &mut State, canvas: &mut Canvas, image: ImageId, region: BoundingBox, width: f32, height: f32) {
+        state.render_target = self.frame_target;
+        canvas.set_render_target(state.render_target);
+
+        canvas.save();
+        self.reset_canvas(canvas);
+        canvas.scissor(region.x, region.y, region.w, region.h);
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, width, height);
+        canvas.fill_path(&mut path, Paint::image(image, 0.0, 0.0, width, height, 0.0, 1.0));
+        canvas.restore();
+    }
+
+    // Redirects drawing to the clip layer, creating the layer image if it doesn't match the size of the window.
+    // Returns false if the layer could not be created.
+    fn begin_clip_layer(&mut self, state: &mut State, canvas: &mut Canvas, width: f32, height: f32) -> bool {
+        let (physical_width, physical_height) = self.physical_size(width, height);
+        let image = match Self::window_image(canvas, self.clip_layer, physical_width, physical_height) {
+            Some((image, _)) => image,
+            None => {
+                self.clip_layer = None;
+                return false;
+            }
+        };
+
+        self.clip_layer = Some(image);
+
+        // The clip layer is drawn back into the target it interrupted, which may be a retained layer
+        self.clip_return_target = state.render_target;
+
+        state.render_target = RenderTarget::Image(image);
+        canvas.set_render_target(state.render_target);
+        canvas.clear_rect(0, 0, physical_width, physical_height, Color::rgba(0, 0, 0, 0));
+
+        true
+    }
+
+    // Draws the contents of the clip layer to the screen through the rounded clip shape
+    fn end_clip_layer(&mut self, state: &mut State, canvas: &mut Canvas, clip: &RoundedClip, width: f32, height: f32) {
+        // Erase the contents outside of the outer rounded clips, so that nested clips intersect
+        let outer_clips = state.data.get_rounded_clips(clip.entity);
+        if self.clip_layer.is_some() && !outer_clips.is_empty() {
+            canvas.save();
+            self.reset_canvas(canvas);
+            canvas.global_composite_operation(CompositeOperation::DestinationOut);
+            for outer_clip in outer_clips.iter() {
+                let mut path = Path::new();
+                path.rect(0.0, 0.0, width, height);
+                outer_clip.add_to_path(&mut path);
+                path.solidity(Solidity::Hole);
+                canvas.fill_path(&mut path, Paint::color(Color::rgba(0, 0, 0, 255)));
+            }
+            canvas.restore();
+        }
+
+        state.render_target = self.clip_return_target;
+        canvas.set_render_target(state.render_target);
+
+        if let Some(image) = self.clip_layer {
+            canvas.save();
+            self.reset_canvas(canvas);
+            let mut path = clip.path();
+            canvas.fill_path(&mut path, Paint::image(image, 0.0, 0.0, width, height, 0.0, 1.0));
+            canvas.restore();
+        }
+    }
+}
+
+// Returns the entities an event is propagated to, in the order they receive it
+fn propagation_path(tree: &Tree, event: &Event) -> Vec<Entity> {
+    let target = event.target;
+    let mut path = Vec::new();
+
+    if event.propagation == Propagation::All {
+        path.extend(tree.into_iter());
+        return path;
+    }
+
+    if event.propagation == Propagation::Down || event.propagation == Propagation::DownUp {
+        let ancestors: Vec<Entity> = target.parent_iter(tree).collect();
+        path.extend(ancestors.iter().rev().filter(|entity| **entity != Entity::root() && **entity != target));
+    }
+
+    if event.propagation != Propagation::Fall {
+        path.push(target);
+    }
+
+    if event.propagation == Propagation::Up || event.propagation == Propagation::DownUp {
+        path.extend(target.parent_iter(tree).filter(|entity| *entity != target));
+    }
+
+    if event.propagation == Propagation::Fall {
+        path.extend(target.branch_iter(tree).filter(|entity| *entity != target));
+    }
+
+    path
+}
+
+// Sets the composite operation which blends the colors that are drawn with the render target
+fn set_blend_mode(canvas: &mut Canvas, blend_mode: BlendMode) {
+    match blend_mode {
+        BlendMode::Normal => canvas.global_composite_operation(CompositeOperation::SourceOver),
+        BlendMode::Multiply => canvas.global_composite_blend_func(BlendFactor::DstColor, BlendFactor::OneMinusSrcAlpha),
+        BlendMode::Screen => canvas.global_composite_blend_func(BlendFactor::One, BlendFactor::OneMinusSrcColor),
+        BlendMode::Add => canvas.global_composite_operation(CompositeOperation::Lighter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Widget;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum TestEvent {
+        Ping(i32),
+        // Sends itself again when it's received, which never settles
+        Echo,
+    }
+
+    // Records the pings it receives
+    #[derive(Default)]
+    struct Recorder {
+        pings: Vec<i32>,
+        echoes: usize,
+    }
+
+    impl Widget for Recorder {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+            match event.message.downcast::<TestEvent>() {
+                Some(TestEvent::Ping(value)) => self.pings.push(*value),
+
+                Some(TestEvent::Echo) => {
+                    self.echoes += 1;
+                    state.insert_event(Event::new(TestEvent::Echo).direct(entity));
+                }
+
+                None => {}
+            }
+        }
+    }
+
+    fn ping(target: Entity, value: i32) -> Event {
+        Event::new(TestEvent::Ping(value)).direct(target)
+    }
+
+    /// Only the latest copy of a unique event with the same variant, target and origin is queued
+    #[test]
+    fn unique_events() {
+        let mut state = State::new();
+        let entity = state.add(Entity::root());
+        state.event_queue.clear();
+
+        for _ in 0..3 {
+            entity.redraw(&mut state);
+            entity.relayout(&mut state);
+            Entity::root().redraw(&mut state);
+        }
+
+        state.insert_event(ping(entity, 1).unique());
+        state.insert_event(ping(entity, 2).unique());
+        state.insert_event(ping(entity, 3));
+        state.insert_event(ping(entity, 4));
+
+        assert_eq!(state.take_events().len(), 6);
+        assert!(state.unique_events.is_empty());
+    }
+
+    /// A superseded unique event is dropped when the queue is flushed and the latest copy keeps its place
+    #[test]
+    fn unique_event_dispatch() {
+        let mut state = State::new();
+        let mut event_manager = EventManager::new();
+        let entity = Recorder::default().build(&mut state, Entity::root(), |builder| builder);
+
+        state.insert_event(ping(entity, 1).unique());
+        state.insert_event(ping(entity, 2));
+        state.insert_event(ping(entity, 3).unique());
+        event_manager.flush_events(&mut state);
+
+        assert_eq!(state.query::<Recorder>(entity).unwrap().pings, vec![2, 3]);
+    }
+
+    /// Events are dispatched by increasing order, and in the order they were inserted otherwise
+    #[test]
+    fn event_order() {
+        let mut state = State::new();
+        let mut event_manager = EventManager::new();
+        let entity = Recorder::default().build(&mut state, Entity::root(), |builder| builder);
+
+        state.insert_event(ping(entity, 1));
+        state.insert_event(ping(entity, 2).order(-1));
+        state.insert_event(ping(entity, 3));
+        state.insert_event(ping(entity, 4).order(1));
+        state.insert_event(ping(entity, 5).order(-1));
+        event_manager.flush_events(&mut state);
+
+        assert_eq!(state.query::<Recorder>(entity).unwrap().pings, vec![2, 5, 1, 3, 4]);
+    }
+
+    /// Events which keep sending events are stopped after a number of batches and continue in the next frame
+    #[test]
+    fn event_cascade() {
+        let mut state = State::new();
+        let mut event_manager = EventManager::new();
+        let entity = Recorder::default().build(&mut state, Entity::root(), |builder| builder);
+
+        state.insert_event(Event::new(TestEvent::Echo).direct(entity));
+        event_manager.flush_events(&mut state);
+
+        assert_eq!(state.query::<Recorder>(entity).unwrap().echoes, MAX_EVENT_CASCADE);
+        assert_eq!(state.event_queue.len(), 1);
+        assert!(state.scheduler.is_active());
+        assert_eq!(event_manager.unsettled_cascades(), 1);
+    }
+
+    // A writer whose output can be read after it's given to a tracer
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// The tracer logs each dispatched event with its path and the handlers which received it, or why it was skipped,
+    /// at the end of each batch
+    #[test]
+    fn event_trace() {
+        let mut state = State::new();
+        let mut event_manager = EventManager::new();
+        let parent = Recorder::default().build(&mut state, Entity::root(), |builder| builder);
+        let child = Recorder::default().build(&mut state, parent, |builder| builder);
+        event_manager.flush_events(&mut state);
+
+        let buffer = SharedBuffer::default();
+        event_manager.set_tracer(EventTracer::new(buffer.clone()));
+
+        state.insert_event(Event::new(TestEvent::Ping(1)).target(child));
+        state.insert_event(Event::new(TestEvent::Ping(2)));
+        event_manager.flush_events(&mut state);
+
+        let trace = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        let message = std::any::type_name::<TestEvent>();
+        assert_eq!(
+            trace,
+            format!(
+                "{{\"batch\":0,\"message\":\"{}\",\"origin\":null,\"target\":{},\"propagation\":\"Up\",\"path\":[{},{},{}],\"handlers\":[{},{}],\"consumed_by\":null,\"skipped\":null}}\n\
+                 {{\"batch\":0,\"message\":\"{}\",\"origin\":null,\"target\":null,\"propagation\":\"Up\",\"path\":[],\"handlers\":[],\"consumed_by\":null,\"skipped\":\"null target\"}}\n",
+                message,
+                child,
+                child,
+                parent,
+                Entity::root(),
+                child,
+                parent,
+                message,
+            )
+        );
+
+        // Nothing more is written once tracing stops
+        event_manager.take_tracer();
+        state.insert_event(ping(child, 2));
+        event_manager.flush_events(&mut state);
+        assert_eq!(buffer.0.borrow().iter().filter(|byte| **byte == b'\n').count(), 2);
     }
 }
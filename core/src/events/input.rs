@@ -0,0 +1,445 @@
+use crate::{apply_hover, cancel_drag, drag_moved, drag_released, focus_arrow, focus_next, focus_prev, handle_shortcut, BoundingBox, Code, Entity, Event, Key, PropSet, Propagation, State, Units, WindowEvent};
+use crate::{InputRecording, ModifiersState, MouseButton, MouseButtonState};
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Raw input from a window backend, with positions and sizes in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// The cursor moved to a position in the window
+    PointerMoved(f32, f32),
+    /// A mouse button was pressed or released
+    Button(MouseButton, MouseButtonState),
+    /// The mouse wheel scrolled by a number of lines horizontally and vertically
+    Wheel(f32, f32),
+    /// A key was pressed or released
+    Key(Code, Option<Key>, MouseButtonState),
+    /// The state of the modifier keys changed
+    Modifiers(ModifiersState),
+    /// A character of text was typed
+    Text(char),
+    /// The window was resized
+    Resized(f32, f32),
+    /// The ratio of physical to logical pixels of the window changed
+    ScaleFactor(f32),
+    /// The window gained or lost focus
+    Focused(bool),
+    /// The user asked to close the window
+    CloseRequested,
+    /// A file from another application is being dragged over the window
+    FileHovered(PathBuf),
+    /// A file from another application was dropped onto the window
+    FileDropped(PathBuf),
+    /// The files being dragged over the window left it or the drag was cancelled
+    FileHoverCancelled,
+}
+
+/// Translates raw input from a window backend into window events and updates of [State].
+///
+/// Each backend converts its native events into [InputEvent]s and passes them to [handle](InputState::handle),
+/// which updates the mouse, modifiers, hovered and focused entities in state and queues the matching [WindowEvent]s,
+/// e.g. a `MouseDown` for the hovered (or captured) entity or a `KeyDown` for the focused entity.
+pub struct InputState {
+    double_click_interval: Duration,
+    // The time, position and button of the last press
+    last_click: Option<(Instant, (f32, f32), MouseButton)>,
+    // Whether the last press was the second click of a double click, so that a third click isn't another double click
+    double_click: bool,
+    // The start time and recording of the input, while recording
+    recording: Option<(Instant, InputRecording)>,
+    // The entity which the files dragged over the window were sent to, and the paths of the files
+    file_hover: Option<(Entity, Vec<PathBuf>)>,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            double_click_interval: Duration::from_millis(500),
+            last_click: None,
+            double_click: false,
+            recording: None,
+            file_hover: None,
+        }
+    }
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the longest time between two clicks which makes a double click. Defaults to 500ms.
+    pub fn set_double_click_interval(&mut self, interval: Duration) {
+        self.double_click_interval = interval;
+    }
+
+    /// Starts recording the handled input, replacing a recording in progress
+    pub fn start_recording(&mut self) {
+        self.recording = Some((Instant::now(), InputRecording::new()));
+    }
+
+    /// Stops recording input, returning the recording or None if input wasn't being recorded
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recording.take().map(|(_, recording)| recording)
+    }
+
+    /// Returns true if input is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Handles an input event which happened now
+    pub fn handle(&mut self, state: &mut State, input: InputEvent) {
+        self.handle_at(state, input, Instant::now());
+    }
+
+    /// Handles an input event which happened at the given time, which is used to detect double clicks
+    pub fn handle_at(&mut self, state: &mut State, input: InputEvent, time: Instant) {
+        if let Some((start, recording)) = self.recording.as_mut() {
+            recording.push(time.saturating_duration_since(*start), input.clone());
+        }
+
+        match input {
+            InputEvent::PointerMoved(x, y) => {
+                state.mouse.cursorx = x;
+                state.mouse.cursory = y;
+
+                apply_hover(state);
+                self.move_file_hover(state);
+                drag_moved(state);
+
+                if state.captured != Entity::null() {
+                    state.insert_event(
+                        Event::new(WindowEvent::MouseMove(x, y))
+                            .target(state.captured)
+                            .propagate(Propagation::Direct),
+                    );
+                } else if state.hovered != Entity::root() {
+                    state.insert_event(Event::new(WindowEvent::MouseMove(x, y)).target(state.hovered));
+                }
+            }
+
+            InputEvent::Button(button, button_state) => {
+                self.button(state, button, button_state, time);
+            }
+
+            InputEvent::Wheel(x, y) => {
+                insert_pointer_event(state, WindowEvent::MouseScroll(x, y));
+            }
+
+            InputEvent::Key(code, key, key_state) => {
+                self.key(state, code, key, key_state);
+            }
+
+            InputEvent::Modifiers(modifiers) => {
+                state.modifiers = modifiers;
+            }
+
+            InputEvent::Text(character) => {
+                state.insert_event(
+                    Event::new(WindowEvent::CharInput(character))
+                        .target(state.focused)
+                        .propagate(Propagation::Down),
+                );
+            }
+
+            InputEvent::Resized(width, height) => {
+                let root = Entity::root();
+
+                state.style.width.insert(root, Units::Pixels(width));
+                state.style.height.insert(root, Units::Pixels(height));
+
+                state.data.set_width(root, width);
+                state.data.set_height(root, height);
+
+                let mut bounding_box = BoundingBox::default();
+                bounding_box.w = width;
+                bounding_box.h = height;
+
+                state.data.set_clip_region(root, bounding_box);
+
+                root.restyle(state);
+                root.relayout(state);
+                root.redraw(state);
+            }
+
+            InputEvent::ScaleFactor(scale_factor) => {
+                state.set_scale_factor(scale_factor);
+            }
+
+            InputEvent::Focused(_) => {
+                Entity::root().restyle(state);
+                Entity::root().relayout(state);
+                Entity::root().redraw(state);
+            }
+
+            InputEvent::CloseRequested => {
+                state.insert_event(Event::new(WindowEvent::WindowClose));
+            }
+
+            // Most backends don't report the cursor moving while a file is dragged over the window, so the files
+            // are sent to the entity under the last known cursor position, hit-tested again in case the layout changed
+            InputEvent::FileHovered(path) => {
+                apply_hover(state);
+                self.move_file_hover(state);
+
+                let hovered = state.hovered;
+                let (target, paths) = self.file_hover.get_or_insert_with(|| (hovered, Vec::new()));
+                paths.push(path.clone());
+                state.insert_event(Event::new(WindowEvent::FileHovered(path)).target(*target));
+            }
+
+            InputEvent::FileDropped(path) => {
+                apply_hover(state);
+                self.move_file_hover(state);
+
+                if let Some((_, paths)) = self.file_hover.as_mut() {
+                    paths.retain(|hovered_path| *hovered_path != path);
+                }
+
+                if self.file_hover.as_ref().map_or(false, |(_, paths)| paths.is_empty()) {
+                    self.file_hover = None;
+                }
+
+                state.insert_event(Event::new(WindowEvent::FileDropped(path)).target(state.hovered));
+            }
+
+            InputEvent::FileHoverCancelled => {
+                let target = self.file_hover.take().map_or(state.hovered, |(target, _)| target);
+                state.insert_event(Event::new(WindowEvent::FileHoverCancelled).target(target));
+            }
+        }
+    }
+
+    // Sends the files dragged over the window to the hovered entity if it changed, cancelling the hover of the
+    // entity they were sent to before
+    fn move_file_hover(&mut self, state: &mut State) {
+        if let Some((target, paths)) = self.file_hover.as_mut() {
+            if *target != state.hovered {
+                state.insert_event(Event::new(WindowEvent::FileHoverCancelled).target(*target));
+
+                for path in paths.iter() {
+                    state.insert_event(Event::new(WindowEvent::FileHovered(path.clone())).target(state.hovered));
+                }
+
+                *target = state.hovered;
+            }
+        }
+    }
+
+    fn button(&mut self, state: &mut State, button: MouseButton, button_state: MouseButtonState, time: Instant) {
+        let cursor = (state.mouse.cursorx, state.mouse.cursory);
+        let hovered = state.hovered;
+
+        let button_data = match button {
+            MouseButton::Left => Some(&mut state.mouse.left),
+            MouseButton::Right => Some(&mut state.mouse.right),
+            MouseButton::Middle => Some(&mut state.mouse.middle),
+            MouseButton::Other(_) => None,
+        };
+
+        if let Some(button_data) = button_data {
+            button_data.state = button_state;
+
+            match button_state {
+                MouseButtonState::Pressed => {
+                    button_data.pos_down = cursor;
+                    button_data.pressed = hovered;
+                }
+
+                MouseButtonState::Released => {
+                    button_data.pos_up = cursor;
+                    button_data.released = hovered;
+                }
+            }
+        }
+
+        match button_state {
+            MouseButtonState::Pressed => {
+                // A second press of the same button at the same position within the interval is a double click
+                let is_double_click = match self.last_click {
+                    Some((last_time, last_pos, last_button)) => {
+                        time.saturating_duration_since(last_time) <= self.double_click_interval
+                            && last_pos == cursor
+                            && last_button == button
+                    }
+
+                    None => false,
+                };
+
+                if is_double_click {
+                    if !self.double_click {
+                        insert_pointer_event(state, WindowEvent::MouseDoubleClick(button));
+                        self.double_click = true;
+                    }
+                } else {
+                    self.double_click = false;
+                }
+
+                self.last_click = Some((time, cursor, button));
+
+                insert_pointer_event(state, WindowEvent::MouseDown(button));
+            }
+
+            MouseButtonState::Released => {
+                insert_pointer_event(state, WindowEvent::MouseUp(button));
+                drag_released(state);
+            }
+        }
+    }
+
+    fn key(&mut self, state: &mut State, code: Code, key: Option<Key>, key_state: MouseButtonState) {
+        // Key events go to the focused entity, or the hovered entity if nothing is focused,
+        // as it was before the key moved the focus
+        let target = if state.focused != Entity::null() {
+            state.focused
+        } else {
+            state.hovered
+        };
+
+        if key_state == MouseButtonState::Pressed {
+            // Escape cancels a drag instead of being sent to the focused entity
+            if code == Code::Escape && state.drag.is_dragging() {
+                cancel_drag(state);
+                return;
+            }
+
+            // A key which is part of a shortcut isn't sent to the focused entity
+            if handle_shortcut(state, code) {
+                return;
+            }
+
+            match code {
+                Code::Tab => {
+                    if state.modifiers.shift {
+                        focus_prev(state);
+                    } else {
+                        focus_next(state);
+                    }
+
+                    Entity::root().restyle(state);
+                }
+
+                Code::ArrowLeft | Code::ArrowRight | Code::ArrowUp | Code::ArrowDown | Code::Home | Code::End => {
+                    if focus_arrow(state, code) {
+                        Entity::root().restyle(state);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        let window_event = match key_state {
+            MouseButtonState::Pressed => WindowEvent::KeyDown(code, key),
+            MouseButtonState::Released => WindowEvent::KeyUp(code, key),
+        };
+
+        state.insert_event(Event::new(window_event).target(target).propagate(Propagation::DownUp));
+    }
+}
+
+// Sends a pointer event to the captured entity, or to the hovered entity if none is captured
+fn insert_pointer_event(state: &mut State, window_event: WindowEvent) {
+    if state.captured != Entity::null() {
+        state.insert_event(
+            Event::new(window_event)
+                .target(state.captured)
+                .propagate(Propagation::Direct),
+        );
+    } else {
+        state.insert_event(Event::new(window_event).target(state.hovered));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Chord, Shortcut, ShortcutScope};
+
+    fn window_events(state: &mut State) -> Vec<WindowEvent> {
+        state
+            .event_queue
+            .drain(..)
+            .filter_map(|mut event| event.message.downcast::<WindowEvent>().cloned())
+            .collect()
+    }
+
+    /// Two presses at the same position within the interval make one double click, and a third press doesn't make another
+    #[test]
+    fn double_click() {
+        let mut state = State::new();
+        let mut input = InputState::new();
+
+        let start = Instant::now();
+        input.handle_at(&mut state, InputEvent::PointerMoved(10.0, 10.0), start);
+        state.event_queue.clear();
+
+        for (index, millis) in [0, 100, 200].iter().enumerate() {
+            let time = start + Duration::from_millis(*millis);
+            input.handle_at(&mut state, InputEvent::Button(MouseButton::Left, MouseButtonState::Pressed), time);
+            input.handle_at(&mut state, InputEvent::Button(MouseButton::Left, MouseButtonState::Released), time);
+
+            let double_clicks = window_events(&mut state)
+                .into_iter()
+                .filter(|event| matches!(event, WindowEvent::MouseDoubleClick(MouseButton::Left)))
+                .count();
+
+            assert_eq!(double_clicks, if index == 1 { 1 } else { 0 });
+        }
+
+        assert_eq!(state.mouse.left.state, MouseButtonState::Released);
+        assert_eq!(state.mouse.left.pos_down, (10.0, 10.0));
+    }
+
+    /// Key and text events are sent to the focused entity and modifiers are stored in state
+    #[test]
+    fn key_events() {
+        let mut state = State::new();
+        let mut input = InputState::new();
+
+        let modifiers = ModifiersState { shift: true, ..ModifiersState::default() };
+        input.handle(&mut state, InputEvent::Modifiers(modifiers));
+        input.handle(&mut state, InputEvent::Key(Code::KeyA, Some(Key::Character("A".to_string())), MouseButtonState::Pressed));
+        input.handle(&mut state, InputEvent::Text('A'));
+
+        assert_eq!(state.modifiers, modifiers);
+
+        let events = state.event_queue.drain(..).collect::<Vec<_>>();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.target == state.focused));
+
+        let mut events = events.into_iter();
+        assert!(matches!(
+            events.next().unwrap().message.downcast::<WindowEvent>(),
+            Some(WindowEvent::KeyDown(Code::KeyA, Some(Key::Character(_))))
+        ));
+        assert!(matches!(
+            events.next().unwrap().message.downcast::<WindowEvent>(),
+            Some(WindowEvent::CharInput('A'))
+        ));
+    }
+
+    /// A key which completes a shortcut sends the message of the shortcut instead of a key event
+    #[test]
+    fn shortcut_keys() {
+        let mut state = State::new();
+        let mut input = InputState::new();
+
+        state
+            .shortcuts
+            .bind(Shortcut::new(Chord::new(Code::KeyQ).ctrl()), ShortcutScope::Global, Entity::root(), WindowEvent::WindowClose)
+            .unwrap();
+
+        input.handle(&mut state, InputEvent::Modifiers(ModifiersState { ctrl: true, ..ModifiersState::default() }));
+        input.handle(&mut state, InputEvent::Key(Code::KeyQ, None, MouseButtonState::Pressed));
+        input.handle(&mut state, InputEvent::Key(Code::KeyW, None, MouseButtonState::Pressed));
+
+        let events = window_events(&mut state);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], WindowEvent::WindowClose));
+        assert!(matches!(events[1], WindowEvent::KeyDown(Code::KeyW, None)));
+    }
+}
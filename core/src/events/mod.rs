@@ -40,6 +40,19 @@ pub use event_manager::EventManager;
 
 mod event;
 pub use event::{Event, Message, Propagation};
+pub(crate) use event::UniqueKey;
+
+mod input;
+pub use input::{InputEvent, InputState};
+
+mod proxy;
+pub use proxy::{EventLoopClosed, EventProxy, EventReceiver};
+
+mod recording;
+pub use recording::{InputRecording, RecordingParseError};
+
+mod tracer;
+pub use tracer::{EventTrace, EventTracer};
 
 mod event_handler;
 pub(crate) use event_handler::EventHandler;
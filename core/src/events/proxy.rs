@@ -0,0 +1,147 @@
+use crate::{Entity, Event, Message};
+
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+// An event sent from another thread, which is built on the UI thread as events can't be sent between threads
+type ProxiedEvent = Box<dyn FnOnce() -> Event + Send>;
+
+/// Sends events into the application from other threads, e.g. meter levels from an audio thread.
+///
+/// The proxy is obtained with `state.event_proxy()`, or from the `Application` of a backend, and can be cloned and
+/// moved to any thread. Sent events are inserted into the event queue at the start of the next frame, and the backend
+/// wakes the event loop when an event is sent.
+///
+/// # Examples
+/// ```
+/// let proxy = state.event_proxy();
+/// std::thread::spawn(move || {
+///     proxy.send(meter, MeterEvent::Level(0.5)).ok();
+/// });
+/// ```
+#[derive(Clone)]
+pub struct EventProxy {
+    sender: Sender<ProxiedEvent>,
+    // Wakes the event loop of the backend, shared by all of the clones of the proxy
+    waker: Arc<Mutex<Option<Box<dyn Fn() + Send>>>>,
+}
+
+impl EventProxy {
+    /// Creates a proxy and the receiver of the events sent through it
+    pub fn channel() -> (EventProxy, EventReceiver) {
+        let (sender, receiver) = channel();
+
+        let proxy = EventProxy {
+            sender,
+            waker: Arc::new(Mutex::new(None)),
+        };
+
+        (proxy, EventReceiver { receiver })
+    }
+
+    /// Sends a message to the target entity, returning an error if the application has closed
+    pub fn send<M: Message + Send>(&self, target: Entity, message: M) -> Result<(), EventLoopClosed> {
+        self.sender
+            .send(Box::new(move || Event::new(message).target(target)))
+            .map_err(|_| EventLoopClosed)?;
+
+        if let Ok(waker) = self.waker.lock() {
+            if let Some(waker) = waker.as_ref() {
+                (waker)();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the function which wakes the event loop when an event is sent, called by the backend
+    pub fn set_waker<F: Fn() + Send + 'static>(&self, waker: F) {
+        if let Ok(mut current) = self.waker.lock() {
+            *current = Some(Box::new(waker));
+        }
+    }
+}
+
+impl fmt::Debug for EventProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventProxy").finish()
+    }
+}
+
+/// Receives the events sent through an [EventProxy].
+pub struct EventReceiver {
+    receiver: Receiver<ProxiedEvent>,
+}
+
+impl EventReceiver {
+    /// Returns the events which have been sent since the last call, without waiting
+    pub fn try_events(&self) -> Vec<Event> {
+        self.receiver.try_iter().map(|build| build()).collect()
+    }
+}
+
+/// The error returned when sending through an [EventProxy] after the application has closed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EventLoopClosed;
+
+impl fmt::Display for EventLoopClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the event loop has closed")
+    }
+}
+
+impl std::error::Error for EventLoopClosed {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::State;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, PartialEq)]
+    struct Level(f32);
+
+    /// Events sent from another thread wake the event loop and are queued with their target by the state
+    #[test]
+    fn send_from_thread() {
+        let mut state = State::new();
+        let meter = state.add(Entity::root());
+        state.event_queue.clear();
+
+        let wakes = Arc::new(AtomicUsize::new(0));
+        let proxy = state.event_proxy();
+        let counter = wakes.clone();
+        proxy.set_waker(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        std::thread::spawn(move || {
+            proxy.send(meter, Level(0.5)).unwrap();
+            proxy.send(meter, Level(0.25)).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(wakes.load(Ordering::SeqCst), 2);
+
+        state.receive_proxied_events();
+
+        let mut events = state.event_queue.drain(..).collect::<Vec<_>>();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].target, meter);
+        assert_eq!(events[0].message.downcast::<Level>(), Some(&mut Level(0.5)));
+        assert_eq!(events[1].message.downcast::<Level>(), Some(&mut Level(0.25)));
+    }
+
+    /// Sending fails once the receiver has been dropped
+    #[test]
+    fn closed() {
+        let (proxy, receiver) = EventProxy::channel();
+        drop(receiver);
+
+        assert_eq!(proxy.send(Entity::root(), Level(1.0)), Err(EventLoopClosed));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{Code, EventManager, InputEvent, InputState, Key, ModifiersState, MouseButton, MouseButtonState, State};
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// A recording of the raw input of a window, with the time of each input since the recording started.
+///
+/// Input is recorded by an [InputState] between `start_recording` and `stop_recording`, and can be saved to a file
+/// and replayed into a [State], e.g. to reproduce a bug reported by a user in a regression test.
+///
+/// A recording is saved as text, with one input per line: the time in microseconds, the name of the input and its
+/// values separated by spaces, e.g. `1520000 Button Left Pressed`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    events: Vec<(Duration, InputEvent)>,
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an input at the given time since the start of the recording
+    pub fn push(&mut self, time: Duration, input: InputEvent) {
+        self.events.push((time, input));
+    }
+
+    /// Returns the recorded inputs with their times since the start of the recording
+    pub fn events(&self) -> &[(Duration, InputEvent)] {
+        &self.events
+    }
+
+    /// Saves the recording to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    /// Loads a recording saved with [save](InputRecording::save)
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        std::fs::read_to_string(path)?
+            .parse()
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+
+    /// Replays the recording into the state, dispatching the events caused by each input before the next.
+    ///
+    /// The inputs are handled with their recorded times, so that double clicks are the same as when recorded, but
+    /// without waiting between them.
+    pub fn replay(&self, state: &mut State, input_state: &mut InputState, event_manager: &mut EventManager) {
+        let start = Instant::now();
+
+        for (time, input) in self.events.iter() {
+            input_state.handle_at(state, input.clone(), start + *time);
+
+            if !state.event_queue.is_empty() {
+                event_manager.flush_events(state);
+            }
+        }
+    }
+}
+
+impl fmt::Display for InputRecording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (time, input) in self.events.iter() {
+            write!(f, "{} ", time.as_micros())?;
+
+            match input {
+                InputEvent::PointerMoved(x, y) => write!(f, "PointerMoved {} {}", x, y)?,
+                InputEvent::Button(button, button_state) => {
+                    write!(f, "Button {} {}", button_name(*button), state_name(*button_state))?
+                }
+                InputEvent::Wheel(x, y) => write!(f, "Wheel {} {}", x, y)?,
+                // The key is last as it can contain spaces
+                InputEvent::Key(code, key, key_state) => {
+                    write!(f, "Key {} {}", code, state_name(*key_state))?;
+
+                    if let Some(key) = key {
+                        write!(f, " {}", key)?;
+                    }
+                }
+                InputEvent::Modifiers(modifiers) => write!(
+                    f,
+                    "Modifiers {} {} {} {}",
+                    modifiers.shift as u8, modifiers.ctrl as u8, modifiers.alt as u8, modifiers.logo as u8
+                )?,
+                // The character is written as its code point so that whitespace survives
+                InputEvent::Text(character) => write!(f, "Text {}", *character as u32)?,
+                InputEvent::Resized(width, height) => write!(f, "Resized {} {}", width, height)?,
+                InputEvent::ScaleFactor(scale_factor) => write!(f, "ScaleFactor {}", scale_factor)?,
+                InputEvent::Focused(focused) => write!(f, "Focused {}", focused)?,
+                InputEvent::CloseRequested => write!(f, "CloseRequested")?,
+                InputEvent::FileHovered(path) => write!(f, "FileHovered {}", path.display())?,
+                InputEvent::FileDropped(path) => write!(f, "FileDropped {}", path.display())?,
+                InputEvent::FileHoverCancelled => write!(f, "FileHoverCancelled")?,
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for InputRecording {
+    type Err = RecordingParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut recording = InputRecording::new();
+
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let error = |message: &str| RecordingParseError {
+                line: index + 1,
+                message: message.to_string(),
+            };
+
+            let mut parts = line.splitn(3, ' ');
+            let time = parts
+                .next()
+                .and_then(|time| time.parse::<u64>().ok())
+                .ok_or_else(|| error("expected the time in microseconds"))?;
+            let name = parts.next().ok_or_else(|| error("expected the name of the input"))?;
+            let rest = parts.next().unwrap_or_default();
+
+            let input = parse_input(name, rest).ok_or_else(|| error(&format!("invalid {} input", name)))?;
+
+            recording.push(Duration::from_micros(time), input);
+        }
+
+        Ok(recording)
+    }
+}
+
+/// The error returned when parsing an [InputRecording] which is not in the saved format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingParseError {
+    /// The line of the recording, counted from 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RecordingParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for RecordingParseError {}
+
+// Parses the values of an input, returning None if they are invalid
+fn parse_input(name: &str, rest: &str) -> Option<InputEvent> {
+    let mut values = rest.split(' ');
+    let mut next_f32 = || values.next().and_then(|value| value.parse::<f32>().ok());
+
+    Some(match name {
+        "PointerMoved" => InputEvent::PointerMoved(next_f32()?, next_f32()?),
+        "Wheel" => InputEvent::Wheel(next_f32()?, next_f32()?),
+        "Resized" => InputEvent::Resized(next_f32()?, next_f32()?),
+        "ScaleFactor" => InputEvent::ScaleFactor(next_f32()?),
+
+        "Button" => {
+            let mut values = rest.split(' ');
+            InputEvent::Button(parse_button(values.next()?)?, parse_state(values.next()?)?)
+        }
+
+        "Key" => {
+            // The key is the rest of the line after the code and the state, and is missing if there isn't one
+            let mut values = rest.splitn(3, ' ');
+            let code = Code::from_str(values.next()?).ok()?;
+            let key_state = parse_state(values.next()?)?;
+            let key = match values.next() {
+                Some(key) => Some(Key::from_str(key).ok()?),
+                None => None,
+            };
+
+            InputEvent::Key(code, key, key_state)
+        }
+
+        "Modifiers" => {
+            let flags = rest.split(' ').map(|flag| flag == "1").collect::<Vec<_>>();
+            if flags.len() != 4 {
+                return None;
+            }
+
+            InputEvent::Modifiers(ModifiersState {
+                shift: flags[0],
+                ctrl: flags[1],
+                alt: flags[2],
+                logo: flags[3],
+            })
+        }
+
+        "Text" => InputEvent::Text(std::char::from_u32(rest.parse().ok()?)?),
+        "Focused" => InputEvent::Focused(rest.parse().ok()?),
+        "CloseRequested" => InputEvent::CloseRequested,
+        "FileHovered" => InputEvent::FileHovered(PathBuf::from(rest)),
+        "FileDropped" => InputEvent::FileDropped(PathBuf::from(rest)),
+        "FileHoverCancelled" => InputEvent::FileHoverCancelled,
+
+        _ => return None,
+    })
+}
+
+fn button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "Left".to_string(),
+        MouseButton::Right => "Right".to_string(),
+        MouseButton::Middle => "Middle".to_string(),
+        MouseButton::Other(id) => id.to_string(),
+    }
+}
+
+fn parse_button(name: &str) -> Option<MouseButton> {
+    match name {
+        "Left" => Some(MouseButton::Left),
+        "Right" => Some(MouseButton::Right),
+        "Middle" => Some(MouseButton::Middle),
+        id => id.parse().ok().map(MouseButton::Other),
+    }
+}
+
+fn state_name(button_state: MouseButtonState) -> &'static str {
+    match button_state {
+        MouseButtonState::Pressed => "Pressed",
+        MouseButtonState::Released => "Released",
+    }
+}
+
+fn parse_state(name: &str) -> Option<MouseButtonState> {
+    match name {
+        "Pressed" => Some(MouseButtonState::Pressed),
+        "Released" => Some(MouseButtonState::Released),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A recording survives being saved as text and parsed again, including keys and characters with spaces
+    #[test]
+    fn round_trip() {
+        let mut recording = InputRecording::new();
+        let inputs = vec![
+            InputEvent::PointerMoved(15.5, 10.0),
+            InputEvent::Button(MouseButton::Left, MouseButtonState::Pressed),
+            InputEvent::Button(MouseButton::Other(4), MouseButtonState::Released),
+            InputEvent::Modifiers(ModifiersState { shift: true, ..ModifiersState::default() }),
+            InputEvent::Key(Code::Space, Some(Key::Character(" ".to_string())), MouseButtonState::Pressed),
+            InputEvent::Key(Code::Enter, Some(Key::Enter), MouseButtonState::Released),
+            InputEvent::Key(Code::KeyA, None, MouseButtonState::Pressed),
+            InputEvent::Text(' '),
+            InputEvent::Wheel(0.0, -1.0),
+            InputEvent::Focused(false),
+            InputEvent::FileDropped(PathBuf::from("samples/kick drum.wav")),
+            InputEvent::CloseRequested,
+        ];
+
+        for (index, input) in inputs.into_iter().enumerate() {
+            recording.push(Duration::from_millis(index as u64 * 16), input);
+        }
+
+        let text = recording.to_string();
+        assert_eq!(text.parse::<InputRecording>(), Ok(recording));
+    }
+
+    /// The line of an invalid input is reported
+    #[test]
+    fn parse_error() {
+        let error = "0 PointerMoved 1 2\n16 Button Left Down\n".parse::<InputRecording>().unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    /// Replaying a recording reproduces the state of the recorded session
+    #[test]
+    fn record_and_replay() {
+        let mut state = State::new();
+        let mut input_state = InputState::new();
+
+        input_state.start_recording();
+        input_state.handle(&mut state, InputEvent::PointerMoved(5.0, 5.0));
+        input_state.handle(&mut state, InputEvent::Button(MouseButton::Right, MouseButtonState::Pressed));
+        let recording = input_state.stop_recording().unwrap();
+        assert_eq!(recording.events().len(), 2);
+
+        let mut replayed = State::new();
+        recording.replay(&mut replayed, &mut InputState::new(), &mut EventManager::new());
+
+        assert_eq!(replayed.mouse.cursorx, 5.0);
+        assert_eq!(replayed.mouse.right.state, MouseButtonState::Pressed);
+        assert_eq!(replayed.mouse.right.pos_down, (5.0, 5.0));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Entity, Event, Propagation};
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// The record of the dispatch of one event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventTrace {
+    /// The number of the batch of events the event was dispatched in, counted from when tracing started
+    pub batch: u64,
+    /// The name of the type of the message
+    pub message: &'static str,
+    pub origin: Entity,
+    pub target: Entity,
+    pub propagation: Propagation,
+    /// The entities the event was propagated to, in order, whether or not they have a handler.
+    /// Listeners receive the event before it's propagated and aren't included.
+    pub path: Vec<Entity>,
+    /// The entities whose handlers received the event, in the order they received it
+    pub handlers: Vec<Entity>,
+    /// The entity which consumed the event, if it was consumed
+    pub consumed_by: Option<Entity>,
+    /// Why the event wasn't propagated, e.g. a `Redraw` which only sets a flag in the event manager
+    pub skipped: Option<&'static str>,
+}
+
+impl EventTrace {
+    /// Formats the trace as a line of JSON
+    pub fn to_json(&self) -> String {
+        let path = self.path.iter().map(|entity| entity_json(*entity)).collect::<Vec<_>>();
+        let handlers = self.handlers.iter().map(|entity| entity_json(*entity)).collect::<Vec<_>>();
+        let skipped = match self.skipped {
+            Some(reason) => format!("\"{}\"", reason.escape_default()),
+            None => "null".to_string(),
+        };
+
+        format!(
+            "{{\"batch\":{},\"message\":\"{}\",\"origin\":{},\"target\":{},\"propagation\":\"{:?}\",\"path\":[{}],\"handlers\":[{}],\"consumed_by\":{},\"skipped\":{}}}",
+            self.batch,
+            self.message.escape_default(),
+            entity_json(self.origin),
+            entity_json(self.target),
+            self.propagation,
+            path.join(","),
+            handlers.join(","),
+            entity_json(self.consumed_by.unwrap_or(Entity::null())),
+            skipped,
+        )
+    }
+}
+
+// Formats an entity as its index, or null
+fn entity_json(entity: Entity) -> String {
+    if entity == Entity::null() {
+        "null".to_string()
+    } else {
+        entity.to_string()
+    }
+}
+
+/// Logs every event dispatched by the [EventManager](crate::EventManager) as a line of JSON, see [EventTrace].
+///
+/// Tracing is enabled with `event_manager.set_tracer(EventTracer::create("events.jsonl")?)`. The trace is written
+/// at the end of each batch of events.
+pub struct EventTracer {
+    writer: Box<dyn Write>,
+    batch: u64,
+    // The event being dispatched
+    current: Option<EventTrace>,
+}
+
+impl EventTracer {
+    /// Creates a tracer which writes to the writer
+    pub fn new<W: Write + 'static>(writer: W) -> Self {
+        Self {
+            writer: Box::new(writer),
+            batch: 0,
+            current: None,
+        }
+    }
+
+    /// Creates a tracer which writes to a new file at the path, replacing an existing file
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    // Starts the trace of an event, writing the trace of the previous event
+    pub(crate) fn begin(&mut self, event: &Event) {
+        self.finish();
+
+        self.current = Some(EventTrace {
+            batch: self.batch,
+            message: event.message_name(),
+            origin: event.origin,
+            target: event.target,
+            propagation: event.propagation.clone(),
+            path: Vec::new(),
+            handlers: Vec::new(),
+            consumed_by: None,
+            skipped: None,
+        });
+    }
+
+    // Records the entities the event is propagated to
+    pub(crate) fn path(&mut self, path: Vec<Entity>) {
+        if let Some(trace) = self.current.as_mut() {
+            trace.path = path;
+        }
+    }
+
+    // Records why the event isn't propagated
+    pub(crate) fn skipped(&mut self, reason: &'static str) {
+        if let Some(trace) = self.current.as_mut() {
+            trace.skipped = Some(reason);
+        }
+    }
+
+    // Records that a handler received the event
+    pub(crate) fn handled(&mut self, entity: Entity, consumed: bool) {
+        if let Some(trace) = self.current.as_mut() {
+            trace.handlers.push(entity);
+
+            if consumed && trace.consumed_by.is_none() {
+                trace.consumed_by = Some(entity);
+            }
+        }
+    }
+
+    // Writes the traces of the batch
+    pub(crate) fn end_batch(&mut self) {
+        self.finish();
+        self.writer.flush().ok();
+        self.batch += 1;
+    }
+
+    fn finish(&mut self) {
+        if let Some(trace) = self.current.take() {
+            writeln!(self.writer, "{}", trace.to_json()).ok();
+        }
+    }
+}
+
+impl Drop for EventTracer {
+    fn drop(&mut self) {
+        self.finish();
+        self.writer.flush().ok();
+    }
+}
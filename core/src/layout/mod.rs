@@ -18,6 +18,8 @@ pub(crate) fn geometry_changed(state: &mut State, tree: &Tree) {
     for node in tree.down_iter() {
         let geometry_changed = state.data.geometry_changed(node);
         if !geometry_changed.is_empty() {
+            // The entity needs to be redrawn where it was and where it is now
+            state.damage.mark(node);
             state.insert_event(Event::new(WindowEvent::GeometryChanged(geometry_changed)).target(node).propagate(Propagation::Down));
         }
 
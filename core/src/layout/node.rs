@@ -63,7 +63,7 @@ impl<'a> Node<'a> for Entity {
     }
 
     fn width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
-        store.width.get(*self).cloned()
+        or_content_size(store.width.get(*self).cloned(), store.content_size.get(*self).map(|size| size.0))
     }
 
     fn min_width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
@@ -75,7 +75,7 @@ impl<'a> Node<'a> for Entity {
     }
 
     fn height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
-        store.height.get(*self).cloned()
+        or_content_size(store.height.get(*self).cloned(), store.content_size.get(*self).map(|size| size.1))
     }
 
     fn min_height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
@@ -119,19 +119,19 @@ impl<'a> Node<'a> for Entity {
     }
 
     fn border_left(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
-        store.border_width.get(*self).cloned()
+        store.border_width_left.get(*self).or_else(|| store.border_width.get(*self)).cloned()
     }
 
     fn border_right(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
-        store.border_width.get(*self).cloned()
+        store.border_width_right.get(*self).or_else(|| store.border_width.get(*self)).cloned()
     }
 
     fn border_top(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
-        store.border_width.get(*self).cloned()
+        store.border_width_top.get(*self).or_else(|| store.border_width.get(*self)).cloned()
     }
 
     fn border_bottom(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
-        store.border_width.get(*self).cloned()
+        store.border_width_bottom.get(*self).or_else(|| store.border_width.get(*self)).cloned()
     }
 
     fn row_index(&self, store: &'_ Self::Data) -> Option<usize> {
@@ -149,4 +149,12 @@ impl<'a> Node<'a> for Entity {
     fn col_span(&self, store: &'_ Self::Data) -> Option<usize> {
         store.col_span.get(*self).cloned()
     }
-}
\ No newline at end of file
+}
+
+// Resolves an unset or `Auto` size to the size of the content of the entity, if it has one
+fn or_content_size(units: Option<morphorm::Units>, content: Option<f32>) -> Option<morphorm::Units> {
+    match (units, content) {
+        (None, Some(content)) | (Some(morphorm::Units::Auto), Some(content)) => Some(morphorm::Units::Pixels(content)),
+        (units, _) => units,
+    }
+}
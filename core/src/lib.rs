@@ -24,6 +24,9 @@ pub use id::GenerationalId;
 pub mod layout;
 pub use layout::GeometryChanged;
 
+pub mod renderer;
+pub use renderer::*;
+
 pub mod state;
 pub use state::*;
 
@@ -35,6 +38,9 @@ pub use binding::*;
 pub mod style;
 pub use style::*;
 
+pub mod svg;
+pub use svg::*;
+
 mod systems;
 pub use crate::systems::*;
 
@@ -51,12 +57,8 @@ pub mod window;
 pub use window::*;
 
 
-use femtovg::renderer::OpenGl;
-
 pub use keyboard_types::{Code, Key};
 
-pub type Canvas = femtovg::Canvas<OpenGl>;
-
 
 
 
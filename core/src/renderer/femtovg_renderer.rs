@@ -0,0 +1,491 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+use crate::Color;
+
+use super::{
+    Align, Baseline, BlendFactor, CompositeOperation, FillRule, FontId, FontMetrics, GlyphPosition, ImageFilter,
+    ImageFlags, ImageId, LineCap, LineJoin, Paint, PaintFlavor, Path, PixelFormat, RenderError, RenderTarget,
+    Renderer, Solidity, TextMetrics, Verb,
+};
+
+// The number of frames a converted path is kept for after it was last drawn
+const PATH_CACHE_FRAMES: u64 = 60;
+
+// A path converted to femtovg, which keeps its tessellation between frames
+struct CachedPath {
+    verbs: Vec<Verb>,
+    path: femtovg::Path,
+    last_used: u64,
+}
+
+/// A [Renderer] which draws with femtovg, e.g. to an OpenGL context
+pub struct FemtovgRenderer<T: femtovg::Renderer> {
+    canvas: femtovg::Canvas<T>,
+    // Maps tuix image ids to femtovg image ids
+    images: HashMap<ImageId, femtovg::ImageId>,
+    next_image: usize,
+    // Indexed by tuix font id
+    fonts: Vec<femtovg::FontId>,
+    // Converted paths keyed by a hash of their verbs, so that a path drawn again is only converted and tessellated once
+    paths: HashMap<u64, CachedPath>,
+    // The number of flushed frames, used to drop paths which are no longer drawn
+    frame: u64,
+}
+
+impl<T: femtovg::Renderer> FemtovgRenderer<T> {
+    pub fn new(canvas: femtovg::Canvas<T>) -> Self {
+        Self {
+            canvas,
+            images: HashMap::new(),
+            next_image: 0,
+            fonts: Vec::new(),
+            paths: HashMap::new(),
+            frame: 0,
+        }
+    }
+
+    /// Returns the femtovg canvas, e.g. to take a screenshot
+    pub fn canvas(&mut self) -> &mut femtovg::Canvas<T> {
+        &mut self.canvas
+    }
+
+    fn add_image(&mut self, id: femtovg::ImageId) -> ImageId {
+        let image_id = ImageId(self.next_image);
+        self.next_image += 1;
+        self.images.insert(image_id, id);
+        image_id
+    }
+
+    fn convert_paint(&self, paint: &Paint) -> femtovg::Paint {
+        let mut femtovg_paint = match paint.flavor {
+            PaintFlavor::Color(color) => femtovg::Paint::color(color.into()),
+
+            PaintFlavor::Image { id, x, y, width, height, angle, alpha } => match self.images.get(&id) {
+                Some(image) => femtovg::Paint::image(*image, x, y, width, height, angle, alpha),
+                None => femtovg::Paint::color(femtovg::Color::rgba(0, 0, 0, 0)),
+            },
+
+            PaintFlavor::LinearGradient { start_x, start_y, end_x, end_y, stops, num_stops } => {
+                let stops = stops[..num_stops]
+                    .iter()
+                    .map(|(offset, color)| (*offset, femtovg::Color::from(*color)))
+                    .collect::<Vec<_>>();
+
+                femtovg::Paint::linear_gradient_stops(start_x, start_y, end_x, end_y, &stops)
+            }
+
+            PaintFlavor::BoxGradient { x, y, width, height, radius, feather, inner_color, outer_color } => {
+                femtovg::Paint::box_gradient(x, y, width, height, radius, feather, inner_color.into(), outer_color.into())
+            }
+        };
+
+        femtovg_paint.set_line_width(paint.line_width);
+        femtovg_paint.set_line_cap(convert_line_cap(paint.line_cap));
+        femtovg_paint.set_line_join(convert_line_join(paint.line_join));
+        femtovg_paint.set_fill_rule(convert_fill_rule(paint.fill_rule));
+        femtovg_paint.set_anti_alias(paint.anti_alias);
+        apply_text_settings(&mut femtovg_paint, paint, &self.fonts);
+
+        femtovg_paint
+    }
+}
+
+impl<T: femtovg::Renderer> Renderer for FemtovgRenderer<T> {
+    fn set_size(&mut self, width: u32, height: u32, dpi: f32) {
+        self.canvas.set_size(width, height, dpi);
+    }
+
+    fn clear_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        self.canvas.clear_rect(x, y, width, height, color.into());
+    }
+
+    fn flush(&mut self) {
+        self.canvas.flush();
+
+        self.frame += 1;
+        drop_unused_paths(&mut self.paths, self.frame);
+    }
+
+    fn save(&mut self) {
+        self.canvas.save();
+    }
+
+    fn restore(&mut self) {
+        self.canvas.restore();
+    }
+
+    fn reset(&mut self) {
+        self.canvas.reset();
+    }
+
+    fn set_transform(&mut self, a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) {
+        self.canvas.set_transform(a, b, c, d, e, f);
+    }
+
+    fn reset_transform(&mut self) {
+        self.canvas.reset_transform();
+    }
+
+    fn translate(&mut self, x: f32, y: f32) {
+        self.canvas.translate(x, y);
+    }
+
+    fn rotate(&mut self, angle: f32) {
+        self.canvas.rotate(angle);
+    }
+
+    fn scale(&mut self, x: f32, y: f32) {
+        self.canvas.scale(x, y);
+    }
+
+    fn global_composite_operation(&mut self, operation: CompositeOperation) {
+        self.canvas.global_composite_operation(convert_composite_operation(operation));
+    }
+
+    fn global_composite_blend_func(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor) {
+        self.canvas.global_composite_blend_func(convert_blend_factor(src_factor), convert_blend_factor(dst_factor));
+    }
+
+    fn scissor(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.canvas.scissor(x, y, width, height);
+    }
+
+    fn intersect_scissor(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.canvas.intersect_scissor(x, y, width, height);
+    }
+
+    fn reset_scissor(&mut self) {
+        self.canvas.reset_scissor();
+    }
+
+    fn fill_path(&mut self, path: &mut Path, paint: Paint) {
+        let femtovg_paint = self.convert_paint(&paint);
+        let femtovg_path = cached_path(&mut self.paths, self.frame, path);
+        self.canvas.fill_path(femtovg_path, femtovg_paint);
+    }
+
+    fn stroke_path(&mut self, path: &mut Path, paint: Paint) {
+        let femtovg_paint = self.convert_paint(&paint);
+        let femtovg_path = cached_path(&mut self.paths, self.frame, path);
+        self.canvas.stroke_path(femtovg_path, femtovg_paint);
+    }
+
+    fn add_font_mem(&mut self, data: &[u8]) -> Result<FontId, RenderError> {
+        let id = self.canvas.add_font_mem(data).map_err(convert_error)?;
+        self.fonts.push(id);
+        Ok(FontId(self.fonts.len() - 1))
+    }
+
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError> {
+        let paint = self.convert_paint(&paint);
+        self.canvas.fill_text(x, y, text, paint).map(convert_text_metrics).map_err(convert_error)
+    }
+
+    fn measure_text(&self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError> {
+        let paint = self.convert_paint(&paint);
+        self.canvas.measure_text(x, y, text, paint).map(convert_text_metrics).map_err(convert_error)
+    }
+
+    fn measure_font(&self, paint: Paint) -> Result<FontMetrics, RenderError> {
+        let paint = self.convert_paint(&paint);
+        self.canvas.measure_font(paint).map(convert_font_metrics).map_err(convert_error)
+    }
+
+    fn break_text_vec(&self, max_width: f32, text: &str, paint: Paint) -> Result<Vec<Range<usize>>, RenderError> {
+        let paint = self.convert_paint(&paint);
+        self.canvas.break_text_vec(max_width, text, paint).map_err(convert_error)
+    }
+
+    fn create_image_empty(&mut self, width: usize, height: usize, format: PixelFormat, flags: ImageFlags) -> Result<ImageId, RenderError> {
+        let id = self
+            .canvas
+            .create_image_empty(width, height, convert_pixel_format(format), convert_image_flags(flags))
+            .map_err(convert_error)?;
+
+        Ok(self.add_image(id))
+    }
+
+    fn load_image_mem(&mut self, data: &[u8], flags: ImageFlags) -> Result<ImageId, RenderError> {
+        let id = self.canvas.load_image_mem(data, convert_image_flags(flags)).map_err(convert_error)?;
+
+        Ok(self.add_image(id))
+    }
+
+    fn image_size(&self, id: ImageId) -> Result<(usize, usize), RenderError> {
+        let image = self.images.get(&id).ok_or(RenderError::ImageNotFound)?;
+        self.canvas.image_size(*image).map_err(convert_error)
+    }
+
+    fn delete_image(&mut self, id: ImageId) {
+        if let Some(image) = self.images.remove(&id) {
+            self.canvas.delete_image(image);
+        }
+    }
+
+    fn filter_image(&mut self, target: ImageId, filter: ImageFilter, source: ImageId) {
+        if let (Some(target), Some(source)) = (self.images.get(&target), self.images.get(&source)) {
+            let filter = match filter {
+                ImageFilter::GaussianBlur { sigma } => femtovg::ImageFilter::GaussianBlur { sigma },
+            };
+
+            self.canvas.filter_image(*target, filter, *source);
+        }
+    }
+
+    fn set_render_target(&mut self, target: RenderTarget) {
+        let target = match target {
+            RenderTarget::Screen => femtovg::RenderTarget::Screen,
+            RenderTarget::Image(id) => match self.images.get(&id) {
+                Some(image) => femtovg::RenderTarget::Image(*image),
+                None => femtovg::RenderTarget::Screen,
+            },
+        };
+
+        self.canvas.set_render_target(target);
+    }
+}
+
+// Returns the converted path with the same verbs, converting it if it isn't cached
+fn cached_path<'a>(paths: &'a mut HashMap<u64, CachedPath>, frame: u64, path: &Path) -> &'a mut femtovg::Path {
+    let cached = paths.entry(hash_verbs(path.verbs())).or_insert_with(|| CachedPath {
+        verbs: path.verbs().to_vec(),
+        path: convert_path(path),
+        last_used: frame,
+    });
+
+    // Different paths with the same hash replace each other
+    if cached.verbs != path.verbs() {
+        cached.verbs = path.verbs().to_vec();
+        cached.path = convert_path(path);
+    }
+
+    cached.last_used = frame;
+    &mut cached.path
+}
+
+// Drops the paths which haven't been drawn in the last frames
+fn drop_unused_paths(paths: &mut HashMap<u64, CachedPath>, frame: u64) {
+    paths.retain(|_, cached| frame - cached.last_used <= PATH_CACHE_FRAMES);
+}
+
+fn hash_verbs(verbs: &[Verb]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for verb in verbs {
+        match *verb {
+            Verb::MoveTo(x, y) => (0u8, x.to_bits(), y.to_bits()).hash(&mut hasher),
+            Verb::LineTo(x, y) => (1u8, x.to_bits(), y.to_bits()).hash(&mut hasher),
+            Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                (2u8, c1x.to_bits(), c1y.to_bits(), c2x.to_bits(), c2y.to_bits(), x.to_bits(), y.to_bits()).hash(&mut hasher)
+            }
+            Verb::Close => 3u8.hash(&mut hasher),
+            Verb::Solidity(solidity) => (4u8, solidity == Solidity::Hole).hash(&mut hasher),
+        }
+    }
+
+    hasher.finish()
+}
+
+fn convert_path(path: &Path) -> femtovg::Path {
+    let mut femtovg_path = femtovg::Path::new();
+    for verb in path.verbs() {
+        match *verb {
+            Verb::MoveTo(x, y) => femtovg_path.move_to(x, y),
+            Verb::LineTo(x, y) => femtovg_path.line_to(x, y),
+            Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => femtovg_path.bezier_to(c1x, c1y, c2x, c2y, x, y),
+            Verb::Close => femtovg_path.close(),
+            Verb::Solidity(solidity) => femtovg_path.solidity(convert_solidity(solidity)),
+        }
+    }
+
+    femtovg_path
+}
+
+fn apply_text_settings(femtovg_paint: &mut femtovg::Paint, paint: &Paint, fonts: &[femtovg::FontId]) {
+    let font_ids = paint
+        .font_ids()
+        .filter_map(|font_id| fonts.get(font_id.0).cloned())
+        .collect::<Vec<_>>();
+
+    femtovg_paint.set_font(&font_ids);
+    femtovg_paint.set_font_size(paint.font_size);
+    femtovg_paint.set_text_align(convert_align(paint.text_align));
+    femtovg_paint.set_text_baseline(convert_baseline(paint.text_baseline));
+}
+
+// Converts the text settings of a paint for measuring text with a femtovg text context
+pub(crate) fn text_paint(paint: &Paint, fonts: &[femtovg::FontId]) -> femtovg::Paint {
+    let mut femtovg_paint = femtovg::Paint::default();
+    apply_text_settings(&mut femtovg_paint, paint, fonts);
+    femtovg_paint
+}
+
+pub(crate) fn convert_text_metrics(metrics: femtovg::TextMetrics) -> TextMetrics {
+    TextMetrics {
+        x: metrics.x,
+        y: metrics.y,
+        width: metrics.width(),
+        height: metrics.height(),
+        glyphs: metrics
+            .glyphs
+            .iter()
+            .map(|glyph| GlyphPosition {
+                c: glyph.c,
+                byte_index: glyph.byte_index,
+                x: glyph.x,
+                y: glyph.y,
+                width: glyph.width,
+                height: glyph.height,
+            })
+            .collect(),
+    }
+}
+
+pub(crate) fn convert_font_metrics(metrics: femtovg::FontMetrics) -> FontMetrics {
+    FontMetrics {
+        ascender: metrics.ascender(),
+        descender: metrics.descender(),
+        height: metrics.height(),
+    }
+}
+
+pub(crate) fn convert_error(error: femtovg::ErrorKind) -> RenderError {
+    RenderError::Backend(format!("{:?}", error))
+}
+
+fn convert_align(align: Align) -> femtovg::Align {
+    match align {
+        Align::Left => femtovg::Align::Left,
+        Align::Center => femtovg::Align::Center,
+        Align::Right => femtovg::Align::Right,
+    }
+}
+
+fn convert_baseline(baseline: Baseline) -> femtovg::Baseline {
+    match baseline {
+        Baseline::Top => femtovg::Baseline::Top,
+        Baseline::Middle => femtovg::Baseline::Middle,
+        Baseline::Alphabetic => femtovg::Baseline::Alphabetic,
+        Baseline::Bottom => femtovg::Baseline::Bottom,
+    }
+}
+
+fn convert_blend_factor(factor: BlendFactor) -> femtovg::BlendFactor {
+    match factor {
+        BlendFactor::Zero => femtovg::BlendFactor::Zero,
+        BlendFactor::One => femtovg::BlendFactor::One,
+        BlendFactor::SrcColor => femtovg::BlendFactor::SrcColor,
+        BlendFactor::OneMinusSrcColor => femtovg::BlendFactor::OneMinusSrcColor,
+        BlendFactor::DstColor => femtovg::BlendFactor::DstColor,
+        BlendFactor::OneMinusDstColor => femtovg::BlendFactor::OneMinusDstColor,
+        BlendFactor::SrcAlpha => femtovg::BlendFactor::SrcAlpha,
+        BlendFactor::OneMinusSrcAlpha => femtovg::BlendFactor::OneMinusSrcAlpha,
+        BlendFactor::DstAlpha => femtovg::BlendFactor::DstAlpha,
+        BlendFactor::OneMinusDstAlpha => femtovg::BlendFactor::OneMinusDstAlpha,
+        BlendFactor::SrcAlphaSaturate => femtovg::BlendFactor::SrcAlphaSaturate,
+    }
+}
+
+fn convert_composite_operation(operation: CompositeOperation) -> femtovg::CompositeOperation {
+    match operation {
+        CompositeOperation::SourceOver => femtovg::CompositeOperation::SourceOver,
+        CompositeOperation::SourceIn => femtovg::CompositeOperation::SourceIn,
+        CompositeOperation::SourceOut => femtovg::CompositeOperation::SourceOut,
+        CompositeOperation::Atop => femtovg::CompositeOperation::Atop,
+        CompositeOperation::DestinationOver => femtovg::CompositeOperation::DestinationOver,
+        CompositeOperation::DestinationIn => femtovg::CompositeOperation::DestinationIn,
+        CompositeOperation::DestinationOut => femtovg::CompositeOperation::DestinationOut,
+        CompositeOperation::DestinationAtop => femtovg::CompositeOperation::DestinationAtop,
+        CompositeOperation::Lighter => femtovg::CompositeOperation::Lighter,
+        CompositeOperation::Copy => femtovg::CompositeOperation::Copy,
+        CompositeOperation::Xor => femtovg::CompositeOperation::Xor,
+    }
+}
+
+fn convert_fill_rule(rule: FillRule) -> femtovg::FillRule {
+    match rule {
+        FillRule::EvenOdd => femtovg::FillRule::EvenOdd,
+        FillRule::NonZero => femtovg::FillRule::NonZero,
+    }
+}
+
+fn convert_line_cap(cap: LineCap) -> femtovg::LineCap {
+    match cap {
+        LineCap::Butt => femtovg::LineCap::Butt,
+        LineCap::Round => femtovg::LineCap::Round,
+        LineCap::Square => femtovg::LineCap::Square,
+    }
+}
+
+fn convert_line_join(join: LineJoin) -> femtovg::LineJoin {
+    match join {
+        LineJoin::Miter => femtovg::LineJoin::Miter,
+        LineJoin::Round => femtovg::LineJoin::Round,
+        LineJoin::Bevel => femtovg::LineJoin::Bevel,
+    }
+}
+
+fn convert_solidity(solidity: Solidity) -> femtovg::Solidity {
+    match solidity {
+        Solidity::Solid => femtovg::Solidity::Solid,
+        Solidity::Hole => femtovg::Solidity::Hole,
+    }
+}
+
+fn convert_pixel_format(format: PixelFormat) -> femtovg::PixelFormat {
+    match format {
+        PixelFormat::Rgba8 => femtovg::PixelFormat::Rgba8,
+        PixelFormat::Rgb8 => femtovg::PixelFormat::Rgb8,
+        PixelFormat::Gray8 => femtovg::PixelFormat::Gray8,
+    }
+}
+
+fn convert_image_flags(flags: ImageFlags) -> femtovg::ImageFlags {
+    let mut femtovg_flags = femtovg::ImageFlags::empty();
+    let pairs = [
+        (ImageFlags::GENERATE_MIPMAPS, femtovg::ImageFlags::GENERATE_MIPMAPS),
+        (ImageFlags::REPEAT_X, femtovg::ImageFlags::REPEAT_X),
+        (ImageFlags::REPEAT_Y, femtovg::ImageFlags::REPEAT_Y),
+        (ImageFlags::FLIP_Y, femtovg::ImageFlags::FLIP_Y),
+        (ImageFlags::PREMULTIPLIED, femtovg::ImageFlags::PREMULTIPLIED),
+        (ImageFlags::NEAREST, femtovg::ImageFlags::NEAREST),
+    ];
+
+    for (flag, femtovg_flag) in pairs.iter() {
+        if flags.contains(*flag) {
+            femtovg_flags |= *femtovg_flag;
+        }
+    }
+
+    femtovg_flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A path with the same verbs as a cached path reuses its conversion, and unused paths are dropped on flush
+    #[test]
+    fn path_cache() {
+        let mut paths = HashMap::new();
+
+        for _ in 0..2 {
+            let mut path = Path::new();
+            path.rounded_rect(0.0, 0.0, 10.0, 10.0, 2.0);
+            cached_path(&mut paths, 0, &path);
+        }
+        assert_eq!(paths.len(), 1);
+
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 10.0, 10.0);
+        cached_path(&mut paths, PATH_CACHE_FRAMES + 1, &path);
+        assert_eq!(paths.len(), 2);
+
+        drop_unused_paths(&mut paths, PATH_CACHE_FRAMES + 1);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths.values().next().unwrap().verbs, path.verbs());
+    }
+}
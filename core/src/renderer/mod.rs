@@ -0,0 +1,276 @@
+//! # Renderer
+//!
+//! Widgets draw through the [Renderer] trait rather than a particular graphics library. A backend implements
+//! the trait to rasterise paths, text and images, and the rest of tuix only deals with the backend-independent
+//! [Path], [Paint] and id types defined here.
+//!
+//! The windowing backends use [FemtovgRenderer], which draws with OpenGL via femtovg. A renderer which does
+//! not need a GPU can be used instead by passing it to [EventManager::draw](crate::EventManager::draw).
+
+use bitflags::bitflags;
+
+use crate::Color;
+
+mod path;
+pub use path::*;
+
+mod paint;
+pub use paint::*;
+
+mod text;
+pub use text::*;
+
+mod femtovg_renderer;
+pub use femtovg_renderer::FemtovgRenderer;
+
+/// The horizontal alignment of text relative to its x position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// The vertical alignment of text relative to its y position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Baseline {
+    /// The top of the em square
+    Top,
+    /// The middle of the em square
+    Middle,
+    /// The alphabetic baseline
+    Alphabetic,
+    /// The bottom of the em square
+    Bottom,
+}
+
+/// A factor of a blend function, see [Renderer::global_composite_blend_func]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+    SrcAlphaSaturate,
+}
+
+/// A Porter-Duff composite operation, see [Renderer::global_composite_operation]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositeOperation {
+    SourceOver,
+    SourceIn,
+    SourceOut,
+    Atop,
+    DestinationOver,
+    DestinationIn,
+    DestinationOut,
+    DestinationAtop,
+    Lighter,
+    Copy,
+    Xor,
+}
+
+/// How the inside of a path is determined when it is filled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    EvenOdd,
+    NonZero,
+}
+
+/// How the ends of a stroked line are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+/// How the corners of a stroked path are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// The winding of a sub-path, where holes are cut out of solid shapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solidity {
+    Solid,
+    Hole,
+}
+
+/// Identifies an image created by a [Renderer]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImageId(pub usize);
+
+/// Identifies a font added to a [Renderer] or [TextContext]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FontId(pub usize);
+
+bitflags! {
+    pub struct ImageFlags: u32 {
+        /// Generate mipmaps when the image is created
+        const GENERATE_MIPMAPS = 1;
+        /// Repeat the image horizontally
+        const REPEAT_X = 1 << 1;
+        /// Repeat the image vertically
+        const REPEAT_Y = 1 << 2;
+        /// Flip the image vertically when it is drawn, used for images which are render targets
+        const FLIP_Y = 1 << 3;
+        /// The image data has premultiplied alpha
+        const PREMULTIPLIED = 1 << 4;
+        /// Use nearest-neighbour instead of linear filtering
+        const NEAREST = 1 << 5;
+    }
+}
+
+/// The format of the pixel data of an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Rgba8,
+    Rgb8,
+    Gray8,
+}
+
+/// Where a [Renderer] draws to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderTarget {
+    /// The window or output buffer of the renderer
+    Screen,
+    /// An image created with [Renderer::create_image_empty]
+    Image(ImageId),
+}
+
+/// A filter which can be applied to an image with [Renderer::filter_image]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFilter {
+    GaussianBlur { sigma: f32 },
+}
+
+/// An error returned by a [Renderer]
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderError {
+    /// The image id does not refer to an image of the renderer
+    ImageNotFound,
+    /// The font id does not refer to a font of the renderer
+    FontNotFound,
+    /// Font or image data could not be decoded
+    InvalidData,
+    /// An error from the underlying graphics library
+    Backend(String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::ImageNotFound => write!(f, "Image not found"),
+            RenderError::FontNotFound => write!(f, "Font not found"),
+            RenderError::InvalidData => write!(f, "Invalid font or image data"),
+            RenderError::Backend(message) => write!(f, "Renderer error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// A 2D drawing surface which widgets draw to in [Widget::on_draw](crate::Widget::on_draw).
+///
+/// The methods follow the canvas API of femtovg (and nanovg): a state stack of transform and scissor which is
+/// changed with `save`/`restore`, filled or stroked paths, text drawn with fonts added to the renderer, and
+/// images which can be used as paints or as render targets.
+///
+/// Coordinates are in logical pixels, before the transform is applied.
+pub trait Renderer {
+    /// Resizes the output of the renderer, in physical pixels
+    fn set_size(&mut self, width: u32, height: u32, dpi: f32);
+
+    /// Fills the rectangle of the current render target with a color, ignoring the transform and scissor
+    fn clear_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color);
+
+    /// Submits the drawing commands since the last flush
+    fn flush(&mut self);
+
+    /// Pushes the current transform, scissor and composite operation onto the state stack
+    fn save(&mut self);
+
+    /// Pops the transform, scissor and composite operation from the state stack
+    fn restore(&mut self);
+
+    /// Resets the transform, scissor and composite operation of the current state
+    fn reset(&mut self);
+
+    /// Multiplies the current transform by the matrix [a c e; b d f; 0 0 1]
+    fn set_transform(&mut self, a: f32, b: f32, c: f32, d: f32, e: f32, f: f32);
+
+    fn reset_transform(&mut self);
+
+    fn translate(&mut self, x: f32, y: f32);
+
+    /// Rotates the current transform by an angle in radians
+    fn rotate(&mut self, angle: f32);
+
+    fn scale(&mut self, x: f32, y: f32);
+
+    /// Sets how the colors which are drawn are combined with the colors of the render target.
+    /// The default is `CompositeOperation::SourceOver`.
+    fn global_composite_operation(&mut self, operation: CompositeOperation);
+
+    /// Combines the colors which are drawn with the colors of the render target by multiplying them by
+    /// the source and destination factors and adding them. Colors have premultiplied alpha.
+    fn global_composite_blend_func(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor);
+
+    /// Limits drawing to a rectangle, which is transformed by the current transform
+    fn scissor(&mut self, x: f32, y: f32, width: f32, height: f32);
+
+    /// Limits drawing to the intersection of the current scissor and a rectangle
+    fn intersect_scissor(&mut self, x: f32, y: f32, width: f32, height: f32);
+
+    fn reset_scissor(&mut self);
+
+    /// Fills a path. The path is mutable so that backends can cache data on it.
+    fn fill_path(&mut self, path: &mut Path, paint: Paint);
+
+    /// Strokes a path with the line width, cap and join of the paint
+    fn stroke_path(&mut self, path: &mut Path, paint: Paint);
+
+    /// Adds a font from the data of a ttf or otf file
+    fn add_font_mem(&mut self, data: &[u8]) -> Result<FontId, RenderError>;
+
+    /// Draws a line of text with the fonts, size and alignment of the paint
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError>;
+
+    /// Returns the size and glyph positions of a line of text without drawing it
+    fn measure_text(&self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError>;
+
+    fn measure_font(&self, paint: Paint) -> Result<FontMetrics, RenderError>;
+
+    /// Splits text into lines no wider than max_width, returning the byte range of each line
+    fn break_text_vec(&self, max_width: f32, text: &str, paint: Paint) -> Result<Vec<std::ops::Range<usize>>, RenderError>;
+
+    /// Creates an image with uninitialized contents, e.g. to use as a render target
+    fn create_image_empty(&mut self, width: usize, height: usize, format: PixelFormat, flags: ImageFlags) -> Result<ImageId, RenderError>;
+
+    /// Creates an image from the data of an encoded image file, e.g. a png
+    fn load_image_mem(&mut self, data: &[u8], flags: ImageFlags) -> Result<ImageId, RenderError>;
+
+    /// Returns the width and height of an image in pixels
+    fn image_size(&self, id: ImageId) -> Result<(usize, usize), RenderError>;
+
+    fn delete_image(&mut self, id: ImageId);
+
+    /// Applies a filter to the source image, writing the result to the target image
+    fn filter_image(&mut self, target: ImageId, filter: ImageFilter, source: ImageId);
+
+    /// Redirects drawing to the screen or to an image
+    fn set_render_target(&mut self, target: RenderTarget);
+}
+
+/// The surface which widgets draw to
+pub type Canvas = dyn Renderer;
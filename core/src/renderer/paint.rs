@@ -0,0 +1,235 @@
+use crate::Color;
+
+use super::{Align, Baseline, FillRule, FontId, ImageId, LineCap, LineJoin};
+
+/// The maximum number of color stops in a gradient. Extra stops are ignored.
+pub const MAX_GRADIENT_STOPS: usize = 16;
+
+/// The maximum number of fallback fonts of a [Paint]
+pub const MAX_FONTS: usize = 8;
+
+/// What a shape or text is filled with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaintFlavor {
+    Color(Color),
+    /// An image pattern, where (x, y) and (width, height) are the position and size of one tile of the image
+    Image {
+        id: ImageId,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        angle: f32,
+        alpha: f32,
+    },
+    LinearGradient {
+        start_x: f32,
+        start_y: f32,
+        end_x: f32,
+        end_y: f32,
+        stops: [(f32, Color); MAX_GRADIENT_STOPS],
+        num_stops: usize,
+    },
+    /// A blurred rounded rectangle, used for drop shadows
+    BoxGradient {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+        feather: f32,
+        inner_color: Color,
+        outer_color: Color,
+    },
+}
+
+/// Describes how a [Path](super::Path) or text is filled or stroked by a [Renderer](super::Renderer).
+///
+/// The constructors and setters match those of a femtovg paint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Paint {
+    pub flavor: PaintFlavor,
+    pub line_width: f32,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    pub fill_rule: FillRule,
+    pub anti_alias: bool,
+    pub fonts: [Option<FontId>; MAX_FONTS],
+    pub font_size: f32,
+    pub text_align: Align,
+    pub text_baseline: Baseline,
+}
+
+impl Default for Paint {
+    fn default() -> Self {
+        Self {
+            flavor: PaintFlavor::Color(Color::white()),
+            line_width: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            fill_rule: FillRule::NonZero,
+            anti_alias: true,
+            fonts: [None; MAX_FONTS],
+            font_size: 16.0,
+            text_align: Align::Left,
+            text_baseline: Baseline::Alphabetic,
+        }
+    }
+}
+
+impl Paint {
+    /// Creates a paint with a solid color
+    pub fn color(color: impl Into<Color>) -> Self {
+        Self {
+            flavor: PaintFlavor::Color(color.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a paint which fills with an image, see [PaintFlavor::Image]
+    pub fn image(id: ImageId, x: f32, y: f32, width: f32, height: f32, angle: f32, alpha: f32) -> Self {
+        Self {
+            flavor: PaintFlavor::Image {
+                id,
+                x,
+                y,
+                width,
+                height,
+                angle,
+                alpha,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Creates a linear gradient between two colors
+    pub fn linear_gradient(
+        start_x: f32,
+        start_y: f32,
+        end_x: f32,
+        end_y: f32,
+        start_color: impl Into<Color>,
+        end_color: impl Into<Color>,
+    ) -> Self {
+        let stops: [(f32, Color); 2] = [(0.0, start_color.into()), (1.0, end_color.into())];
+        Self::linear_gradient_stops(start_x, start_y, end_x, end_y, &stops)
+    }
+
+    /// Creates a linear gradient with a list of (position, color) stops, where positions are between 0.0 and 1.0
+    pub fn linear_gradient_stops<C: Into<Color> + Copy>(
+        start_x: f32,
+        start_y: f32,
+        end_x: f32,
+        end_y: f32,
+        stops: &[(f32, C)],
+    ) -> Self {
+        let mut gradient_stops = [(0.0, Color::black()); MAX_GRADIENT_STOPS];
+        let num_stops = stops.len().min(MAX_GRADIENT_STOPS);
+        for (slot, (offset, color)) in gradient_stops.iter_mut().zip(stops.iter()) {
+            *slot = (*offset, (*color).into());
+        }
+
+        Self {
+            flavor: PaintFlavor::LinearGradient {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                stops: gradient_stops,
+                num_stops,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Creates a gradient which fades from the inner color inside the rounded rectangle to the outer color
+    /// over the feather distance
+    pub fn box_gradient(
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+        feather: f32,
+        inner_color: impl Into<Color>,
+        outer_color: impl Into<Color>,
+    ) -> Self {
+        Self {
+            flavor: PaintFlavor::BoxGradient {
+                x,
+                y,
+                width,
+                height,
+                radius,
+                feather,
+                inner_color: inner_color.into(),
+                outer_color: outer_color.into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn set_color(&mut self, color: impl Into<Color>) {
+        self.flavor = PaintFlavor::Color(color.into());
+    }
+
+    pub fn set_line_width(&mut self, width: f32) {
+        self.line_width = width;
+    }
+
+    pub fn set_line_cap(&mut self, cap: LineCap) {
+        self.line_cap = cap;
+    }
+
+    pub fn set_line_join(&mut self, join: LineJoin) {
+        self.line_join = join;
+    }
+
+    pub fn set_fill_rule(&mut self, rule: FillRule) {
+        self.fill_rule = rule;
+    }
+
+    pub fn set_anti_alias(&mut self, value: bool) {
+        self.anti_alias = value;
+    }
+
+    /// Sets the fonts used to draw text, in order of preference
+    pub fn set_font(&mut self, font_ids: &[FontId]) {
+        self.fonts = [None; MAX_FONTS];
+        for (slot, font_id) in self.fonts.iter_mut().zip(font_ids.iter()) {
+            *slot = Some(*font_id);
+        }
+    }
+
+    /// Returns the fonts used to draw text
+    pub fn font_ids(&self) -> impl Iterator<Item = FontId> + '_ {
+        self.fonts.iter().filter_map(|font_id| *font_id)
+    }
+
+    pub fn set_font_size(&mut self, size: f32) {
+        self.font_size = size;
+    }
+
+    pub fn set_text_align(&mut self, align: Align) {
+        self.text_align = align;
+    }
+
+    pub fn set_text_baseline(&mut self, baseline: Baseline) {
+        self.text_baseline = baseline;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gradients with more than the maximum number of stops keep the first stops
+    #[test]
+    fn gradient_stops_limit() {
+        let stops = vec![(0.5, Color::white()); MAX_GRADIENT_STOPS + 4];
+        match Paint::linear_gradient_stops(0.0, 0.0, 1.0, 0.0, &stops).flavor {
+            PaintFlavor::LinearGradient { num_stops, .. } => assert_eq!(num_stops, MAX_GRADIENT_STOPS),
+            _ => panic!("Expected a linear gradient"),
+        }
+    }
+}
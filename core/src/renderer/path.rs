@@ -0,0 +1,245 @@
+use std::f32::consts::PI;
+
+use super::Solidity;
+
+// Length proportional to radius of a cubic bezier handle for 90deg arcs.
+const KAPPA90: f32 = 0.5522847493;
+
+/// A single command of a [Path]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verb {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    BezierTo(f32, f32, f32, f32, f32, f32),
+    Close,
+    /// Sets the winding of the current sub-path, used to cut holes in a shape
+    Solidity(Solidity),
+}
+
+/// A shape made of one or more sub-paths which can be filled or stroked by a [Renderer](crate::Renderer).
+///
+/// The methods match those of a femtovg path so that drawing code can be moved between backends without changes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    verbs: Vec<Verb>,
+    // The current point, used by commands which continue from the last point
+    last: (f32, f32),
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the commands which make up the path
+    pub fn verbs(&self) -> &[Verb] {
+        &self.verbs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.verbs.is_empty()
+    }
+
+    /// Starts a new sub-path at the point
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.verbs.push(Verb::MoveTo(x, y));
+        self.last = (x, y);
+    }
+
+    /// Adds a line from the current point to the point
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.verbs.push(Verb::LineTo(x, y));
+        self.last = (x, y);
+    }
+
+    /// Adds a cubic bezier from the current point to the point
+    pub fn bezier_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.verbs.push(Verb::BezierTo(c1x, c1y, c2x, c2y, x, y));
+        self.last = (x, y);
+    }
+
+    /// Adds a quadratic bezier from the current point to the point
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last;
+        self.bezier_to(
+            x0 + 2.0 / 3.0 * (cx - x0),
+            y0 + 2.0 / 3.0 * (cy - y0),
+            x + 2.0 / 3.0 * (cx - x),
+            y + 2.0 / 3.0 * (cy - y),
+            x,
+            y,
+        );
+    }
+
+    /// Closes the current sub-path with a line to its first point
+    pub fn close(&mut self) {
+        self.verbs.push(Verb::Close);
+    }
+
+    /// Sets the winding of the current sub-path
+    pub fn solidity(&mut self, solidity: Solidity) {
+        self.verbs.push(Verb::Solidity(solidity));
+    }
+
+    /// Adds a circular arc centered on (cx, cy) from angle a0 to a1 in radians.
+    /// A `Solidity::Hole` direction draws the arc clockwise, otherwise it is drawn counter-clockwise.
+    pub fn arc(&mut self, cx: f32, cy: f32, r: f32, a0: f32, a1: f32, dir: Solidity) {
+        let clockwise = dir == Solidity::Hole;
+
+        let mut da = a1 - a0;
+
+        if clockwise {
+            if da.abs() >= PI * 2.0 {
+                da = PI * 2.0;
+            } else {
+                while da < 0.0 {
+                    da += PI * 2.0;
+                }
+            }
+        } else if da.abs() >= PI * 2.0 {
+            da = -PI * 2.0;
+        } else {
+            while da > 0.0 {
+                da -= PI * 2.0;
+            }
+        }
+
+        // Split the arc into segments of at most 90 degrees
+        let divisions = ((da.abs() / (PI * 0.5)) + 0.5).floor().max(1.0).min(5.0) as usize;
+        let half_angle = (da / divisions as f32) / 2.0;
+        let mut kappa = (4.0 / 3.0 * (1.0 - half_angle.cos()) / half_angle.sin()).abs();
+
+        if !clockwise {
+            kappa = -kappa;
+        }
+
+        let (mut px, mut py, mut ptanx, mut ptany) = (0.0, 0.0, 0.0, 0.0);
+
+        for i in 0..=divisions {
+            let angle = a0 + da * (i as f32 / divisions as f32);
+            let (dx, dy) = (angle.cos(), angle.sin());
+            let x = cx + dx * r;
+            let y = cy + dy * r;
+            let tanx = -dy * r * kappa;
+            let tany = dx * r * kappa;
+
+            if i == 0 {
+                if self.verbs.is_empty() {
+                    self.move_to(x, y);
+                } else {
+                    self.line_to(x, y);
+                }
+            } else {
+                self.bezier_to(px + ptanx, py + ptany, x - tanx, y - tany, x, y);
+            }
+
+            px = x;
+            py = y;
+            ptanx = tanx;
+            ptany = tany;
+        }
+    }
+
+    /// Adds a closed rectangle sub-path
+    pub fn rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.move_to(x, y);
+        self.line_to(x, y + h);
+        self.line_to(x + w, y + h);
+        self.line_to(x + w, y);
+        self.close();
+    }
+
+    /// Adds a closed rectangle sub-path with the same radius for each corner
+    pub fn rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, r: f32) {
+        self.rounded_rect_varying(x, y, w, h, r, r, r, r);
+    }
+
+    /// Adds a closed rectangle sub-path with a different radius for each corner
+    pub fn rounded_rect_varying(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        rad_top_left: f32,
+        rad_top_right: f32,
+        rad_bottom_right: f32,
+        rad_bottom_left: f32,
+    ) {
+        if rad_top_left < 0.1 && rad_top_right < 0.1 && rad_bottom_right < 0.1 && rad_bottom_left < 0.1 {
+            self.rect(x, y, w, h);
+            return;
+        }
+
+        let halfw = w.abs() * 0.5;
+        let halfh = h.abs() * 0.5;
+
+        let rx_bl = rad_bottom_left.min(halfw) * w.signum();
+        let ry_bl = rad_bottom_left.min(halfh) * h.signum();
+        let rx_br = rad_bottom_right.min(halfw) * w.signum();
+        let ry_br = rad_bottom_right.min(halfh) * h.signum();
+        let rx_tr = rad_top_right.min(halfw) * w.signum();
+        let ry_tr = rad_top_right.min(halfh) * h.signum();
+        let rx_tl = rad_top_left.min(halfw) * w.signum();
+        let ry_tl = rad_top_left.min(halfh) * h.signum();
+
+        let k = 1.0 - KAPPA90;
+
+        self.move_to(x, y + ry_tl);
+        self.line_to(x, y + h - ry_bl);
+        self.bezier_to(x, y + h - ry_bl * k, x + rx_bl * k, y + h, x + rx_bl, y + h);
+        self.line_to(x + w - rx_br, y + h);
+        self.bezier_to(x + w - rx_br * k, y + h, x + w, y + h - ry_br * k, x + w, y + h - ry_br);
+        self.line_to(x + w, y + ry_tr);
+        self.bezier_to(x + w, y + ry_tr * k, x + w - rx_tr * k, y, x + w - rx_tr, y);
+        self.line_to(x + rx_tl, y);
+        self.bezier_to(x + rx_tl * k, y, x, y + ry_tl * k, x, y + ry_tl);
+        self.close();
+    }
+
+    /// Adds a closed ellipse sub-path
+    pub fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32) {
+        self.move_to(cx - rx, cy);
+        self.bezier_to(cx - rx, cy + ry * KAPPA90, cx - rx * KAPPA90, cy + ry, cx, cy + ry);
+        self.bezier_to(cx + rx * KAPPA90, cy + ry, cx + rx, cy + ry * KAPPA90, cx + rx, cy);
+        self.bezier_to(cx + rx, cy - ry * KAPPA90, cx + rx * KAPPA90, cy - ry, cx, cy - ry);
+        self.bezier_to(cx - rx * KAPPA90, cy - ry, cx - rx, cy - ry * KAPPA90, cx - rx, cy);
+        self.close();
+    }
+
+    /// Adds a closed circle sub-path
+    pub fn circle(&mut self, cx: f32, cy: f32, r: f32) {
+        self.ellipse(cx, cy, r, r);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Quadratic curves are stored as the equivalent cubic curve
+    #[test]
+    fn quad_to_cubic() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(3.0, 3.0, 6.0, 0.0);
+        assert_eq!(path.verbs()[1], Verb::BezierTo(2.0, 2.0, 4.0, 2.0, 6.0, 0.0));
+    }
+
+    /// An arc starts with a move and ends on the end angle
+    #[test]
+    fn arc_end_point() {
+        let mut path = Path::new();
+        path.arc(0.0, 0.0, 10.0, 0.0, PI, Solidity::Hole);
+
+        assert_eq!(path.verbs()[0], Verb::MoveTo(10.0, 0.0));
+        match path.verbs().last() {
+            Some(Verb::BezierTo(_, _, _, _, x, y)) => {
+                assert!((x + 10.0).abs() < 1e-4);
+                assert!(y.abs() < 1e-4);
+            }
+
+            _ => panic!("Expected the arc to end with a curve"),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use super::{femtovg_renderer, FontId, Paint, RenderError};
+
+/// The position of a glyph of measured or drawn text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphPosition {
+    /// The character of the glyph
+    pub c: char,
+    /// The index of the first byte of the character in the text
+    pub byte_index: usize,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// The bounds and glyph positions of a line of text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextMetrics {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub glyphs: Vec<GlyphPosition>,
+}
+
+impl TextMetrics {
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+}
+
+/// Vertical metrics of a font at the size of a paint
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FontMetrics {
+    pub ascender: f32,
+    pub descender: f32,
+    pub height: f32,
+}
+
+impl FontMetrics {
+    pub fn ascender(&self) -> f32 {
+        self.ascender
+    }
+
+    pub fn descender(&self) -> f32 {
+        self.descender
+    }
+
+    /// The distance between the baselines of two lines of text
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+}
+
+/// Measures text without a [Renderer](super::Renderer), so that widgets can lay out text outside of drawing.
+///
+/// Fonts must be added in the same order as they are added to the renderer so that font ids match.
+#[derive(Default)]
+pub struct TextContext {
+    context: femtovg::TextContext,
+    fonts: Vec<femtovg::FontId>,
+}
+
+impl TextContext {
+    /// Adds a font from the data of a ttf or otf file
+    pub fn add_font_mem(&mut self, data: &[u8]) -> Result<FontId, RenderError> {
+        let id = self.context.add_font_mem(data).map_err(femtovg_renderer::convert_error)?;
+        self.fonts.push(id);
+        Ok(FontId(self.fonts.len() - 1))
+    }
+
+    pub fn measure_text(&self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError> {
+        let paint = femtovg_renderer::text_paint(&paint, &self.fonts);
+        self.context
+            .measure_text(x, y, text, paint)
+            .map(femtovg_renderer::convert_text_metrics)
+            .map_err(femtovg_renderer::convert_error)
+    }
+
+    pub fn measure_font(&self, paint: Paint) -> Result<FontMetrics, RenderError> {
+        let paint = femtovg_renderer::text_paint(&paint, &self.fonts);
+        self.context
+            .measure_font(paint)
+            .map(femtovg_renderer::convert_font_metrics)
+            .map_err(femtovg_renderer::convert_error)
+    }
+
+    /// Splits text into lines no wider than max_width, returning the byte range of each line
+    pub fn break_text_vec(&self, max_width: f32, text: &str, paint: Paint) -> Result<Vec<Range<usize>>, RenderError> {
+        let paint = femtovg_renderer::text_paint(&paint, &self.fonts);
+        self.context
+            .break_text_vec(max_width, text, paint)
+            .map_err(femtovg_renderer::convert_error)
+    }
+}
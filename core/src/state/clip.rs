@@ -0,0 +1,160 @@
+use crate::Path;
+
+use crate::{BorderCornerShape, BoundingBox, Entity};
+
+// Length proportional to radius of a cubic bezier handle for 90deg arcs.
+const KAPPA90: f32 = 0.5522847493;
+
+/// A clip region with rounded (or bevelled) corners.
+///
+/// Entities which are clipped by a clip widget with a border radius are drawn into a layer which is then
+/// composited through this shape. Corners are ordered top-left, top-right, bottom-right, bottom-left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundedClip {
+    /// The clip widget which the shape belongs to
+    pub entity: Entity,
+    /// The bounds of the clip widget inside its border
+    pub bounds: BoundingBox,
+    pub radii: [f32; 4],
+    pub shapes: [BorderCornerShape; 4],
+}
+
+impl RoundedClip {
+    // Returns the corner radii limited to half of the width and height of the bounds
+    fn clamped_radii(&self) -> [f32; 4] {
+        let max_radius = 0.5 * self.bounds.w.min(self.bounds.h).max(0.0);
+        let mut radii = self.radii;
+        for radius in radii.iter_mut() {
+            *radius = radius.max(0.0).min(max_radius);
+        }
+
+        radii
+    }
+
+    /// Returns true if the point is inside the clip shape
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let BoundingBox { x: bx, y: by, w, h } = self.bounds;
+
+        if x < bx || y < by || x >= bx + w || y >= by + h {
+            return false;
+        }
+
+        let radii = self.clamped_radii();
+
+        for index in 0..4 {
+            let radius = radii[index];
+            if radius <= 0.0 {
+                continue;
+            }
+
+            // Distance of the point from the corner of the bounds, measured towards the center
+            let (dx, dy) = match index {
+                0 => (x - bx, y - by),
+                1 => (bx + w - x, y - by),
+                2 => (bx + w - x, by + h - y),
+                _ => (x - bx, by + h - y),
+            };
+
+            if dx >= radius || dy >= radius {
+                continue;
+            }
+
+            let inside = match self.shapes[index] {
+                BorderCornerShape::Round => {
+                    let (cx, cy) = (radius - dx, radius - dy);
+                    cx * cx + cy * cy <= radius * radius
+                }
+
+                BorderCornerShape::Bevel => dx + dy >= radius,
+            };
+
+            if !inside {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Returns a path around the clip shape
+    pub fn path(&self) -> Path {
+        let mut path = Path::new();
+        self.add_to_path(&mut path);
+        path
+    }
+
+    /// Adds the clip shape to a path as a new sub-path
+    pub fn add_to_path(&self, path: &mut Path) {
+        let BoundingBox { x, y, w, h } = self.bounds;
+        let [tl, tr, br, bl] = self.clamped_radii();
+
+        path.move_to(x, y + tl);
+        path.line_to(x, y + h - bl);
+        self.corner(path, 3, (x, y + h - bl), (x + bl, y + h), (x, y + h));
+        path.line_to(x + w - br, y + h);
+        self.corner(path, 2, (x + w - br, y + h), (x + w, y + h - br), (x + w, y + h));
+        path.line_to(x + w, y + tr);
+        self.corner(path, 1, (x + w, y + tr), (x + w - tr, y), (x + w, y));
+        path.line_to(x + tl, y);
+        self.corner(path, 0, (x + tl, y), (x, y + tl), (x, y));
+        path.close();
+    }
+
+    // Adds a corner from start to end, with the control points pulled towards the corner point of the bounds
+    fn corner(&self, path: &mut Path, index: usize, start: (f32, f32), end: (f32, f32), corner: (f32, f32)) {
+        if start == end {
+            return;
+        }
+
+        match self.shapes[index] {
+            BorderCornerShape::Round => {
+                let c1 = (start.0 + (corner.0 - start.0) * KAPPA90, start.1 + (corner.1 - start.1) * KAPPA90);
+                let c2 = (end.0 + (corner.0 - end.0) * KAPPA90, end.1 + (corner.1 - end.1) * KAPPA90);
+                path.bezier_to(c1.0, c1.1, c2.0, c2.1, end.0, end.1);
+            }
+
+            BorderCornerShape::Bevel => path.line_to(end.0, end.1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(radius: f32, shape: BorderCornerShape) -> RoundedClip {
+        RoundedClip {
+            entity: Entity::null(),
+            bounds: BoundingBox { x: 10.0, y: 10.0, w: 100.0, h: 50.0 },
+            radii: [radius; 4],
+            shapes: [shape; 4],
+        }
+    }
+
+    /// Points in the corner outside of the radius are not inside the clip
+    #[test]
+    fn round_corners() {
+        let clip = clip(20.0, BorderCornerShape::Round);
+        assert!(!clip.contains(11.0, 11.0));
+        assert!(!clip.contains(108.0, 58.0));
+        assert!(clip.contains(16.0, 16.0));
+        assert!(clip.contains(60.0, 11.0));
+        assert!(!clip.contains(5.0, 30.0));
+    }
+
+    /// Bevelled corners cut the corner with a straight line
+    #[test]
+    fn bevel_corners() {
+        let clip = clip(20.0, BorderCornerShape::Bevel);
+        assert!(!clip.contains(15.0, 15.0));
+        assert!(clip.contains(21.0, 21.0));
+    }
+
+    /// Radii larger than the bounds are limited to half of the smallest side
+    #[test]
+    fn clamped_radius() {
+        let clip = clip(1000.0, BorderCornerShape::Round);
+        assert!(clip.contains(60.0, 35.0));
+        assert!(!clip.contains(12.0, 12.0));
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+
+use crate::storage::animatable_set::AnimatableSet;
+use crate::{BoundingBox, Entity, Interpolator, State, Units};
+
+/// Records which entities have changed since the window was last drawn.
+///
+/// Entities are marked when they are restyled, relayed out, animated or redrawn with `entity.redraw(state)`.
+/// When the window is drawn, the regions covered by the marked entities (before and after the change) become
+/// damage rectangles, and only the entities which overlap those rectangles are drawn again.
+/// Marking the root entity, e.g. with `Entity::root().redraw(state)`, damages the whole window.
+#[derive(Debug, Clone)]
+pub struct Damage {
+    entities: HashSet<Entity>,
+    full: bool,
+    // Whether only the damaged regions are redrawn
+    partial: bool,
+    // Whether repainted regions are flashed on top of the window
+    debug: bool,
+}
+
+impl Default for Damage {
+    fn default() -> Self {
+        Self {
+            entities: HashSet::new(),
+            full: true,
+            partial: true,
+            debug: false,
+        }
+    }
+}
+
+impl Damage {
+    /// Marks an entity as needing to be redrawn. Marking the root or a null entity damages the whole window.
+    ///
+    /// Entities are still recorded when the whole window is damaged, so that only the retained layers
+    /// which they are drawn into are repainted.
+    pub fn mark(&mut self, entity: Entity) {
+        if entity == Entity::root() || entity == Entity::null() {
+            self.full = true;
+        } else {
+            self.entities.insert(entity);
+        }
+    }
+
+    /// Marks the whole window as needing to be redrawn
+    pub fn mark_all(&mut self) {
+        self.full = true;
+    }
+
+    /// Returns true if the whole window needs to be redrawn
+    pub fn is_full(&self) -> bool {
+        self.full || !self.partial
+    }
+
+    /// Returns true if nothing has changed since the window was last drawn
+    pub fn is_empty(&self) -> bool {
+        !self.is_full() && self.entities.is_empty()
+    }
+
+    /// Returns the entities which have changed since the window was last drawn
+    pub fn entities(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.iter()
+    }
+
+    /// Sets whether only the damaged regions of the window are redrawn (the default), or the whole window
+    pub fn set_partial_redraw(&mut self, partial: bool) {
+        self.partial = partial;
+        self.full = true;
+    }
+
+    /// Sets whether the regions which are repainted are flashed on top of the window, to help find unnecessary redraws
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+        self.full = true;
+    }
+
+    pub fn is_debug(&self) -> bool {
+        self.debug
+    }
+
+    // Marks the entities with a playing animation
+    pub(crate) fn mark_animating<T>(&mut self, set: &AnimatableSet<T>)
+    where
+        T: 'static + Default + Clone + Interpolator + PartialEq + std::fmt::Debug,
+    {
+        for entity in set.animating_entities() {
+            self.mark(entity);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entities.clear();
+        self.full = false;
+    }
+}
+
+// Resolves a length to pixels, with percentages relative to the width of the entity
+fn resolve_length(units: Units, width: f32) -> f32 {
+    match units {
+        Units::Pixels(val) => val,
+        Units::Percentage(val) => width * (val / 100.0),
+        _ => 0.0,
+    }
+}
+
+/// Returns the smallest box containing both boxes
+pub(crate) fn union_bounds(a: BoundingBox, b: BoundingBox) -> BoundingBox {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    BoundingBox {
+        x,
+        y,
+        w: (a.x + a.w).max(b.x + b.w) - x,
+        h: (a.y + a.h).max(b.y + b.h) - y,
+    }
+}
+
+/// Returns the overlap of two boxes, or None if they don't overlap
+pub(crate) fn intersect_bounds(a: BoundingBox, b: BoundingBox) -> Option<BoundingBox> {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+    let w = (a.x + a.w).min(b.x + b.w) - x;
+    let h = (a.y + a.h).min(b.y + b.h) - y;
+
+    if w > 0.0 && h > 0.0 {
+        Some(BoundingBox { x, y, w, h })
+    } else {
+        None
+    }
+}
+
+/// Returns the region of the window which an entity draws into, including its shadows, outline and transform,
+/// or None if the entity draws nothing.
+pub(crate) fn visual_bounds(state: &State, entity: Entity) -> Option<BoundingBox> {
+    let bounds = state.data.get_bounds(entity);
+
+    // Shadows and outlines are drawn outside of the bounds
+    let mut left = 0.0f32;
+    let mut right = 0.0f32;
+    let mut top = 0.0f32;
+    let mut bottom = 0.0f32;
+
+    if let Some(shadows) = state.style.box_shadow.get(entity) {
+        for shadow in shadows.iter().filter(|shadow| !shadow.inset) {
+            let h_offset = resolve_length(shadow.horizontal_offset, bounds.w);
+            let v_offset = resolve_length(shadow.vertical_offset, bounds.w);
+            let extent = resolve_length(shadow.blur_radius, bounds.w).max(0.0)
+                + resolve_length(shadow.spread_radius, bounds.w).max(0.0);
+
+            left = left.max(extent - h_offset);
+            right = right.max(extent + h_offset);
+            top = top.max(extent - v_offset);
+            bottom = bottom.max(extent + v_offset);
+        }
+    }
+
+    if state.style.outer_shadow_color.get(entity).is_some() {
+        let h_offset = resolve_length(state.style.outer_shadow_h_offset.get(entity).cloned().unwrap_or_default(), bounds.w);
+        let v_offset = resolve_length(state.style.outer_shadow_v_offset.get(entity).cloned().unwrap_or_default(), bounds.w);
+        // The shadow image is blurred with a kernel 2.5 times the blur radius
+        let extent = 2.5 * resolve_length(state.style.outer_shadow_blur.get(entity).cloned().unwrap_or_default(), bounds.w).max(0.0);
+
+        left = left.max(extent - h_offset);
+        right = right.max(extent + h_offset);
+        top = top.max(extent - v_offset);
+        bottom = bottom.max(extent + v_offset);
+    }
+
+    let outline_width = resolve_length(state.style.outline_width.get(entity).cloned().unwrap_or_default(), bounds.w);
+    if outline_width > 0.0 {
+        let outline_offset = resolve_length(state.style.outline_offset.get(entity).cloned().unwrap_or_default(), bounds.w);
+        let extent = (outline_width + outline_offset).max(0.0);
+        left = left.max(extent);
+        right = right.max(extent);
+        top = top.max(extent);
+        bottom = bottom.max(extent);
+    }
+
+    // Allow for anti-aliased edges
+    let x0 = bounds.x - left - 1.0;
+    let y0 = bounds.y - top - 1.0;
+    let x1 = bounds.x + bounds.w + right + 1.0;
+    let y1 = bounds.y + bounds.h + bottom + 1.0;
+
+    // Transform the corners and take the box around them
+    let transform = state.data.get_transform(entity);
+    let corners = [
+        transform.transform_point(x0, y0),
+        transform.transform_point(x1, y0),
+        transform.transform_point(x1, y1),
+        transform.transform_point(x0, y1),
+    ];
+
+    let min_x = corners.iter().map(|p| p.0).fold(f32::INFINITY, f32::min);
+    let min_y = corners.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+    let max_x = corners.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max);
+    let max_y = corners.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+
+    let visual = BoundingBox {
+        x: min_x.floor(),
+        y: min_y.floor(),
+        w: max_x.ceil() - min_x.floor(),
+        h: max_y.ceil() - min_y.floor(),
+    };
+
+    // Entities are scissored to their clip region
+    intersect_bounds(visual, state.data.get_clip_region(entity))
+}
+
+/// Merges overlapping rectangles. If there are more than `max` rectangles left, they are merged into one.
+pub(crate) fn merge_rects(mut rects: Vec<BoundingBox>, max: usize) -> Vec<BoundingBox> {
+    let mut merged = true;
+    while merged {
+        merged = false;
+        'outer: for i in 0..rects.len() {
+            for j in (i + 1)..rects.len() {
+                if intersect_bounds(rects[i], rects[j]).is_some() {
+                    let other = rects.swap_remove(j);
+                    rects[i] = union_bounds(rects[i], other);
+                    merged = true;
+                    break 'outer;
+                }
+            }
+        }
+    }
+
+    if rects.len() > max {
+        let first = rects[0];
+        return vec![rects.into_iter().fold(first, union_bounds)];
+    }
+
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> BoundingBox {
+        BoundingBox { x, y, w, h }
+    }
+
+    /// Overlapping rectangles are merged and separate rectangles are kept apart until there are too many
+    #[test]
+    fn merge_damage_rects() {
+        let rects = vec![rect(0.0, 0.0, 10.0, 10.0), rect(5.0, 5.0, 10.0, 10.0), rect(50.0, 50.0, 5.0, 5.0)];
+
+        let merged = merge_rects(rects.clone(), 4);
+        assert_eq!(merged.len(), 2);
+        assert!(merged.contains(&rect(0.0, 0.0, 15.0, 15.0)));
+
+        assert_eq!(merge_rects(rects, 1), vec![rect(0.0, 0.0, 55.0, 55.0)]);
+    }
+
+    /// Marking the root damages the whole window, and entities are still recorded for their layers
+    #[test]
+    fn mark_root() {
+        let mut damage = Damage::default();
+        damage.clear();
+        assert!(damage.is_empty());
+
+        damage.mark(Entity::new(1, 0));
+        assert!(!damage.is_full());
+        assert_eq!(damage.entities().count(), 1);
+
+        damage.mark(Entity::root());
+        assert!(damage.is_full());
+
+        damage.mark(Entity::new(2, 0));
+        assert_eq!(damage.entities().count(), 2);
+    }
+}
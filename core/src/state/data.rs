@@ -2,12 +2,13 @@ use std::collections::HashMap;
 use std::default;
 use std::fmt::Debug;
 
-use femtovg::ImageId;
+use crate::ImageId;
 use morphorm::GeometryChanged;
 use crate::Display;
 use crate::Entity;
+use crate::RoundedClip;
 
-use crate::style::Visibility;
+use crate::style::{BlendMode, Visibility};
 use crate::style::Transform2D;
 
 use crate::storage::sparse_set::SparseSet;
@@ -41,7 +42,7 @@ impl std::ops::Add for Pos {
 }
 
 /// Respresents an axis-aligned bounding box of an entity.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct BoundingBox {
     pub x: f32,
     pub y: f32,
@@ -65,6 +66,16 @@ pub(crate) struct Size {
 
 
 
+/// How an entity and its descendants are composited as a group.
+///
+/// A group is drawn into an offscreen image, which is then drawn behind the entities after it
+/// with the opacity and blend mode of the group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Group {
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+}
+
 impl Default for BoundingBox {
     fn default() -> Self {
         Self {
@@ -101,6 +112,7 @@ pub struct CachedData {
     pub(crate) visibility: SparseSet<Visibility>,
     pub(crate) display: SparseSet<Display>,
     pub(crate) opacity: SparseSet<f32>,
+    group: SparseSet<Option<Group>>,
     // TODO - combine hoverable and focusable with a bitflag
     pub(crate) abilities: SparseSet<Abilities>,
 
@@ -111,6 +123,7 @@ pub struct CachedData {
 
     //pub(crate) prev_size: SparseSet<Pos>,
     clip_region: SparseSet<BoundingBox>,
+    rounded_clip: SparseSet<Option<RoundedClip>>,
 
     // Transform
     rotate: SparseSet<f32>,
@@ -153,6 +166,7 @@ impl CachedData {
         self.child_max.insert(entity, (0.0, 0.0))?;
 
         self.opacity.insert(entity, 1.0)?;
+        self.group.insert(entity, None)?;
 
         self.rotate.insert(entity, 0.0)?;
         self.scale.insert(entity, (1.0, 1.0))?;
@@ -162,6 +176,7 @@ impl CachedData {
         self.z_index.insert(entity, 0)?;
 
         self.clip_region.insert(entity, Default::default())?;
+        self.rounded_clip.insert(entity, None)?;
         self.space.insert(entity, Default::default())?;
         self.size.insert(entity, Default::default())?;
         self.cross_stretch_sum.insert(entity, Default::default())?;
@@ -233,6 +248,7 @@ impl CachedData {
         self.child_max.remove(entity);
 
         self.opacity.remove(entity);
+        self.group.remove(entity);
 
         self.rotate.remove(entity);
         self.scale.remove(entity);
@@ -242,6 +258,7 @@ impl CachedData {
         self.z_index.remove(entity);
 
         self.clip_region.remove(entity);
+        self.rounded_clip.remove(entity);
         self.space.remove(entity);
         self.size.remove(entity);
         self.cross_stretch_sum.remove(entity);
@@ -378,6 +395,37 @@ impl CachedData {
             .unwrap()
     }
 
+    /// Returns the rounded clip shape of the entity, if it is clipped by a clip widget with rounded corners.
+    ///
+    /// The rounded clip is applied in addition to the rectangular clip region.
+    pub fn get_rounded_clip(&self, entity: Entity) -> Option<RoundedClip> {
+        self.rounded_clip
+            .get(entity)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the rounded clip shapes of the entity, from the innermost.
+    ///
+    /// A rounded clip widget which is itself clipped by an outer rounded clip widget adds the outer shape,
+    /// so the entity is only visible where all of the shapes intersect.
+    pub fn get_rounded_clips(&self, entity: Entity) -> Vec<RoundedClip> {
+        let mut clips: Vec<RoundedClip> = Vec::new();
+        let mut next = self.get_rounded_clip(entity);
+
+        while let Some(clip) = next {
+            // A clip widget which clips itself would otherwise repeat forever
+            if clips.iter().any(|inner| inner.entity == clip.entity) {
+                break;
+            }
+
+            clips.push(clip);
+            next = self.get_rounded_clip(clip.entity);
+        }
+
+        clips
+    }
+
     /// Returns the Z index of the entity.
     ///
     /// Entities can specify a z-index with `entity.set_z_index(state, value)`. 
@@ -455,10 +503,19 @@ impl CachedData {
     // }
 
     /// Returns the opacity of the entity.
+    /// Returns the opacity which the entity is drawn with.
+    ///
+    /// This is the product of the opacities of the entity and its ancestors, excluding the opacity
+    /// of any group it is drawn in, which is applied when the group is composited.
     pub fn get_opacity(&self, entity: Entity) -> f32 {
         self.opacity.get(entity).cloned().unwrap()
     }
 
+    /// Returns how the entity and its descendants are composited, or None if they are drawn directly
+    pub fn get_group(&self, entity: Entity) -> Option<Group> {
+        self.group.get(entity).cloned().unwrap_or_default()
+    }
+
     pub(crate) fn get_horizontal_free_space(&self, entity: Entity) -> f32 {
         self.horizontal_free_space
             .get(entity)
@@ -625,6 +682,12 @@ impl CachedData {
         }
     }
 
+    pub fn set_rounded_clip(&mut self, entity: Entity, val: Option<RoundedClip>) {
+        if let Some(rounded_clip) = self.rounded_clip.get_mut(entity) {
+            *rounded_clip = val;
+        }
+    }
+
     pub(crate) fn set_z_index(&mut self, entity: Entity, val: i32) {
         if let Some(z_index) = self.z_index.get_mut(entity) {
             *z_index = val;
@@ -775,6 +838,12 @@ impl CachedData {
         }
     }
 
+    pub(crate) fn set_group(&mut self, entity: Entity, val: Option<Group>) {
+        if let Some(group) = self.group.get_mut(entity) {
+            *group = val;
+        }
+    }
+
     pub(crate) fn set_rotate(&mut self, entity: Entity, val: f32) {
         if let Some(transform) = self.transform.get_mut(entity) {
             let mut t = Transform2D::identity();
@@ -0,0 +1,454 @@
+use crate::{Entity, Event, PositionType, PropSet, Propagation, PseudoClass, State, TreeExt, Units};
+
+use std::any::Any;
+use std::fmt;
+use std::rc::Rc;
+
+// The z-order of the drag ghost, which is drawn above popups and overlays
+const GHOST_Z_ORDER: i32 = 2000;
+
+/// The data dragged from a source entity, which is shared by all of the events of a drag.
+#[derive(Clone)]
+pub struct DragPayload {
+    source: Entity,
+    data: Rc<dyn Any>,
+}
+
+impl DragPayload {
+    pub fn new<T: Any>(source: Entity, data: T) -> Self {
+        Self {
+            source,
+            data: Rc::new(data),
+        }
+    }
+
+    /// Returns the entity which started the drag
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+
+    /// Returns the dragged data if it has the given type, so that a drop target can check what is being dragged
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.data.downcast_ref::<T>()
+    }
+
+    /// Returns true if the dragged data has the given type
+    pub fn is<T: Any>(&self) -> bool {
+        self.data.is::<T>()
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DragPayload").field("source", &self.source).finish()
+    }
+}
+
+/// The events of a drag, see [DragState].
+#[derive(Debug, Clone)]
+pub enum DragEvent {
+    /// Sent to the source when the pointer moves further than the drag threshold, which starts the drag
+    DragStart(DragPayload),
+    /// Sent up the tree from the entity under the pointer when the pointer moves onto it. A drop target which
+    /// can take the payload calls `state.accept_drag(entity)` in response
+    DragEnter(DragPayload),
+    /// Sent to the drop target when the pointer moves over it, with the position of the pointer
+    DragOver(DragPayload, f32, f32),
+    /// Sent to the drop target when the pointer moves off of it or the drag is cancelled
+    DragLeave,
+    /// Sent to the drop target when the button is released over it
+    Drop(DragPayload),
+    /// Sent to the source when the drag ends, with whether the payload was dropped onto a target
+    DragEnd { dropped: bool },
+}
+
+/// The state of drag and drop between entities.
+///
+/// A source starts a drag with [State::start_drag], usually in response to a mouse press, which becomes active
+/// once the pointer moves further than the drag threshold. While the drag is active, the entity under the pointer
+/// is sent a [DragEvent::DragEnter], and the entity which accepts it with [State::accept_drag] becomes the drop
+/// target, which has the `:drag-over` pseudo-class and receives [DragEvent::DragOver] and [DragEvent::Drop].
+/// Moving onto another entity, including a child of the drop target, leaves the target and enters the new entity.
+/// Releasing the button ends the drag and pressing Escape cancels it.
+pub struct DragState {
+    threshold: f32,
+    payload: Option<DragPayload>,
+    // The position of the pointer when the drag was started
+    start: (f32, f32),
+    // Whether the pointer has moved past the threshold
+    active: bool,
+    // The entity under the pointer which was last sent a DragEnter
+    over: Entity,
+    target: Entity,
+    ghost: Entity,
+    // The position of the pointer relative to the source when the drag started, which the ghost keeps
+    ghost_offset: (f32, f32),
+}
+
+impl Default for DragState {
+    fn default() -> Self {
+        Self {
+            threshold: 4.0,
+            payload: None,
+            start: (0.0, 0.0),
+            active: false,
+            over: Entity::null(),
+            target: Entity::null(),
+            ghost: Entity::null(),
+            ghost_offset: (0.0, 0.0),
+        }
+    }
+}
+
+impl DragState {
+    /// Sets the distance in logical pixels which the pointer must move before a drag starts. Defaults to 4px.
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    /// Returns true if a drag has moved past the threshold and not yet ended
+    pub fn is_dragging(&self) -> bool {
+        self.active
+    }
+
+    /// Returns the payload of the started or active drag
+    pub fn payload(&self) -> Option<&DragPayload> {
+        self.payload.as_ref()
+    }
+
+    /// Returns the entity which accepted the drag, or null if there isn't one
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    /// Returns the ghost of the drag, or null if there isn't one
+    pub fn ghost(&self) -> Entity {
+        self.ghost
+    }
+
+    // Forgets a removed entity. A drag whose source is removed carries on, as the payload is still valid
+    pub(crate) fn remove_entity(&mut self, entity: Entity) {
+        if self.target == entity {
+            self.target = Entity::null();
+        }
+
+        if self.over == entity {
+            self.over = Entity::null();
+        }
+
+        if self.ghost == entity {
+            self.ghost = Entity::null();
+        }
+    }
+}
+
+pub(crate) fn start_drag(state: &mut State, payload: DragPayload) {
+    if state.drag.active {
+        return;
+    }
+
+    end_drag(state);
+
+    state.drag.payload = Some(payload);
+    state.drag.start = (state.mouse.cursorx, state.mouse.cursory);
+}
+
+pub(crate) fn set_drag_ghost(state: &mut State, ghost: Entity) {
+    if state.drag.ghost != ghost && state.drag.ghost != Entity::null() {
+        state.remove(state.drag.ghost);
+    }
+
+    state.drag.ghost = ghost;
+
+    if ghost == Entity::null() {
+        return;
+    }
+
+    ghost
+        .set_position_type(state, PositionType::SelfDirected)
+        .set_z_order(state, GHOST_Z_ORDER);
+
+    // The ghost follows the pointer, so it mustn't hide the entities under the pointer from hovering
+    for entity in ghost.branch_iter(&state.tree.clone()) {
+        entity.set_hoverable(state, false);
+    }
+
+    move_ghost(state);
+}
+
+pub(crate) fn accept_drag(state: &mut State, target: Entity) {
+    if !state.drag.active || state.drag.target == target {
+        return;
+    }
+
+    reject_drag(state);
+
+    state.drag.target = target;
+    set_drag_over(state, target, true);
+}
+
+pub(crate) fn reject_drag(state: &mut State) {
+    let target = std::mem::replace(&mut state.drag.target, Entity::null());
+
+    if target != Entity::null() {
+        set_drag_over(state, target, false);
+    }
+}
+
+/// Updates the drag after the pointer has moved and the hovered entity has been updated.
+///
+/// Starts the drag if the pointer has moved past the threshold, moves the ghost and sends the enter, leave and
+/// over events.
+pub(crate) fn drag_moved(state: &mut State) {
+    let payload = match state.drag.payload.clone() {
+        Some(payload) => payload,
+        None => return,
+    };
+
+    let (x, y) = (state.mouse.cursorx, state.mouse.cursory);
+
+    if !state.drag.active {
+        let (start_x, start_y) = state.drag.start;
+        let (dx, dy) = (x - start_x, y - start_y);
+
+        if (dx * dx + dy * dy).sqrt() < state.drag.threshold {
+            return;
+        }
+
+        let source = payload.source;
+        state.drag.active = true;
+        state.drag.ghost_offset = (
+            start_x - state.data.get_posx(source),
+            start_y - state.data.get_posy(source),
+        );
+
+        state.insert_event(
+            Event::new(DragEvent::DragStart(payload.clone()))
+                .target(source)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    move_ghost(state);
+
+    // Moving between the children of the target stays over the target, without leaving and entering it again
+    let target = state.drag.target;
+    let within_target = target != Entity::null() && state.hovered.is_descendant_of(&state.tree, target);
+
+    if state.hovered != state.drag.over && !within_target {
+        if target != Entity::null() {
+            state.insert_event(Event::new(DragEvent::DragLeave).target(target).propagate(Propagation::Direct));
+            reject_drag(state);
+        }
+
+        state.drag.over = state.hovered;
+        state.insert_event(
+            Event::new(DragEvent::DragEnter(payload))
+                .target(state.hovered)
+                .propagate(Propagation::Up),
+        );
+    } else if target != Entity::null() {
+        state.drag.over = state.hovered;
+        state.insert_event(
+            Event::new(DragEvent::DragOver(payload, x, y))
+                .target(target)
+                .propagate(Propagation::Direct),
+        );
+    }
+}
+
+/// Ends the drag when the button is released, dropping the payload onto the target if there is one
+pub(crate) fn drag_released(state: &mut State) {
+    if state.drag.active {
+        if let Some(payload) = state.drag.payload.clone() {
+            let target = state.drag.target;
+            let dropped = target != Entity::null();
+
+            if dropped {
+                state.insert_event(
+                    Event::new(DragEvent::Drop(payload.clone()))
+                        .target(target)
+                        .propagate(Propagation::Direct),
+                );
+            }
+
+            state.insert_event(
+                Event::new(DragEvent::DragEnd { dropped })
+                    .target(payload.source)
+                    .propagate(Propagation::Direct),
+            );
+        }
+    }
+
+    end_drag(state);
+}
+
+pub(crate) fn cancel_drag(state: &mut State) {
+    if state.drag.active {
+        if let Some(payload) = state.drag.payload.clone() {
+            let target = state.drag.target;
+            if target != Entity::null() {
+                state.insert_event(Event::new(DragEvent::DragLeave).target(target).propagate(Propagation::Direct));
+            }
+
+            state.insert_event(
+                Event::new(DragEvent::DragEnd { dropped: false })
+                    .target(payload.source)
+                    .propagate(Propagation::Direct),
+            );
+        }
+    }
+
+    end_drag(state);
+}
+
+// Clears the drag, removing the ghost and the pseudo-class of the target
+fn end_drag(state: &mut State) {
+    reject_drag(state);
+
+    if state.drag.ghost != Entity::null() {
+        state.remove(state.drag.ghost);
+    }
+
+    state.drag = DragState {
+        threshold: state.drag.threshold,
+        ..DragState::default()
+    };
+}
+
+// Positions the ghost at the pointer, offset by where the source was grabbed
+fn move_ghost(state: &mut State) {
+    let ghost = state.drag.ghost;
+    if ghost == Entity::null() || !state.drag.active {
+        return;
+    }
+
+    let (offset_x, offset_y) = state.drag.ghost_offset;
+    let left = state.mouse.cursorx - offset_x;
+    let top = state.mouse.cursory - offset_y;
+
+    ghost
+        .set_left(state, Units::Pixels(left))
+        .set_top(state, Units::Pixels(top));
+}
+
+fn set_drag_over(state: &mut State, entity: Entity, value: bool) {
+    if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(entity) {
+        pseudo_classes.set(PseudoClass::DRAG_OVER, value);
+    }
+
+    Entity::root().restyle(state);
+    Entity::root().redraw(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drag_events(state: &mut State) -> Vec<(Entity, DragEvent)> {
+        state
+            .event_queue
+            .drain(..)
+            .filter_map(|mut event| {
+                let target = event.target;
+                event.message.downcast::<DragEvent>().cloned().map(|drag_event| (target, drag_event))
+            })
+            .collect()
+    }
+
+    fn move_to(state: &mut State, x: f32, y: f32) {
+        state.mouse.cursorx = x;
+        state.mouse.cursory = y;
+        drag_moved(state);
+    }
+
+    /// A drag starts once the pointer moves past the threshold, and is dropped onto the entity which accepted it
+    #[test]
+    fn threshold_and_drop() {
+        let mut state = State::new();
+        let source = state.add(Entity::root());
+        let target = state.add(Entity::root());
+
+        state.start_drag(source, 5u32);
+        move_to(&mut state, 3.0, 0.0);
+        assert!(!state.drag.is_dragging());
+        assert!(drag_events(&mut state).is_empty());
+
+        move_to(&mut state, 3.0, 4.0);
+        assert!(state.drag.is_dragging());
+        let events = drag_events(&mut state);
+        assert!(matches!(&events[0], (entity, DragEvent::DragStart(_)) if *entity == source));
+        assert!(matches!(&events[1], (entity, DragEvent::DragEnter(payload)) if *entity == Entity::root() && payload.get::<u32>() == Some(&5)));
+
+        state.hovered = target;
+        move_to(&mut state, 10.0, 10.0);
+        state.accept_drag(target);
+        assert!(state.style.pseudo_classes.get(target).unwrap().contains(PseudoClass::DRAG_OVER));
+
+        move_to(&mut state, 11.0, 10.0);
+        drag_released(&mut state);
+        assert!(!state.drag.is_dragging());
+        assert!(!state.style.pseudo_classes.get(target).unwrap().contains(PseudoClass::DRAG_OVER));
+
+        let events = drag_events(&mut state);
+        assert!(matches!(&events[1], (entity, DragEvent::DragOver(_, x, _)) if *entity == target && *x == 11.0));
+        assert!(matches!(&events[2], (entity, DragEvent::Drop(payload)) if *entity == target && payload.source() == source));
+        assert!(matches!(&events[3], (entity, DragEvent::DragEnd { dropped: true }) if *entity == source));
+    }
+
+    /// Releasing before the threshold is a click, and cancelling an active drag leaves the target without a drop
+    #[test]
+    fn click_and_cancel() {
+        let mut state = State::new();
+        let source = state.add(Entity::root());
+
+        state.start_drag(source, ());
+        drag_released(&mut state);
+        assert!(state.drag.payload().is_none());
+        assert!(drag_events(&mut state).is_empty());
+
+        state.start_drag(source, ());
+        move_to(&mut state, 10.0, 0.0);
+        state.accept_drag(Entity::root());
+        state.cancel_drag();
+
+        let events = drag_events(&mut state);
+        assert!(matches!(&events[2], (entity, DragEvent::DragLeave) if *entity == Entity::root()));
+        assert!(matches!(&events[3], (entity, DragEvent::DragEnd { dropped: false }) if *entity == source));
+        assert_eq!(state.drag.target(), Entity::null());
+    }
+
+    /// Moving between the children of the target keeps the drag over it, instead of leaving and entering it again
+    #[test]
+    fn target_children() {
+        let mut state = State::new();
+        let source = state.add(Entity::root());
+        let target = state.add(Entity::root());
+        let first = state.add(target);
+        let second = state.add(target);
+
+        state.start_drag(source, ());
+        state.hovered = first;
+        move_to(&mut state, 10.0, 0.0);
+        state.accept_drag(target);
+        drag_events(&mut state);
+
+        state.hovered = second;
+        move_to(&mut state, 12.0, 0.0);
+        assert_eq!(state.drag.target(), target);
+        assert!(state.style.pseudo_classes.get(target).unwrap().contains(PseudoClass::DRAG_OVER));
+
+        let events = drag_events(&mut state);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], (entity, DragEvent::DragOver(..)) if *entity == target));
+
+        // Leaving the target for another entity leaves it
+        state.hovered = source;
+        move_to(&mut state, 30.0, 0.0);
+        assert_eq!(state.drag.target(), Entity::null());
+
+        let events = drag_events(&mut state);
+        assert!(matches!(&events[0], (entity, DragEvent::DragLeave) if *entity == target));
+        assert!(matches!(&events[1], (entity, DragEvent::DragEnter(_)) if *entity == source));
+    }
+}
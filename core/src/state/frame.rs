@@ -0,0 +1,215 @@
+use std::time::{Duration, Instant};
+
+/// The time spent producing a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// The total time from the start of the frame until it was drawn, including event handling
+    pub frame_time: Duration,
+    /// The time spent restyling
+    pub style: Duration,
+    /// The time spent laying out
+    pub layout: Duration,
+    /// The time spent drawing
+    pub draw: Duration,
+}
+
+/// Decides when the application needs to wake up to produce a frame, and records how long frames take.
+///
+/// Backends sleep until an OS event arrives or until the time returned by [next_wake](FrameScheduler::next_wake).
+/// A playing animation requests the next frame, which is aligned to the frame interval so that animations tick once per
+/// display refresh, and anything else which needs to run at a later time can ask to be woken with [wake_at](FrameScheduler::wake_at).
+/// When nothing is animating and no wake-up is pending the application sleeps until the next OS event.
+#[derive(Debug, Clone)]
+pub struct FrameScheduler {
+    frame_interval: Duration,
+    // The time the last frame was started, which animation frames are aligned to
+    last_frame: Option<Instant>,
+    // The start of the frame in progress
+    frame_start: Option<Instant>,
+    // Whether an animation needs another frame
+    animating: bool,
+    // Times at which the application should be woken
+    deadlines: Vec<Instant>,
+    current: FrameStats,
+    last: FrameStats,
+}
+
+impl Default for FrameScheduler {
+    fn default() -> Self {
+        Self {
+            frame_interval: Duration::from_secs(1) / 60,
+            last_frame: None,
+            frame_start: None,
+            animating: false,
+            deadlines: Vec::new(),
+            current: FrameStats::default(),
+            last: FrameStats::default(),
+        }
+    }
+}
+
+impl FrameScheduler {
+    /// Sets the rate, in frames per second, at which animations are ticked. Defaults to 60.
+    pub fn set_frame_rate(&mut self, frame_rate: f32) {
+        if frame_rate > 0.0 {
+            self.frame_interval = Duration::from_secs_f64(1.0 / frame_rate as f64);
+        }
+    }
+
+    /// Returns the time between animation frames
+    pub fn frame_interval(&self) -> Duration {
+        self.frame_interval
+    }
+
+    /// Requests another frame, e.g. to tick an animation
+    pub fn request_frame(&mut self) {
+        self.animating = true;
+    }
+
+    /// Requests that the application is woken at the given time
+    pub fn wake_at(&mut self, time: Instant) {
+        self.deadlines.push(time);
+    }
+
+    /// Returns true if a frame has been requested or a wake-up is pending
+    pub fn is_active(&self) -> bool {
+        self.animating || !self.deadlines.is_empty()
+    }
+
+    /// Returns the time at which the application should next be woken, or None if it can sleep until the next OS event.
+    ///
+    /// A requested frame is aligned to the frame interval, starting from the last frame. If frames have been missed the
+    /// next frame is the next interval after `now`, so animations don't try to catch up.
+    pub fn next_wake(&self, now: Instant) -> Option<Instant> {
+        let next_frame = if self.animating {
+            Some(match self.last_frame {
+                Some(last_frame) if now > last_frame => {
+                    let interval = self.frame_interval.as_secs_f64().max(f64::EPSILON);
+                    let missed = ((now - last_frame).as_secs_f64() / interval).floor() as u32;
+                    last_frame + self.frame_interval * (missed + 1)
+                }
+
+                Some(last_frame) => last_frame + self.frame_interval,
+
+                None => now,
+            })
+        } else {
+            None
+        };
+
+        next_frame.into_iter().chain(self.deadlines.iter().cloned()).min()
+    }
+
+    /// Called by the backend when it starts processing a frame. Clears the requested frame and any wake-ups which are due.
+    pub fn begin_frame(&mut self, now: Instant) {
+        if self.frame_start.is_none() {
+            self.frame_start = Some(now);
+        }
+
+        self.last_frame = Some(now);
+        self.animating = false;
+        self.deadlines.retain(|deadline| *deadline > now);
+    }
+
+    /// Called by the backend when it has finished a frame, after drawing if the window needed drawing.
+    ///
+    /// Frames which didn't restyle, lay out or draw anything are not recorded.
+    pub fn end_frame(&mut self, now: Instant) {
+        let mut stats = std::mem::take(&mut self.current);
+
+        if let Some(frame_start) = self.frame_start.take() {
+            stats.frame_time = now.saturating_duration_since(frame_start);
+        }
+
+        if stats.style + stats.layout + stats.draw > Duration::default() {
+            stats.frame_time = stats.frame_time.max(stats.style + stats.layout + stats.draw);
+            self.last = stats;
+        }
+    }
+
+    /// Returns the statistics of the last recorded frame
+    pub fn stats(&self) -> FrameStats {
+        self.last
+    }
+
+    pub(crate) fn record_style(&mut self, duration: Duration) {
+        self.current.style += duration;
+    }
+
+    pub(crate) fn record_layout(&mut self, duration: Duration) {
+        self.current.layout += duration;
+    }
+
+    pub(crate) fn record_draw(&mut self, duration: Duration) {
+        self.current.draw += duration;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Animation frames are aligned to the frame interval and missed frames are skipped
+    #[test]
+    fn next_animation_frame() {
+        let mut scheduler = FrameScheduler::default();
+        scheduler.set_frame_rate(100.0);
+
+        let start = Instant::now();
+        assert_eq!(scheduler.next_wake(start), None);
+
+        scheduler.begin_frame(start);
+        scheduler.request_frame();
+        assert_eq!(scheduler.next_wake(start), Some(start + Duration::from_millis(10)));
+        assert_eq!(
+            scheduler.next_wake(start + Duration::from_millis(25)),
+            Some(start + Duration::from_millis(30))
+        );
+
+        scheduler.begin_frame(start + Duration::from_millis(10));
+        assert!(!scheduler.is_active());
+    }
+
+    /// A wake-up is returned until a frame begins after it
+    #[test]
+    fn wake_at() {
+        let mut scheduler = FrameScheduler::default();
+
+        let start = Instant::now();
+        let deadline = start + Duration::from_secs(1);
+        scheduler.wake_at(deadline);
+        assert_eq!(scheduler.next_wake(start), Some(deadline));
+
+        scheduler.begin_frame(start);
+        assert_eq!(scheduler.next_wake(start), Some(deadline));
+
+        scheduler.begin_frame(deadline);
+        assert_eq!(scheduler.next_wake(deadline), None);
+    }
+
+    /// Only frames which did some work replace the recorded statistics
+    #[test]
+    fn frame_stats() {
+        let mut scheduler = FrameScheduler::default();
+
+        let start = Instant::now();
+        scheduler.begin_frame(start);
+        scheduler.record_style(Duration::from_millis(2));
+        scheduler.record_draw(Duration::from_millis(3));
+        scheduler.end_frame(start + Duration::from_millis(8));
+
+        assert_eq!(
+            scheduler.stats(),
+            FrameStats {
+                frame_time: Duration::from_millis(8),
+                style: Duration::from_millis(2),
+                layout: Duration::default(),
+                draw: Duration::from_millis(3),
+            }
+        );
+
+        scheduler.begin_frame(start + Duration::from_millis(20));
+        scheduler.end_frame(start + Duration::from_millis(21));
+        assert_eq!(scheduler.stats().frame_time, Duration::from_millis(8));
+    }
+}
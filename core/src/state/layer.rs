@@ -1,8 +1,18 @@
-use femtovg::ImageId;
-
+use crate::ImageId;
 
 
+/// The entities drawn with a particular z-index.
+///
+/// If any of the entities have a `layer: retained` style, the layer is drawn into an offscreen image
+/// which is only repainted when the layer is dirty, and otherwise drawn to the window as it is.
+#[derive(Debug, Clone, Copy, Default)]
 pub struct Layer {
+    // The number of entities with the z-index of the layer
     pub count: u32,
-    pub image: ImageId,
-}
\ No newline at end of file
+    // The offscreen image of a retained layer, created when the layer is first drawn
+    pub image: Option<ImageId>,
+    // Whether the layer is cached in an offscreen image
+    pub retained: bool,
+    // Whether the entities of the layer have changed since the image was drawn
+    pub dirty: bool,
+}
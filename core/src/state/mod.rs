@@ -21,14 +21,42 @@ pub use resource::*;
 mod layer;
 pub use layer::*;
 
+mod clip;
+pub use clip::*;
+
+mod damage;
+pub use damage::Damage;
+pub(crate) use damage::{intersect_bounds, merge_rects, visual_bounds};
+
+mod frame;
+pub use frame::{FrameScheduler, FrameStats};
+
+mod profiler;
+pub use profiler::{FrameRecord, ProfiledSystem, Profiler, SystemRecord};
+
+mod shortcuts;
+pub use shortcuts::{Binding, Chord, Shortcut, ShortcutConflict, ShortcutScope, Shortcuts};
+pub(crate) use shortcuts::handle_shortcut;
+
+mod drag;
+pub use drag::{DragEvent, DragPayload, DragState};
+pub(crate) use drag::{cancel_drag, drag_moved, drag_released};
+
+mod timer;
+pub use timer::{TimerHandle, Timers};
+
+mod tasks;
+pub use tasks::{TaskHandle, Tasks};
+
 
 use crate::storage::shared_set::SharedSet;
-use crate::{AnimationBuilder, BindEvent, Builder, Color, Event, EventHandler, PropSet, Propagation, Rule, Style};
-use crate::{WindowEvent, Tree, TreeExt};
+use crate::{AnimationBuilder, BindEvent, BoundingBox, Builder, Color, Event, EventHandler, PropSet, Propagation, Rule, Style, Units};
+use crate::{EventProxy, EventReceiver, UniqueKey, WindowEvent, Tree, TreeExt};
+use crate::{Svg, SvgError};
 
 use crate::IdManager;
 
-use femtovg::{TextContext};
+use crate::{RenderTarget, TextContext};
 
 use std::collections::{HashMap, VecDeque};
 
@@ -125,6 +153,9 @@ pub struct State {
     // Queue of events
     pub event_queue: VecDeque<Event>,
 
+    // The latest copy of each queued unique event, earlier copies are dropped when the queue is taken
+    pub(crate) unique_events: HashMap<UniqueKey, usize>,
+
     // pub fonts: Fonts, //TODO - Replace with resource manager
 
     pub resource_manager: ResourceManager, //TODO
@@ -138,6 +169,39 @@ pub struct State {
 
     pub layers: HashMap<i32, Layer>,
 
+    // The entities which have changed since the window was last drawn
+    pub damage: Damage,
+
+    // Decides when the next frame is needed and records how long frames take
+    pub scheduler: FrameScheduler,
+
+    // Measures the time spent in each system, see [Profiler]
+    pub profiler: Profiler,
+
+    // The keyboard shortcuts of the application, see [Shortcuts]
+    pub shortcuts: Shortcuts,
+
+    // The drag and drop between entities, see [DragState]
+    pub drag: DragState,
+
+    // The events scheduled to be sent at a later time, see [Timers]
+    pub timers: Timers,
+
+    // The futures running in the background for entities, see [Tasks]
+    pub tasks: Tasks,
+
+    // The target which widgets are currently drawn into, e.g. a layer for a rounded clip region
+    pub render_target: RenderTarget,
+
+    // Sends events from other threads, and receives them on the UI thread
+    event_proxy: EventProxy,
+    event_receiver: EventReceiver,
+
+    // The ratio of physical to logical pixels reported by the window backend
+    scale_factor: f32,
+    // Replaces the scale factor reported by the backend, e.g. to test HiDPI rendering
+    scale_factor_override: Option<f32>,
+
     pub listeners: FnvHashMap<Entity, Box<dyn Fn(&mut dyn EventHandler, &mut State, Entity, &mut Event)>>,
 }
 
@@ -162,6 +226,8 @@ impl State {
 
         style.default_font = "roboto".to_string();
 
+        let (event_proxy, event_receiver) = EventProxy::channel();
+
         let mut resource_manager =ResourceManager::new();
         resource_manager.themes.push(STYLE.to_string());
 
@@ -179,6 +245,7 @@ impl State {
             //callbacks: FnvHashMap::default(),
             event_handlers: FnvHashMap::default(),
             event_queue: VecDeque::new(),
+            unique_events: HashMap::new(),
             removed_entities: Vec::new(),
             // fonts: Fonts {
             //     regular: None,
@@ -195,6 +262,21 @@ impl State {
             text_context: TextContext::default(),
 
             layers: HashMap::default(),
+            damage: Damage::default(),
+            scheduler: FrameScheduler::default(),
+            profiler: Profiler::default(),
+            shortcuts: Shortcuts::with_default_bindings(),
+            drag: DragState::default(),
+            timers: Timers::default(),
+            tasks: Tasks::default(),
+
+            render_target: RenderTarget::Screen,
+
+            event_proxy,
+            event_receiver,
+
+            scale_factor: 1.0,
+            scale_factor_override: None,
 
             listeners: FnvHashMap::default(),
         }
@@ -271,11 +353,109 @@ impl State {
         self.resource_manager.fonts.insert(name.to_owned(), FontOrId::Font(data.to_vec()));
     }
 
+    /// Add an image from memory to the application
+    ///
+    /// The data should contain an encoded image file (e.g. PNG or JPEG). The image is uploaded
+    /// to the canvas before the next draw and can then be referred to by name, e.g. by the `Image` widget.
+    /// Adding an image with the name of an existing image replaces it. An image which can't be decoded is dropped,
+    /// and a `WindowEvent::ImageLoadFailed` is sent to every entity.
+    pub fn add_image_mem(&mut self, name: &str, data: &[u8]) {
+        let previous = self.resource_manager.images.insert(name.to_owned(), ImageOrId::Image(data.to_vec()));
+
+        if let Some(ImageOrId::Id(id, _)) = previous {
+            self.resource_manager.unused_images.push(id);
+        }
+    }
+
+    /// Add an image file to the application
+    pub fn add_image(&mut self, name: &str, path: &str) -> Result<(), std::io::Error> {
+        let data = std::fs::read(path)?;
+        self.add_image_mem(name, &data);
+
+        Ok(())
+    }
+
+    /// Add an SVG document from a string to the application
+    ///
+    /// The document is parsed immediately and can then be referred to by name, e.g. by the `Icon` widget.
+    pub fn add_svg_mem(&mut self, name: &str, data: &str) -> Result<(), SvgError> {
+        let svg = Svg::parse(data)?;
+        self.resource_manager.svgs.insert(name.to_owned(), svg);
+
+        Ok(())
+    }
+
+    /// Add an SVG file to the application
+    pub fn add_svg(&mut self, name: &str, path: &str) -> Result<(), SvgError> {
+        let data = std::fs::read_to_string(path).map_err(|err| SvgError::Io(err.to_string()))?;
+        self.add_svg_mem(name, &data)
+    }
+
     /// Sets the global default font for the application
     pub fn set_default_font(&mut self, name: &str) {
         self.style.default_font = name.to_string();
     }
 
+    /// Returns the ratio of physical to logical pixels of the window.
+    ///
+    /// Styles and layout are in logical pixels, which are multiplied by the scale factor when the window is drawn.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor_override.unwrap_or(self.scale_factor)
+    }
+
+    /// Sets the scale factor of the window. This is called by the window backend when the window is created
+    /// or moved to a display with a different scale factor.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        let previous = self.scale_factor();
+        self.scale_factor = scale_factor;
+        self.scale_factor_changed(previous);
+    }
+
+    /// Overrides the scale factor reported by the window backend, or removes the override if `None`.
+    ///
+    /// # Examples
+    /// Draws the window as it would appear on a display with twice the pixel density:
+    /// ```
+    /// state.set_scale_factor_override(Some(2.0));
+    /// ```
+    pub fn set_scale_factor_override(&mut self, scale_factor: Option<f32>) {
+        let previous = self.scale_factor();
+        self.scale_factor_override = scale_factor;
+        self.scale_factor_changed(previous);
+    }
+
+    fn scale_factor_changed(&mut self, previous: f32) {
+        let scale_factor = self.scale_factor();
+        if scale_factor == previous {
+            return;
+        }
+
+        // The window keeps its size in physical pixels, so its size in logical pixels changes
+        let width = self.data.get_width(Entity::root()) * previous / scale_factor;
+        let height = self.data.get_height(Entity::root()) * previous / scale_factor;
+
+        self.style.width.insert(Entity::root(), Units::Pixels(width));
+        self.style.height.insert(Entity::root(), Units::Pixels(height));
+        self.data.set_width(Entity::root(), width);
+        self.data.set_height(Entity::root(), height);
+
+        let mut bounding_box = BoundingBox::default();
+        bounding_box.w = width;
+        bounding_box.h = height;
+        self.data.set_clip_region(Entity::root(), bounding_box);
+
+        // Widgets with cached images may need to rasterise them again
+        self.insert_event(
+            Event::new(WindowEvent::ScaleFactorChanged(scale_factor))
+                .target(Entity::root())
+                .propagate(Propagation::All),
+        );
+
+        Entity::root().restyle(self);
+        Entity::root().relayout(self);
+        Entity::root().redraw(self);
+    }
+
     // Removes all style data and then reloads the stylesheets
     // TODO change the error type to allow for parsing errors
     pub fn reload_styles(&mut self) -> Result<(), std::io::Error> {
@@ -283,14 +463,6 @@ impl State {
             return Ok(());
         }
 
-        for rule in self.style.rules.iter() {
-            self.style.rule_manager.destroy(rule.id);
-        }
-
-        self.style.rules.clear();
-        
-        self.style.remove_all();
-
         let mut overall_theme = String::new();
 
         // Reload the stored themes
@@ -299,12 +471,20 @@ impl State {
             overall_theme += theme;
         }
 
-        // Reload the stored stylesheets
+        // Reload the stored stylesheets, before removing the current styles so that they're kept if one can't be read
         for stylesheet in self.resource_manager.stylesheets.iter() {
             let theme = std::fs::read_to_string(stylesheet)?;
             overall_theme += &theme;
         }
 
+        for rule in self.style.rules.iter() {
+            self.style.rule_manager.destroy(rule.id);
+        }
+
+        self.style.rules.clear();
+        
+        self.style.remove_all();
+
         self.style.parse_theme(&overall_theme);
 
         Entity::root().restyle(self);
@@ -317,20 +497,38 @@ impl State {
     /// Insert a new event into the application event queue
     ///
     /// Inserts a new event into the application event queue that will be processed on the next event loop.
-    /// If the event unique flag is set to true, only the most recent event of the same type will exist in the queue.
+    /// If the event unique flag is set to true, only the most recent copy of the event is dispatched. Earlier copies
+    /// stay in the queue until it is flushed, so that inserting a unique event doesn't search the queue.
     ///
     /// # Examples
     /// ```
     /// state.insert_event(Event::new(WindowEvent::WindowClose));
     /// ```
-    pub fn insert_event(&mut self, event: Event) {
-        // if event.unique {
-        //     self.event_queue.retain(|e| e != &event);
-        // }
+    pub fn insert_event(&mut self, mut event: Event) {
+        if event.unique {
+            let copies = self.unique_events.entry(event.unique_key()).or_insert(0);
+            *copies += 1;
+            event.copy = *copies;
+        }
 
         self.event_queue.push_back(event);
     }
 
+    /// Removes the queued events, dropping unique events which were replaced by a later copy
+    pub(crate) fn take_events(&mut self) -> Vec<Event> {
+        let unique_events = std::mem::take(&mut self.unique_events);
+
+        self.event_queue
+            .drain(..)
+            .filter(|event| {
+                !event.unique
+                    || unique_events
+                        .get(&event.unique_key())
+                        .map_or(true, |latest| *latest == event.copy)
+            })
+            .collect()
+    }
+
     // This should probably be moved to state.mouse
     pub fn capture(&mut self, entity: Entity) {
         //println!("CAPTURE: {}", entity);
@@ -387,6 +585,153 @@ impl State {
         }  
     }
 
+    /// Starts dragging data from the source entity, usually in response to a mouse press.
+    ///
+    /// The drag becomes active once the pointer moves further than the drag threshold, when the source is sent a
+    /// [DragEvent::DragStart], and ends when the button is released. Releasing before then is an ordinary click.
+    ///
+    /// # Examples
+    /// ```
+    /// state.start_drag(entity, TrackId(3));
+    /// ```
+    pub fn start_drag<T: std::any::Any>(&mut self, source: Entity, data: T) {
+        drag::start_drag(self, DragPayload::new(source, data));
+    }
+
+    /// Sets the entity which follows the pointer during the drag, usually in response to [DragEvent::DragStart].
+    ///
+    /// The ghost should be a child of the window. It is positioned by the drag, drawn above the other entities and
+    /// ignored by hovering, and is removed when the drag ends.
+    pub fn set_drag_ghost(&mut self, ghost: Entity) {
+        drag::set_drag_ghost(self, ghost);
+    }
+
+    /// Makes the entity the drop target of the active drag, usually in response to [DragEvent::DragEnter].
+    ///
+    /// The drop target has the `:drag-over` pseudo-class and is sent [DragEvent::DragOver] and [DragEvent::Drop].
+    pub fn accept_drag(&mut self, target: Entity) {
+        drag::accept_drag(self, target);
+    }
+
+    /// Clears the drop target of the active drag, e.g. when the pointer moves over a part of the target which
+    /// can't take the payload
+    pub fn reject_drag(&mut self) {
+        drag::reject_drag(self);
+    }
+
+    /// Cancels the drag without dropping the payload, as pressing Escape does
+    pub fn cancel_drag(&mut self) {
+        drag::cancel_drag(self);
+    }
+
+    /// Sends an event after a delay, e.g. to show a tooltip or debounce an input.
+    ///
+    /// The timer is cancelled if the target of the event is removed before it's due.
+    ///
+    /// # Examples
+    /// ```
+    /// state.schedule(Event::new(TooltipEvent::Show).target(entity), Duration::from_millis(500));
+    /// ```
+    pub fn schedule(&mut self, event: Event, delay: std::time::Duration) -> TimerHandle {
+        let due = std::time::Instant::now() + delay;
+        self.scheduler.wake_at(due);
+
+        self.timers.insert(due, event, None)
+    }
+
+    /// Sends a message to the target every period until the timer is cancelled, e.g. to blink a caret or repeat a key.
+    ///
+    /// Events can't be copied, so the message is cloned for each tick, as for a shortcut binding. The timer is
+    /// cancelled if the target is removed. Use [interval_with](State::interval_with) to build other events.
+    ///
+    /// # Examples
+    /// ```
+    /// let blink = state.interval(entity, TextboxEvent::Blink, Duration::from_millis(500));
+    /// ```
+    pub fn interval<M: crate::Message + Clone>(&mut self, target: Entity, message: M, period: std::time::Duration) -> TimerHandle {
+        self.interval_with(move || Event::new(message.clone()).target(target), period)
+    }
+
+    /// Sends an event built by the closure every period until the timer is cancelled.
+    ///
+    /// The event is built again for each tick, as events can't be copied. The timer is cancelled if the target of
+    /// the event is removed.
+    ///
+    /// # Examples
+    /// ```
+    /// let blink = state.interval_with(move || Event::new(TextboxEvent::Blink).target(entity).propagate(Propagation::Direct), Duration::from_millis(500));
+    /// ```
+    pub fn interval_with<F>(&mut self, event: F, period: std::time::Duration) -> TimerHandle
+    where
+        F: Fn() -> Event + 'static,
+    {
+        let due = std::time::Instant::now() + period;
+        self.scheduler.wake_at(due);
+
+        let first = event();
+        self.timers.insert(due, first, Some((period, Box::new(event))))
+    }
+
+    /// Cancels a timer, returning false if it had already finished or been cancelled
+    pub fn cancel_timer(&mut self, handle: TimerHandle) -> bool {
+        self.timers.cancel(handle)
+    }
+
+    /// Sends the events of the timers which are due, called by the backend at the start of each frame
+    pub fn update_timers(&mut self, now: std::time::Instant) {
+        let (events, wakes) = self.timers.take_due(now);
+
+        for wake in wakes {
+            self.scheduler.wake_at(wake);
+        }
+
+        for event in events {
+            self.insert_event(event);
+        }
+    }
+
+    /// Returns a proxy which sends events into the application from other threads
+    pub fn event_proxy(&self) -> EventProxy {
+        self.event_proxy.clone()
+    }
+
+    /// Replaces the channel of the event proxy, for backends which hand out a proxy before the state is created
+    pub fn set_event_channel(&mut self, proxy: EventProxy, receiver: EventReceiver) {
+        self.event_proxy = proxy;
+        self.event_receiver = receiver;
+    }
+
+    /// Inserts the events sent through the event proxy into the event queue, called by the backend at the start of
+    /// each frame
+    pub fn receive_proxied_events(&mut self) {
+        for event in self.event_receiver.try_events() {
+            self.insert_event(event);
+        }
+    }
+
+    /// Runs a future in the background and sends its output to the entity as an event, e.g. to load a file without
+    /// blocking the UI.
+    ///
+    /// The task is cancelled if the entity is removed before it completes.
+    ///
+    /// # Examples
+    /// ```
+    /// state.spawn(entity, async move { PresetEvent::Loaded(load_presets(&path)) });
+    /// ```
+    pub fn spawn<F>(&mut self, entity: Entity, future: F) -> TaskHandle
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: crate::Message + Send,
+    {
+        let proxy = self.event_proxy();
+        self.tasks.spawn(entity, future, proxy)
+    }
+
+    /// Cancels a task, returning false if it had already finished or been cancelled
+    pub fn cancel_task(&mut self, handle: TaskHandle) -> bool {
+        self.tasks.cancel(handle)
+    }
+
     // Adds a new entity with a specified parent
     pub fn add(&mut self, parent: Entity) -> Entity {
         let entity = self
@@ -428,6 +773,7 @@ impl State {
     // Run all pending animations
     // TODO - This should probably be moved to style or an animation handling system
     pub fn apply_animations(&mut self) -> bool {
+        let profile_start = self.profiler.start();
 
         let time = std::time::Instant::now();
 
@@ -479,13 +825,74 @@ impl State {
         // Border
         self.style.border_width.tick(time);
         self.style.border_color.tick(time);
+        self.style.border_width_top.tick(time);
+        self.style.border_width_right.tick(time);
+        self.style.border_width_bottom.tick(time);
+        self.style.border_width_left.tick(time);
+        self.style.border_color_top.tick(time);
+        self.style.border_color_right.tick(time);
+        self.style.border_color_bottom.tick(time);
+        self.style.border_color_left.tick(time);
+
+        // Outline
+        self.style.outline_width.tick(time);
+        self.style.outline_color.tick(time);
+        self.style.outline_offset.tick(time);
 
         // Font
         self.style.font_size.tick(time);
         self.style.font_color.tick(time);
         
 
-        self.style.background_color.has_animations()
+        // Animated entities need to be redrawn
+        self.damage.mark_animating(&self.style.background_color);
+        self.damage.mark_animating(&self.style.left);
+        self.damage.mark_animating(&self.style.right);
+        self.damage.mark_animating(&self.style.top);
+        self.damage.mark_animating(&self.style.bottom);
+        self.damage.mark_animating(&self.style.min_left);
+        self.damage.mark_animating(&self.style.max_left);
+        self.damage.mark_animating(&self.style.min_right);
+        self.damage.mark_animating(&self.style.max_right);
+        self.damage.mark_animating(&self.style.min_top);
+        self.damage.mark_animating(&self.style.max_top);
+        self.damage.mark_animating(&self.style.min_bottom);
+        self.damage.mark_animating(&self.style.max_bottom);
+        self.damage.mark_animating(&self.style.width);
+        self.damage.mark_animating(&self.style.height);
+        self.damage.mark_animating(&self.style.min_width);
+        self.damage.mark_animating(&self.style.max_width);
+        self.damage.mark_animating(&self.style.min_height);
+        self.damage.mark_animating(&self.style.max_height);
+        self.damage.mark_animating(&self.style.child_left);
+        self.damage.mark_animating(&self.style.child_right);
+        self.damage.mark_animating(&self.style.child_top);
+        self.damage.mark_animating(&self.style.child_bottom);
+        self.damage.mark_animating(&self.style.row_between);
+        self.damage.mark_animating(&self.style.col_between);
+        self.damage.mark_animating(&self.style.opacity);
+        self.damage.mark_animating(&self.style.rotate);
+        self.damage.mark_animating(&self.style.border_radius_top_left);
+        self.damage.mark_animating(&self.style.border_radius_top_right);
+        self.damage.mark_animating(&self.style.border_radius_bottom_left);
+        self.damage.mark_animating(&self.style.border_radius_bottom_right);
+        self.damage.mark_animating(&self.style.border_width);
+        self.damage.mark_animating(&self.style.border_color);
+        self.damage.mark_animating(&self.style.border_width_top);
+        self.damage.mark_animating(&self.style.border_width_right);
+        self.damage.mark_animating(&self.style.border_width_bottom);
+        self.damage.mark_animating(&self.style.border_width_left);
+        self.damage.mark_animating(&self.style.border_color_top);
+        self.damage.mark_animating(&self.style.border_color_right);
+        self.damage.mark_animating(&self.style.border_color_bottom);
+        self.damage.mark_animating(&self.style.border_color_left);
+        self.damage.mark_animating(&self.style.outline_width);
+        self.damage.mark_animating(&self.style.outline_color);
+        self.damage.mark_animating(&self.style.outline_offset);
+        self.damage.mark_animating(&self.style.font_size);
+        self.damage.mark_animating(&self.style.font_color);
+
+        let animating = self.style.background_color.has_animations()
             || self.style.font_color.has_animations()
             // Spacing
             || self.style.left.has_animations()
@@ -527,6 +934,26 @@ impl State {
             // Border
             || self.style.border_width.has_animations()
             || self.style.border_color.has_animations()
+            || self.style.border_width_top.has_animations()
+            || self.style.border_width_right.has_animations()
+            || self.style.border_width_bottom.has_animations()
+            || self.style.border_width_left.has_animations()
+            || self.style.border_color_top.has_animations()
+            || self.style.border_color_right.has_animations()
+            || self.style.border_color_bottom.has_animations()
+            || self.style.border_color_left.has_animations()
+            // Outline
+            || self.style.outline_width.has_animations()
+            || self.style.outline_color.has_animations()
+            || self.style.outline_offset.has_animations();
+
+        // Playing animations are ticked again on the next frame
+        if animating {
+            self.scheduler.request_frame();
+        }
+
+        self.profiler.record(ProfiledSystem::Animations, profile_start, animating as usize);
 
+        animating
     }
 }
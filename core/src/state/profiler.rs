@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// The number of frames kept by default
+const DEFAULT_CAPACITY: usize = 120;
+
+/// A system which is measured by the [Profiler]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfiledSystem {
+    /// Dispatching events with `EventManager::flush_events`, which includes any restyle and relayout triggered by the events.
+    /// The count is the number of events dispatched.
+    Events,
+    /// Matching style rules with `apply_styles`. The count is the number of entities restyled.
+    Style,
+    /// Computing clip regions with `apply_clipping`. The count is the number of entities clipped.
+    Clipping,
+    /// Laying out the tree. The count is the number of entities laid out.
+    Layout,
+    /// Ticking animations with `State::apply_animations`. The count is 1 if an animation is still playing after the tick.
+    Animations,
+    /// Drawing the window with `EventManager::draw`. The count is the number of entities drawn.
+    Draw,
+}
+
+impl ProfiledSystem {
+    /// All of the profiled systems, in the order they are stored in a [FrameRecord]
+    pub const ALL: [ProfiledSystem; 6] = [
+        ProfiledSystem::Events,
+        ProfiledSystem::Style,
+        ProfiledSystem::Clipping,
+        ProfiledSystem::Layout,
+        ProfiledSystem::Animations,
+        ProfiledSystem::Draw,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The time spent in a system during a frame, and the number of items it processed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SystemRecord {
+    pub duration: Duration,
+    pub count: usize,
+    /// The number of times the system ran during the frame
+    pub runs: usize,
+}
+
+/// The measurements of the systems which ran during one frame
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameRecord {
+    /// The number of the frame, counting from the first recorded frame
+    pub frame: u64,
+    systems: [SystemRecord; 6],
+}
+
+impl FrameRecord {
+    /// Returns the measurements of a system
+    pub fn get(&self, system: ProfiledSystem) -> SystemRecord {
+        self.systems[system.index()]
+    }
+
+    /// Returns true if no system ran during the frame
+    pub fn is_empty(&self) -> bool {
+        self.systems.iter().all(|record| record.runs == 0)
+    }
+}
+
+/// Records how long each system takes, and how much work it does, into a ring buffer of frames.
+///
+/// The profiler is disabled by default. When enabled, the systems listed in [ProfiledSystem] add their measurements to the
+/// frame in progress, which is stored when the backend finishes the frame. Frames in which no system ran are not stored.
+///
+/// # Example
+/// ```
+/// state.profiler.set_enabled(true);
+/// // Later, e.g. in an on_idle callback
+/// for record in state.profiler.records() {
+///     println!("{} {:?}", record.frame, record.get(ProfiledSystem::Layout).duration);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    enabled: bool,
+    capacity: usize,
+    records: VecDeque<FrameRecord>,
+    current: FrameRecord,
+    frame_count: u64,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: DEFAULT_CAPACITY,
+            records: VecDeque::with_capacity(DEFAULT_CAPACITY),
+            current: FrameRecord::default(),
+            frame_count: 0,
+        }
+    }
+}
+
+impl Profiler {
+    /// Sets whether the systems are measured. Disabling the profiler keeps the stored frames.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.current = FrameRecord::default();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the number of frames which are kept, dropping the oldest frames if there are more. Defaults to 120.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.records.len() > self.capacity {
+            self.records.pop_front();
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the stored frames, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &FrameRecord> {
+        self.records.iter()
+    }
+
+    /// Returns the most recently stored frame
+    pub fn latest(&self) -> Option<&FrameRecord> {
+        self.records.back()
+    }
+
+    /// Removes the stored frames
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Returns the time at which a measurement starts, or None if the profiler is disabled
+    pub(crate) fn start(&self) -> Option<Instant> {
+        if self.enabled {
+            Some(Instant::now())
+        } else {
+            None
+        }
+    }
+
+    /// Adds a measurement which started at the time returned by [start](Profiler::start) to the frame in progress
+    pub(crate) fn record(&mut self, system: ProfiledSystem, start: Option<Instant>, count: usize) {
+        if let Some(start) = start {
+            let record = &mut self.current.systems[system.index()];
+            record.duration += start.elapsed();
+            record.count += count;
+            record.runs += 1;
+        }
+    }
+
+    /// Called by the backend when it has finished a frame, after drawing if the window needed drawing.
+    pub fn end_frame(&mut self) {
+        let mut record = std::mem::take(&mut self.current);
+
+        if record.is_empty() {
+            return;
+        }
+
+        record.frame = self.frame_count;
+        self.frame_count += 1;
+
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+
+        self.records.push_back(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measurements are only taken when enabled and the oldest frames are dropped when the buffer is full
+    #[test]
+    fn ring_buffer() {
+        let mut profiler = Profiler::default();
+
+        profiler.record(ProfiledSystem::Style, profiler.start(), 3);
+        profiler.end_frame();
+        assert!(profiler.latest().is_none());
+
+        profiler.set_enabled(true);
+        profiler.set_capacity(2);
+
+        for count in 1..=3 {
+            profiler.record(ProfiledSystem::Style, profiler.start(), count);
+            profiler.record(ProfiledSystem::Style, profiler.start(), count);
+            profiler.end_frame();
+        }
+
+        // Frames in which nothing ran are not stored
+        profiler.end_frame();
+
+        let frames = profiler.records().map(|record| record.frame).collect::<Vec<_>>();
+        assert_eq!(frames, vec![1, 2]);
+
+        let style = profiler.latest().unwrap().get(ProfiledSystem::Style);
+        assert_eq!((style.count, style.runs), (6, 2));
+        assert_eq!(profiler.latest().unwrap().get(ProfiledSystem::Draw), SystemRecord::default());
+    }
+}
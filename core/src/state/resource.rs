@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 
+use crate::Svg;
+
 // pub struct Image {
 //     name: String,
 //     pub width: u32,
@@ -9,14 +11,16 @@ use std::collections::HashMap;
 //     pub data: Vec<u8>,
 // }
 
-// pub enum ImageOrId {
-//     Image(image::DynamicImage),
-//     Id(femtovg::ImageId),
-// }
+/// An image resource which is either waiting to be uploaded to the canvas
+/// (encoded file data) or has been uploaded (image id and size in pixels).
+pub enum ImageOrId {
+    Image(Vec<u8>),
+    Id(crate::ImageId, (usize, usize)),
+}
 
 pub enum FontOrId {
     Font(Vec<u8>),
-    Id(femtovg::FontId),
+    Id(crate::FontId),
 }
 
 // #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -28,6 +32,10 @@ pub struct ResourceManager {
     pub themes: Vec<String>,      // Themes are the string content stylesheets
     //pub images: Vec<Image>,
     pub fonts: HashMap<String, FontOrId>,
+    pub images: HashMap<String, ImageOrId>,
+    pub svgs: HashMap<String, Svg>,
+    // The canvas images of replaced images, which are deleted before the next draw
+    pub(crate) unused_images: Vec<crate::ImageId>,
 
     //pub image_ids: HashMap<Rc<()>, ImageOrId>,
     count: u32,
@@ -43,6 +51,17 @@ impl ResourceManager {
             //image_ids: HashMap::new(),
             count: 0,
             fonts: HashMap::new(),
+            images: HashMap::new(),
+            svgs: HashMap::new(),
+            unused_images: Vec::new(),
+        }
+    }
+
+    /// Returns the canvas image id and the size in pixels of the named image, if it has been loaded
+    pub fn get_image(&self, name: &str) -> Option<(crate::ImageId, (usize, usize))> {
+        match self.images.get(name) {
+            Some(ImageOrId::Id(id, size)) => Some((*id, *size)),
+            _ => None,
         }
     }
 
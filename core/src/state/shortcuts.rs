@@ -0,0 +1,507 @@
+use crate::{Code, Entity, Event, Message, ModifiersState, State, TreeExt, WindowEvent};
+
+use std::fmt;
+
+/// A key pressed while holding a combination of modifier keys, e.g. Ctrl+S.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chord {
+    pub modifiers: ModifiersState,
+    pub code: Code,
+}
+
+impl Chord {
+    /// Creates a chord of a key pressed without any modifiers
+    pub fn new(code: Code) -> Self {
+        Self {
+            modifiers: ModifiersState::default(),
+            code,
+        }
+    }
+
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.modifiers.shift = true;
+
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.modifiers.alt = true;
+
+        self
+    }
+
+    /// Adds the logo key, which is Cmd on macOS and the Windows key on Windows
+    pub fn logo(mut self) -> Self {
+        self.modifiers.logo = true;
+
+        self
+    }
+}
+
+impl fmt::Display for Chord {
+    /// Formats the chord for display, e.g. in a menu, as the modifiers and the key joined with a `+`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+
+        if self.modifiers.logo {
+            if cfg!(target_os = "macos") {
+                write!(f, "Cmd+")?;
+            } else {
+                write!(f, "Super+")?;
+            }
+        }
+
+        let name = format!("{:?}", self.code);
+
+        match self.code {
+            Code::ArrowLeft => write!(f, "Left"),
+            Code::ArrowRight => write!(f, "Right"),
+            Code::ArrowUp => write!(f, "Up"),
+            Code::ArrowDown => write!(f, "Down"),
+            Code::Escape => write!(f, "Esc"),
+            Code::Delete => write!(f, "Del"),
+            Code::Minus => write!(f, "-"),
+            Code::Equal => write!(f, "="),
+            Code::Comma => write!(f, ","),
+            Code::Period => write!(f, "."),
+            Code::Slash => write!(f, "/"),
+            // KeyA is shown as A and Digit1 as 1
+            _ => write!(f, "{}", name.trim_start_matches("Key").trim_start_matches("Digit")),
+        }
+    }
+}
+
+/// A sequence of one or more chords which triggers a [Binding], e.g. Ctrl+K Ctrl+S.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shortcut {
+    chords: Vec<Chord>,
+}
+
+impl Shortcut {
+    /// Creates a shortcut of a single chord
+    pub fn new(chord: Chord) -> Self {
+        Self { chords: vec![chord] }
+    }
+
+    /// Adds a chord which has to be pressed after the previous chords
+    pub fn then(mut self, chord: Chord) -> Self {
+        self.chords.push(chord);
+
+        self
+    }
+
+    pub fn chords(&self) -> &[Chord] {
+        &self.chords
+    }
+
+    // Returns true if the chords are the start of this shortcut, or all of it
+    fn starts_with(&self, chords: &[Chord]) -> bool {
+        self.chords.starts_with(chords)
+    }
+}
+
+impl From<Chord> for Shortcut {
+    fn from(chord: Chord) -> Self {
+        Shortcut::new(chord)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    /// Formats the shortcut for display as its chords separated by spaces, e.g. `Ctrl+K Ctrl+S`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, chord) in self.chords.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", chord)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Determines when a [Binding] is active.
+///
+/// When the same shortcut is bound in more than one active scope, the binding of the most specific scope is triggered:
+/// the deepest focused subtree, then the window, then global.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutScope {
+    /// Always active
+    Global,
+    /// Active while the focused entity is inside of the window
+    Window(Entity),
+    /// Active while the focused entity is the entity or one of its descendants
+    Focus(Entity),
+}
+
+impl ShortcutScope {
+    fn is_active(&self, state: &State) -> bool {
+        match self {
+            ShortcutScope::Global => true,
+            ShortcutScope::Window(entity) | ShortcutScope::Focus(entity) => {
+                state.focused.is_descendant_of(&state.tree, *entity)
+            }
+        }
+    }
+
+    // Higher values are more specific
+    fn specificity(&self, state: &State) -> usize {
+        match self {
+            ShortcutScope::Global => 0,
+            ShortcutScope::Window(_) => 1,
+            ShortcutScope::Focus(entity) => 2 + entity.parent_iter(&state.tree).count(),
+        }
+    }
+}
+
+/// A shortcut bound to a message, which is sent to the target entity when the shortcut is pressed.
+pub struct Binding {
+    shortcut: Shortcut,
+    scope: ShortcutScope,
+    target: Entity,
+    description: String,
+    event: Box<dyn Fn(Entity) -> Event>,
+}
+
+impl Binding {
+    pub fn shortcut(&self) -> &Shortcut {
+        &self.shortcut
+    }
+
+    pub fn scope(&self) -> ShortcutScope {
+        self.scope
+    }
+
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    /// Returns the description shown with the shortcut, e.g. in a list of the bindings
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Sets the description shown with the shortcut
+    pub fn set_description(&mut self, description: &str) -> &mut Self {
+        self.description = description.to_string();
+
+        self
+    }
+}
+
+impl fmt::Debug for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Binding")
+            .field("shortcut", &self.shortcut)
+            .field("scope", &self.scope)
+            .field("target", &self.target)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// The error returned when binding a shortcut which conflicts with an existing binding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortcutConflict {
+    /// The shortcut of the existing binding
+    pub existing: Shortcut,
+    pub scope: ShortcutScope,
+}
+
+impl fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The shortcut conflicts with {} in the scope {:?}", self.existing, self.scope)
+    }
+}
+
+impl std::error::Error for ShortcutConflict {}
+
+/// The keyboard shortcuts of the application, which map a [Shortcut] to a message for a target entity.
+///
+/// When a key is pressed, the shortcuts are checked before the key is sent to the focused entity. A key which completes
+/// a shortcut sends the message of the binding instead of a `KeyDown` event, and a key which begins a longer shortcut
+/// is held until the next key.
+///
+/// # Example
+/// ```
+/// state.shortcuts.bind(
+///     Shortcut::new(Chord::new(Code::KeyS).ctrl()),
+///     ShortcutScope::Global,
+///     editor,
+///     EditorEvent::Save,
+/// )?.set_description("Save");
+/// ```
+#[derive(Default)]
+pub struct Shortcuts {
+    bindings: Vec<Binding>,
+    // The chords of a shortcut which has been started
+    pending: Vec<Chord>,
+}
+
+impl Shortcuts {
+    /// Creates the shortcuts of a new application: F5 reloads the stylesheets.
+    ///
+    /// The default bindings can be removed like any other, e.g.
+    /// `state.shortcuts.unbind(&Shortcut::new(Chord::new(Code::F5)), ShortcutScope::Global)`.
+    pub fn with_default_bindings() -> Self {
+        let mut shortcuts = Self::default();
+
+        if let Ok(binding) = shortcuts.bind(
+            Shortcut::new(Chord::new(Code::F5)),
+            ShortcutScope::Global,
+            Entity::root(),
+            WindowEvent::ReloadStyles,
+        ) {
+            binding.set_description("Reload stylesheets");
+        }
+
+        shortcuts
+    }
+
+    /// Binds a shortcut to a message which is sent to the target when the shortcut is pressed in the scope.
+    ///
+    /// Returns an error if the same shortcut is already bound in the scope, or if one of the shortcuts is the start of
+    /// the other, e.g. Ctrl+K and Ctrl+K Ctrl+S, so that the shorter shortcut couldn't be told apart from the longer one.
+    pub fn bind<M: Message + Clone>(
+        &mut self,
+        shortcut: Shortcut,
+        scope: ShortcutScope,
+        target: Entity,
+        message: M,
+    ) -> Result<&mut Binding, ShortcutConflict> {
+        if let Some(existing) = self.conflict(&shortcut, scope) {
+            return Err(ShortcutConflict {
+                existing: existing.shortcut.clone(),
+                scope,
+            });
+        }
+
+        self.bindings.push(Binding {
+            shortcut,
+            scope,
+            target,
+            description: String::new(),
+            event: Box::new(move |target| Event::new(message.clone()).target(target)),
+        });
+
+        Ok(self.bindings.last_mut().unwrap())
+    }
+
+    /// Removes the binding of a shortcut in a scope, returning true if there was one
+    pub fn unbind(&mut self, shortcut: &Shortcut, scope: ShortcutScope) -> bool {
+        let len = self.bindings.len();
+        self.bindings.retain(|binding| !(binding.shortcut == *shortcut && binding.scope == scope));
+        self.bindings.len() != len
+    }
+
+    /// Removes the bindings with the entity as their target or scope, e.g. when the entity is removed
+    pub fn unbind_entity(&mut self, entity: Entity) {
+        self.bindings.retain(|binding| {
+            binding.target != entity
+                && binding.scope != ShortcutScope::Window(entity)
+                && binding.scope != ShortcutScope::Focus(entity)
+        });
+    }
+
+    /// Returns the existing binding which a shortcut would conflict with in a scope
+    pub fn conflict(&self, shortcut: &Shortcut, scope: ShortcutScope) -> Option<&Binding> {
+        self.bindings.iter().find(|binding| {
+            binding.scope == scope
+                && (binding.shortcut.starts_with(&shortcut.chords) || shortcut.starts_with(&binding.shortcut.chords))
+        })
+    }
+
+    /// Returns the bindings in the order they were added, e.g. for a list of the shortcuts in a help window
+    pub fn bindings(&self) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter()
+    }
+
+    /// Returns the bindings which send messages to the target, e.g. to show the shortcut of a menu item
+    pub fn bindings_for(&self, target: Entity) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter().filter(move |binding| binding.target == target)
+    }
+
+    /// Returns true if the chords of a shortcut have been pressed and the next chord is awaited
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+/// Checks a pressed key against the shortcuts, sending the message of a completed shortcut.
+///
+/// Returns true if the key was used by a shortcut, in which case it shouldn't be sent to the focused entity.
+pub(crate) fn handle_shortcut(state: &mut State, code: Code) -> bool {
+    match code {
+        // Modifiers are part of the next chord
+        Code::ShiftLeft
+        | Code::ShiftRight
+        | Code::ControlLeft
+        | Code::ControlRight
+        | Code::AltLeft
+        | Code::AltRight
+        | Code::MetaLeft
+        | Code::MetaRight => return false,
+
+        _ => {}
+    }
+
+    let chord = Chord {
+        modifiers: state.modifiers,
+        code,
+    };
+
+    let mut chords = std::mem::take(&mut state.shortcuts.pending);
+    let was_pending = !chords.is_empty();
+    chords.push(chord);
+
+    if continue_shortcut(state, chords) {
+        return true;
+    }
+
+    // A key which doesn't continue the started shortcut may begin a new one
+    was_pending && continue_shortcut(state, vec![chord])
+}
+
+// Triggers the binding with the chords, or keeps the chords if they start a binding.
+// Returns true if the chords are part of an active binding.
+fn continue_shortcut(state: &mut State, chords: Vec<Chord>) -> bool {
+    let mut complete: Option<(usize, usize)> = None;
+    let mut partial = false;
+
+    for (index, binding) in state.shortcuts.bindings.iter().enumerate() {
+        if !binding.shortcut.starts_with(&chords) || !binding.scope.is_active(state) {
+            continue;
+        }
+
+        if binding.shortcut.chords.len() == chords.len() {
+            let specificity = binding.scope.specificity(state);
+            match complete {
+                Some((_, best)) if best >= specificity => {}
+                _ => complete = Some((index, specificity)),
+            }
+        } else {
+            partial = true;
+        }
+    }
+
+    if let Some((index, _)) = complete {
+        let binding = &state.shortcuts.bindings[index];
+        let event = (binding.event)(binding.target);
+        state.insert_event(event);
+        return true;
+    }
+
+    if partial {
+        state.shortcuts.pending = chords;
+        return true;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum TestEvent {
+        Save,
+        SaveAll,
+    }
+
+    fn sent(state: &mut State) -> Vec<TestEvent> {
+        state
+            .event_queue
+            .drain(..)
+            .filter_map(|mut event| event.message.downcast::<TestEvent>().cloned())
+            .collect()
+    }
+
+    /// Shortcuts are formatted as their modifiers and keys
+    #[test]
+    fn format() {
+        let shortcut = Shortcut::new(Chord::new(Code::KeyK).ctrl()).then(Chord::new(Code::Digit1).ctrl().shift());
+        assert_eq!(shortcut.to_string(), "Ctrl+K Ctrl+Shift+1");
+        assert_eq!(Chord::new(Code::F5).to_string(), "F5");
+    }
+
+    /// A shortcut which is the start of another in the same scope is a conflict
+    #[test]
+    fn conflicts() {
+        let mut shortcuts = Shortcuts::default();
+        let ctrl_k = Shortcut::new(Chord::new(Code::KeyK).ctrl());
+
+        assert!(shortcuts
+            .bind(ctrl_k.clone().then(Chord::new(Code::KeyS)), ShortcutScope::Global, Entity::root(), TestEvent::Save)
+            .is_ok());
+        assert!(shortcuts.bind(ctrl_k.clone(), ShortcutScope::Global, Entity::root(), TestEvent::Save).is_err());
+        assert!(shortcuts.bind(ctrl_k.clone(), ShortcutScope::Focus(Entity::root()), Entity::root(), TestEvent::Save).is_ok());
+
+        assert!(shortcuts.unbind(&ctrl_k, ShortcutScope::Focus(Entity::root())));
+        assert_eq!(shortcuts.bindings().count(), 1);
+    }
+
+    /// The chords of a sequence are held until the sequence is complete, and other keys reach the focused entity
+    #[test]
+    fn sequences() {
+        let mut state = State::new();
+        let ctrl_k = Chord::new(Code::KeyK).ctrl();
+
+        state
+            .shortcuts
+            .bind(Shortcut::new(Chord::new(Code::KeyS).ctrl()), ShortcutScope::Global, Entity::root(), TestEvent::Save)
+            .unwrap();
+        state
+            .shortcuts
+            .bind(Shortcut::new(ctrl_k).then(Chord::new(Code::KeyS).ctrl()), ShortcutScope::Global, Entity::root(), TestEvent::SaveAll)
+            .unwrap();
+
+        state.modifiers.ctrl = true;
+        assert!(!handle_shortcut(&mut state, Code::ControlLeft));
+        assert!(handle_shortcut(&mut state, Code::KeyK));
+        assert!(state.shortcuts.is_pending());
+        assert!(handle_shortcut(&mut state, Code::KeyS));
+        assert_eq!(sent(&mut state), vec![TestEvent::SaveAll]);
+
+        assert!(handle_shortcut(&mut state, Code::KeyS));
+        assert_eq!(sent(&mut state), vec![TestEvent::Save]);
+
+        // A key which doesn't continue the sequence cancels it and is handled on its own
+        assert!(handle_shortcut(&mut state, Code::KeyK));
+        assert!(handle_shortcut(&mut state, Code::KeyK));
+        assert!(!handle_shortcut(&mut state, Code::KeyA));
+        assert!(!state.shortcuts.is_pending());
+        assert!(sent(&mut state).is_empty());
+    }
+
+    /// F5 reloads the stylesheets by default, and the binding can be removed
+    #[test]
+    fn default_bindings() {
+        let mut state = State::new();
+
+        assert!(handle_shortcut(&mut state, Code::F5));
+        let mut event = state.event_queue.pop_back().unwrap();
+        assert_eq!(event.target, Entity::root());
+        assert!(matches!(event.message.downcast::<WindowEvent>(), Some(WindowEvent::ReloadStyles)));
+
+        assert!(state.shortcuts.unbind(&Shortcut::new(Chord::new(Code::F5)), ShortcutScope::Global));
+        assert!(!handle_shortcut(&mut state, Code::F5));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{Entity, EventProxy, Message};
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+// The number of threads which run tasks
+const WORKER_THREADS: usize = 4;
+// How often a pending task checks whether it has been cancelled, if it isn't woken sooner
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Identifies a task started with [State::spawn](crate::State::spawn), which can be used to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskHandle(u64);
+
+// Shared between the state and the thread running the task
+#[derive(Default)]
+struct TaskStatus {
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+struct Task {
+    handle: TaskHandle,
+    entity: Entity,
+    status: Arc<TaskStatus>,
+}
+
+/// The background tasks of the application, which run futures on a small pool of threads.
+///
+/// The output of a task is sent to its entity as an event through the [EventProxy] of the state, so it arrives
+/// on the UI thread at the start of a frame. Each task occupies a thread of the pool until it completes, so tasks
+/// shouldn't wait for each other. A task is cancelled when its entity is removed.
+#[derive(Default)]
+pub struct Tasks {
+    next_handle: u64,
+    tasks: Vec<Task>,
+    // The queue of the worker threads, which are started by the first task
+    sender: Option<Sender<Job>>,
+}
+
+impl Tasks {
+    /// Cancels a task, so that it isn't polled again and its output isn't sent.
+    /// Returns false if it had already finished or been cancelled.
+    pub fn cancel(&mut self, handle: TaskHandle) -> bool {
+        self.prune();
+
+        match self.tasks.iter().position(|task| task.handle == handle) {
+            Some(index) => {
+                self.tasks.remove(index).status.cancelled.store(true, Ordering::SeqCst);
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    /// Returns true if the task hasn't finished or been cancelled
+    pub fn is_running(&self, handle: TaskHandle) -> bool {
+        self.tasks
+            .iter()
+            .any(|task| task.handle == handle && !task.status.finished.load(Ordering::SeqCst))
+    }
+
+    pub(crate) fn spawn<F>(&mut self, entity: Entity, future: F, proxy: EventProxy) -> TaskHandle
+    where
+        F: Future + Send + 'static,
+        F::Output: Message + Send,
+    {
+        self.prune();
+
+        let handle = TaskHandle(self.next_handle);
+        self.next_handle += 1;
+
+        let status = Arc::new(TaskStatus::default());
+        self.tasks.push(Task {
+            handle,
+            entity,
+            status: status.clone(),
+        });
+
+        let job: Job = Box::new(move || {
+            if let Some(output) = block_on(future, &status.cancelled) {
+                if !status.cancelled.load(Ordering::SeqCst) {
+                    proxy.send(entity, output).ok();
+                }
+            }
+
+            status.finished.store(true, Ordering::SeqCst);
+        });
+
+        let sender = self.sender.get_or_insert_with(start_workers);
+        sender.send(job).expect("Task threads have stopped");
+
+        handle
+    }
+
+    // Cancels the tasks of a removed entity
+    pub(crate) fn remove_entity(&mut self, entity: Entity) {
+        for task in self.tasks.iter().filter(|task| task.entity == entity) {
+            task.status.cancelled.store(true, Ordering::SeqCst);
+        }
+
+        self.tasks.retain(|task| task.entity != entity);
+    }
+
+    // Forgets the tasks which have finished
+    fn prune(&mut self) {
+        self.tasks.retain(|task| !task.status.finished.load(Ordering::SeqCst));
+    }
+}
+
+// Starts the worker threads, which stop when the sender is dropped
+fn start_workers() -> Sender<Job> {
+    let (sender, receiver) = channel::<Job>();
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    for index in 0..WORKER_THREADS {
+        let receiver = receiver.clone();
+
+        thread::Builder::new()
+            .name(format!("tuix-task-{}", index))
+            .spawn(move || run_worker(&receiver))
+            .expect("Failed to start task thread");
+    }
+
+    sender
+}
+
+fn run_worker(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+// Wakes the thread which is polling the task
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Polls the future on the current thread until it completes, or returns None if it's cancelled first
+fn block_on<F: Future>(future: F, cancelled: &AtomicBool) -> Option<F::Output> {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        if cancelled.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return Some(output),
+            Poll::Pending => thread::park_timeout(CANCEL_CHECK_INTERVAL),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EventManager, State};
+
+    use super::*;
+
+    use std::time::Instant;
+
+    #[derive(Debug, PartialEq)]
+    struct Loaded(usize);
+
+    // Receives proxied events until one arrives or a second has passed
+    fn wait_for_event(state: &mut State) -> bool {
+        let start = Instant::now();
+
+        while start.elapsed() < Duration::from_secs(1) {
+            state.receive_proxied_events();
+
+            if !state.event_queue.is_empty() {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        false
+    }
+
+    /// The output of a task is sent to its entity as an event
+    #[test]
+    fn spawn() {
+        let mut state = State::new();
+        let entity = state.add(Entity::root());
+        state.event_queue.clear();
+
+        let handle = state.spawn(entity, async { Loaded(3) });
+        assert!(wait_for_event(&mut state));
+
+        let mut event = state.event_queue.pop_front().unwrap();
+        assert_eq!(event.target, entity);
+        assert_eq!(event.message.downcast::<Loaded>(), Some(&mut Loaded(3)));
+
+        // The task finishes after sending its output
+        let start = Instant::now();
+        while state.tasks.is_running(handle) && start.elapsed() < Duration::from_secs(1) {
+            thread::yield_now();
+        }
+        assert!(!state.tasks.is_running(handle));
+    }
+
+    /// Removing the entity of a task cancels it
+    #[test]
+    fn removed_entity() {
+        let mut state = State::new();
+        let mut event_manager = EventManager::new();
+        let entity = state.add(Entity::root());
+
+        let handle = state.spawn(entity, std::future::pending::<Loaded>());
+        assert!(state.tasks.is_running(handle));
+
+        state.remove(entity);
+        event_manager.flush_events(&mut state);
+
+        assert!(!state.tasks.is_running(handle));
+        assert!(!state.cancel_task(handle));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{Entity, Event};
+
+use std::time::{Duration, Instant};
+
+/// Identifies a timer started with [State::schedule](crate::State::schedule) or
+/// [State::interval](crate::State::interval), which can be used to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle(u64);
+
+struct Timer {
+    handle: TimerHandle,
+    // The entity whose removal cancels the timer, which is the target of the event
+    owner: Entity,
+    due: Instant,
+    // The event sent when the timer is next due
+    event: Event,
+    // Builds the event of each tick of an interval, as events can't be copied
+    repeat: Option<(Duration, Box<dyn Fn() -> Event>)>,
+}
+
+/// The timers of the application, which send events at a later time.
+///
+/// Each timer asks the [FrameScheduler](crate::FrameScheduler) to wake the application when it is due, and the backend
+/// sends the events of the due timers with [State::update_timers](crate::State::update_timers) at the start of each frame.
+/// A timer is cancelled when the target of its event is removed.
+#[derive(Default)]
+pub struct Timers {
+    next_handle: u64,
+    timers: Vec<Timer>,
+}
+
+impl Timers {
+    /// Cancels a timer, returning false if it had already finished or been cancelled
+    pub fn cancel(&mut self, handle: TimerHandle) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|timer| timer.handle != handle);
+
+        self.timers.len() != len
+    }
+
+    /// Returns true if the timer will send another event
+    pub fn is_scheduled(&self, handle: TimerHandle) -> bool {
+        self.timers.iter().any(|timer| timer.handle == handle)
+    }
+
+    /// Returns the time at which the next timer is due, or None if there are no timers
+    pub fn next_due(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.due).min()
+    }
+
+    pub(crate) fn insert(&mut self, due: Instant, event: Event, repeat: Option<(Duration, Box<dyn Fn() -> Event>)>) -> TimerHandle {
+        let handle = TimerHandle(self.next_handle);
+        self.next_handle += 1;
+
+        self.timers.push(Timer {
+            handle,
+            owner: event.target,
+            due,
+            event,
+            repeat,
+        });
+
+        handle
+    }
+
+    /// Removes the timers which are due, returning their events in the order they were due, and the time at which
+    /// each rescheduled interval is next due.
+    ///
+    /// An interval which has missed ticks sends one event and is rescheduled a period after `now`.
+    pub(crate) fn take_due(&mut self, now: Instant) -> (Vec<Event>, Vec<Instant>) {
+        let (mut due, pending): (Vec<_>, Vec<_>) = self.timers.drain(..).partition(|timer| timer.due <= now);
+        self.timers = pending;
+
+        due.sort_by_key(|timer| timer.due);
+
+        let mut events = Vec::with_capacity(due.len());
+        let mut wakes = Vec::new();
+
+        for timer in due {
+            match timer.repeat {
+                Some((period, build)) => {
+                    let mut next_due = timer.due + period;
+                    if next_due <= now {
+                        next_due = now + period;
+                    }
+
+                    let next_event = build();
+                    self.timers.push(Timer {
+                        handle: timer.handle,
+                        owner: timer.owner,
+                        due: next_due,
+                        event: next_event,
+                        repeat: Some((period, build)),
+                    });
+
+                    wakes.push(next_due);
+                    events.push(timer.event);
+                }
+
+                None => events.push(timer.event),
+            }
+        }
+
+        (events, wakes)
+    }
+
+    // Cancels the timers of a removed entity
+    pub(crate) fn remove_entity(&mut self, entity: Entity) {
+        self.timers.retain(|timer| timer.owner != entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EventManager, State, WindowEvent};
+
+    use super::*;
+
+    fn due_events(state: &mut State, now: Instant) -> usize {
+        state.event_queue.clear();
+        state.update_timers(now);
+        state.event_queue.len()
+    }
+
+    /// A scheduled event is sent once when it's due, and wakes the application then
+    #[test]
+    fn schedule() {
+        let mut state = State::new();
+        let start = Instant::now();
+
+        let handle = state.schedule(Event::new(WindowEvent::Redraw), Duration::from_millis(100));
+        assert!(state.scheduler.next_wake(start).is_some());
+        assert_eq!(due_events(&mut state, start), 0);
+
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(200)), 1);
+        assert!(!state.timers.is_scheduled(handle));
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(300)), 0);
+    }
+
+    /// An interval sends an event each period, without catching up on missed ticks, until it's cancelled
+    #[test]
+    fn interval() {
+        let mut state = State::new();
+        let start = Instant::now();
+
+        let handle = state.interval_with(|| Event::new(WindowEvent::Redraw), Duration::from_millis(100));
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(150)), 1);
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(250)), 1);
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(260)), 0);
+
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(1000)), 1);
+        assert!(state.timers.next_due().unwrap() > start + Duration::from_millis(1000));
+
+        assert!(state.cancel_timer(handle));
+        assert!(!state.cancel_timer(handle));
+        assert_eq!(due_events(&mut state, start + Duration::from_millis(2000)), 0);
+    }
+
+    /// An interval of a message sends a copy of it to the target on each tick
+    #[test]
+    fn interval_message() {
+        let mut state = State::new();
+        let start = Instant::now();
+        let entity = state.add(Entity::root());
+
+        state.interval(entity, WindowEvent::Redraw, Duration::from_millis(100));
+
+        for tick in 1..3 {
+            state.event_queue.clear();
+            state.update_timers(start + Duration::from_millis(tick * 100 + 50));
+            assert_eq!(state.event_queue.len(), 1);
+
+            let mut event = state.event_queue.pop_front().unwrap();
+            assert_eq!(event.target, entity);
+            assert!(matches!(event.message.downcast::<WindowEvent>(), Some(WindowEvent::Redraw)));
+        }
+    }
+
+    /// Removing the target of a timer cancels it
+    #[test]
+    fn removed_target() {
+        let mut state = State::new();
+        let mut event_manager = EventManager::new();
+        let entity = state.add(Entity::root());
+
+        let handle = state.schedule(Event::new(WindowEvent::Redraw).target(entity), Duration::from_millis(100));
+        state.remove(entity);
+        event_manager.flush_events(&mut state);
+
+        assert!(!state.timers.is_scheduled(handle));
+    }
+}
@@ -376,6 +376,15 @@ where
         }
     }
 
+    /// Returns the entities with an animation which is still playing
+    pub fn animating_entities(&self) -> Vec<Entity> {
+        self.active_animations
+            .iter()
+            .filter(|state| state.t0 < 1.0)
+            .flat_map(|state| state.entities.iter().cloned())
+            .collect()
+    }
+
     pub fn has_animations(&self) -> bool {
         for state in self.active_animations.iter() {
             if state.t0 < 1.0 {
@@ -0,0 +1,27 @@
+use crate::Color;
+
+use crate::Units;
+
+/// The line style of a border side
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorderStyle {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        BorderStyle::Solid
+    }
+}
+
+/// The values of a border shorthand property, e.g. `border-top: 2px dashed red`.
+/// Any values not specified in the shorthand are reset to their defaults: the default width (which draws no
+/// border), a solid style and a transparent color.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Border {
+    pub width: Option<Units>,
+    pub style: Option<BorderStyle>,
+    pub color: Option<Color>,
+}
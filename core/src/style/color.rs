@@ -137,6 +137,13 @@ impl From<Color> for femtovg::Color {
     }
 }
 
+impl From<femtovg::Color> for Color {
+    fn from(src: femtovg::Color) -> Color {
+        let channel = |value: f32| (value.max(0.0).min(1.0) * 255.0).round() as u8;
+        Color::rgba(channel(src.r), channel(src.g), channel(src.b), channel(src.a))
+    }
+}
+
 /// Compare two colors (Do not take care of alpha)
 impl PartialEq for Color {
     fn eq(&self, other: &Color) -> bool {
@@ -43,6 +43,55 @@ impl Interpolator for Visibility {
 }
 
 
+/// Determines whether the entities with the z-index of an entity are cached in an offscreen image.
+///
+/// A retained layer is only repainted when one of its entities is redrawn, restyled or relayed out,
+/// and is otherwise drawn to the window from the cached image. This suits large static backgrounds.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LayerMode {
+    Auto,
+    Retained,
+}
+
+impl Default for LayerMode {
+    fn default() -> Self {
+        LayerMode::Auto
+    }
+}
+
+impl Interpolator for LayerMode {
+    fn interpolate(_start: &Self, end: &Self, _t: f32) -> Self {
+        return *end;
+    }
+}
+
+
+/// Determines how an entity and its children are blended with the entities drawn behind them.
+///
+/// An entity with a blend mode other than `Normal` is drawn into an offscreen image with its children,
+/// which is then blended as a whole. Entities are blended with what has already been drawn into the same image,
+/// so an entity inside a translucent group or a retained layer does not blend with the entities outside of it.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Add,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        BlendMode::Normal
+    }
+}
+
+impl Interpolator for BlendMode {
+    fn interpolate(_start: &Self, end: &Self, _t: f32) -> Self {
+        return *end;
+    }
+}
+
+
 /// Describes the opacity of a entity.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Opacity(pub f32);
@@ -59,6 +108,19 @@ impl Interpolator for Opacity {
     }
 }
 
+/// Determines which arrow keys move the focus between the members of a composite widget, such as a list or a tab bar.
+///
+/// The members are the keyboard focusable descendants of the widget, which aren't inside of a nested composite widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrowNavigation {
+    /// Left and right move to the previous and next member
+    Horizontal,
+    /// Up and down move to the previous and next member
+    Vertical,
+    /// All of the arrow keys move the focus
+    Both,
+}
+
 /// Next and previous widgets which receive focus.
 #[derive(Debug, Clone)]
 pub struct FocusOrder {
@@ -38,7 +38,10 @@ mod gradient;
 pub use gradient::*;
 
 mod shadow;
-pub(crate) use shadow::*;
+pub use shadow::*;
+
+mod border;
+pub use border::*;
 
 mod display;
 pub use display::*;
@@ -102,9 +105,18 @@ pub struct Style {
     // Z Order
     pub z_order: StyleSet<i32>,
 
+    // Retained layers
+    pub layer: StyleSet<LayerMode>,
+
+    // Blending
+    pub mix_blend_mode: StyleSet<BlendMode>,
+
     // Clipping
     pub clip_widget: SparseSet<Entity>,
 
+    // The size of the content of an entity, e.g. the pixel size of an image, which an `Auto` width or height resolves to
+    pub content_size: SparseSet<(f32, f32)>,
+
     // Transform
     pub rotate: AnimatableSet<f32>,   
     pub translate: StyleSet<(f32, f32)>,
@@ -117,6 +129,23 @@ pub struct Style {
     // Border
     pub border_width: AnimatableSet<Units>,
     pub border_color: AnimatableSet<Color>,
+    pub border_style: StyleSet<BorderStyle>,
+
+    // Border Sides (override the values for all sides)
+    pub border_width_top: AnimatableSet<Units>,
+    pub border_width_right: AnimatableSet<Units>,
+    pub border_width_bottom: AnimatableSet<Units>,
+    pub border_width_left: AnimatableSet<Units>,
+
+    pub border_color_top: AnimatableSet<Color>,
+    pub border_color_right: AnimatableSet<Color>,
+    pub border_color_bottom: AnimatableSet<Color>,
+    pub border_color_left: AnimatableSet<Color>,
+
+    pub border_style_top: StyleSet<BorderStyle>,
+    pub border_style_right: StyleSet<BorderStyle>,
+    pub border_style_bottom: StyleSet<BorderStyle>,
+    pub border_style_left: StyleSet<BorderStyle>,
 
     // Border Shape
     pub border_shape_top_left: StyleSet<BorderCornerShape>,
@@ -134,6 +163,9 @@ pub struct Style {
 
     // Focus Order
     pub focus_order: SparseSet<FocusOrder>,
+    pub tab_index: StyleSet<i32>,
+    pub focus_scope: SparseSet<bool>,
+    pub arrow_navigation: SparseSet<ArrowNavigation>,
 
     // Background
     pub background_color: AnimatableSet<Color>,
@@ -152,6 +184,15 @@ pub struct Style {
     pub inner_shadow_blur: AnimatableSet<Units>,
     pub inner_shadow_color: AnimatableSet<Color>,
 
+    // Box Shadows
+    pub box_shadow: StyleSet<Vec<BoxShadow>>,
+
+    // Outline
+    pub outline_width: AnimatableSet<Units>,
+    pub outline_color: AnimatableSet<Color>,
+    pub outline_style: StyleSet<BorderStyle>,
+    pub outline_offset: AnimatableSet<Units>,
+
     //Text & Font
     pub text: StyleSet<String>,
     pub font: StyleSet<String>,
@@ -291,6 +332,18 @@ impl Style {
                         self.overflow.insert_rule(rule_id, value);
                     }
 
+                    Property::Layer(value) => {
+                        self.layer.insert_rule(rule_id, value);
+                    }
+
+                    Property::MixBlendMode(value) => {
+                        self.mix_blend_mode.insert_rule(rule_id, value);
+                    }
+
+                    Property::TabIndex(value) => {
+                        self.tab_index.insert_rule(rule_id, value);
+                    }
+
                     Property::BackgroundImage(_value) => {
                         todo!();
                     }
@@ -393,12 +446,97 @@ impl Style {
                     }
 
                     // Border
+                    // The shorthands also set each side, so that a more specific shorthand overrides a less specific side
                     Property::BorderWidth(value) => {
-                        self.border_width.insert_rule(rule_id, value);
+                        self.insert_border_width_rule(rule_id, value);
                     }
 
                     Property::BorderColor(value) => {
-                        self.border_color.insert_rule(rule_id, value);
+                        self.insert_border_color_rule(rule_id, value);
+                    }
+
+                    Property::BorderStyle(value) => {
+                        self.insert_border_style_rule(rule_id, value);
+                    }
+
+                    // Values omitted from a shorthand are reset to their defaults, as in CSS
+                    Property::Border(border) => {
+                        self.insert_border_width_rule(rule_id, border.width.unwrap_or_default());
+                        self.insert_border_style_rule(rule_id, border.style.unwrap_or_default());
+                        self.insert_border_color_rule(rule_id, border.color.unwrap_or_default());
+                    }
+
+                    // Border Sides
+                    Property::BorderTop(border) => {
+                        self.border_width_top.insert_rule(rule_id, border.width.unwrap_or_default());
+                        self.border_style_top.insert_rule(rule_id, border.style.unwrap_or_default());
+                        self.border_color_top.insert_rule(rule_id, border.color.unwrap_or_default());
+                    }
+
+                    Property::BorderRight(border) => {
+                        self.border_width_right.insert_rule(rule_id, border.width.unwrap_or_default());
+                        self.border_style_right.insert_rule(rule_id, border.style.unwrap_or_default());
+                        self.border_color_right.insert_rule(rule_id, border.color.unwrap_or_default());
+                    }
+
+                    Property::BorderBottom(border) => {
+                        self.border_width_bottom.insert_rule(rule_id, border.width.unwrap_or_default());
+                        self.border_style_bottom.insert_rule(rule_id, border.style.unwrap_or_default());
+                        self.border_color_bottom.insert_rule(rule_id, border.color.unwrap_or_default());
+                    }
+
+                    Property::BorderLeft(border) => {
+                        self.border_width_left.insert_rule(rule_id, border.width.unwrap_or_default());
+                        self.border_style_left.insert_rule(rule_id, border.style.unwrap_or_default());
+                        self.border_color_left.insert_rule(rule_id, border.color.unwrap_or_default());
+                    }
+
+                    Property::BorderTopWidth(value) => {
+                        self.border_width_top.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderRightWidth(value) => {
+                        self.border_width_right.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderBottomWidth(value) => {
+                        self.border_width_bottom.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderLeftWidth(value) => {
+                        self.border_width_left.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderTopColor(value) => {
+                        self.border_color_top.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderRightColor(value) => {
+                        self.border_color_right.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderBottomColor(value) => {
+                        self.border_color_bottom.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderLeftColor(value) => {
+                        self.border_color_left.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderTopStyle(value) => {
+                        self.border_style_top.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderRightStyle(value) => {
+                        self.border_style_right.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderBottomStyle(value) => {
+                        self.border_style_bottom.insert_rule(rule_id, value);
+                    }
+
+                    Property::BorderLeftStyle(value) => {
+                        self.border_style_left.insert_rule(rule_id, value);
                     }
 
                     Property::BorderCornerShape(shape) => {
@@ -507,6 +645,42 @@ impl Style {
                             .insert_rule(rule_id, box_shadow.color);
                     }
 
+                    // Box Shadows
+                    Property::BoxShadow(box_shadows) => {
+                        self.box_shadow.insert_rule(rule_id, box_shadows);
+                    }
+
+                    // Outline
+                    Property::Outline(outline) => {
+                        if let Some(width) = outline.width {
+                            self.outline_width.insert_rule(rule_id, width);
+                        }
+
+                        if let Some(style) = outline.style {
+                            self.outline_style.insert_rule(rule_id, style);
+                        }
+
+                        if let Some(color) = outline.color {
+                            self.outline_color.insert_rule(rule_id, color);
+                        }
+                    }
+
+                    Property::OutlineWidth(value) => {
+                        self.outline_width.insert_rule(rule_id, value);
+                    }
+
+                    Property::OutlineColor(value) => {
+                        self.outline_color.insert_rule(rule_id, value);
+                    }
+
+                    Property::OutlineStyle(value) => {
+                        self.outline_style.insert_rule(rule_id, value);
+                    }
+
+                    Property::OutlineOffset(value) => {
+                        self.outline_offset.insert_rule(rule_id, value);
+                    }
+
                     // Child Spacing
                     Property::ChildLeft(value) => {
                         self.child_left.insert_rule(rule_id, value);
@@ -711,6 +885,30 @@ impl Style {
         // println!("{:?}", self.child_left.shared_data);
     }
 
+    fn insert_border_width_rule(&mut self, rule_id: Rule, value: Units) {
+        self.border_width.insert_rule(rule_id, value);
+        self.border_width_top.insert_rule(rule_id, value);
+        self.border_width_right.insert_rule(rule_id, value);
+        self.border_width_bottom.insert_rule(rule_id, value);
+        self.border_width_left.insert_rule(rule_id, value);
+    }
+
+    fn insert_border_color_rule(&mut self, rule_id: Rule, value: Color) {
+        self.border_color.insert_rule(rule_id, value);
+        self.border_color_top.insert_rule(rule_id, value);
+        self.border_color_right.insert_rule(rule_id, value);
+        self.border_color_bottom.insert_rule(rule_id, value);
+        self.border_color_left.insert_rule(rule_id, value);
+    }
+
+    fn insert_border_style_rule(&mut self, rule_id: Rule, value: BorderStyle) {
+        self.border_style.insert_rule(rule_id, value);
+        self.border_style_top.insert_rule(rule_id, value);
+        self.border_style_right.insert_rule(rule_id, value);
+        self.border_style_bottom.insert_rule(rule_id, value);
+        self.border_style_left.insert_rule(rule_id, value);
+    }
+
     fn add_transition<T: Default + Interpolator>(
         &self,
         transition: Transition,
@@ -1242,8 +1440,17 @@ impl Style {
         self.opacity.remove(entity);
         // Z Order
         self.z_order.remove(entity);
+        // Layer
+        self.layer.remove(entity);
+        // Blending
+        self.mix_blend_mode.remove(entity);
+        // Focus
+        self.tab_index.remove(entity);
+        self.focus_scope.remove(entity);
+        self.arrow_navigation.remove(entity);
         // Clipping
         self.clip_widget.remove(entity);
+        self.content_size.remove(entity);
 
         // Transform
         self.translate.remove(entity);
@@ -1258,6 +1465,21 @@ impl Style {
         // Border
         self.border_color.remove(entity);
         self.border_width.remove(entity);
+        self.border_style.remove(entity);
+
+        // Border Sides
+        self.border_width_top.remove(entity);
+        self.border_width_right.remove(entity);
+        self.border_width_bottom.remove(entity);
+        self.border_width_left.remove(entity);
+        self.border_color_top.remove(entity);
+        self.border_color_right.remove(entity);
+        self.border_color_bottom.remove(entity);
+        self.border_color_left.remove(entity);
+        self.border_style_top.remove(entity);
+        self.border_style_right.remove(entity);
+        self.border_style_bottom.remove(entity);
+        self.border_style_left.remove(entity);
 
         // Border Shape
         self.border_shape_bottom_left.remove(entity);
@@ -1271,6 +1493,15 @@ impl Style {
         self.border_radius_top_left.remove(entity);
         self.border_radius_bottom_right.remove(entity);
 
+        // Box Shadows
+        self.box_shadow.remove(entity);
+
+        // Outline
+        self.outline_width.remove(entity);
+        self.outline_color.remove(entity);
+        self.outline_style.remove(entity);
+        self.outline_offset.remove(entity);
+
         self.layout_type.remove(entity);
         self.positioning_type.remove(entity);
 
@@ -1327,6 +1558,9 @@ impl Style {
 
 
         self.z_order.clear_rules();
+        self.layer.clear_rules();
+        self.mix_blend_mode.clear_rules();
+        self.tab_index.clear_rules();
         self.rotate.clear_rules();
         self.translate.clear_rules();
         self.scale.clear_rules();
@@ -1380,6 +1614,21 @@ impl Style {
         // Border
         self.border_width.clear_rules();
         self.border_color.clear_rules();
+        self.border_style.clear_rules();
+
+        // Border Sides
+        self.border_width_top.clear_rules();
+        self.border_width_right.clear_rules();
+        self.border_width_bottom.clear_rules();
+        self.border_width_left.clear_rules();
+        self.border_color_top.clear_rules();
+        self.border_color_right.clear_rules();
+        self.border_color_bottom.clear_rules();
+        self.border_color_left.clear_rules();
+        self.border_style_top.clear_rules();
+        self.border_style_right.clear_rules();
+        self.border_style_bottom.clear_rules();
+        self.border_style_left.clear_rules();
 
         // Border Radius
         self.border_radius_top_left.clear_rules();
@@ -1409,6 +1658,15 @@ impl Style {
         self.outer_shadow_blur.clear_rules();
         self.outer_shadow_color.clear_rules();
 
+        // Box Shadows
+        self.box_shadow.clear_rules();
+
+        // Outline
+        self.outline_width.clear_rules();
+        self.outline_color.clear_rules();
+        self.outline_style.clear_rules();
+        self.outline_offset.clear_rules();
+
         self.grid_rows.clear_rules();
         self.grid_cols.clear_rules();
         self.row_between.clear_rules();
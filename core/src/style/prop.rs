@@ -106,7 +106,8 @@ pub trait PropSet: AsEntity + Sized {
 
     /// Force a redraw
     ///
-    /// Sends a `WindowEvent::Redraw` message to the root window.
+    /// Sends a `WindowEvent::Redraw` message which damages the region of the entity, so only that region is repainted.
+    /// Calling this on the root entity repaints the whole window.
     ///
     /// # Example
     /// ```
@@ -156,7 +157,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -192,7 +193,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         ////flag_geo_change(state, self.entity());
 
@@ -222,7 +223,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -234,7 +235,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -246,7 +247,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -260,7 +261,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -274,7 +275,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -329,7 +330,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -348,7 +349,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -368,7 +369,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -386,7 +387,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -405,7 +406,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -417,7 +418,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -438,7 +439,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -453,7 +454,7 @@ pub trait PropSet: AsEntity + Sized {
 
         Entity::root().restyle(state);
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -464,7 +465,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_rotate(self, state: &mut State, value: f32) -> Entity {
         state.style.rotate.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -475,7 +476,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_translate(self, state: &mut State, value: (f32, f32)) -> Entity {
         state.style.translate.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -483,7 +484,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_scale(self, state: &mut State, value: f32) -> Entity {
         state.style.scale.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -508,7 +509,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.positioning_type.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -537,7 +538,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.bottom.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
         self.entity()
@@ -561,7 +562,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.left.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -586,7 +587,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.right.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -597,7 +598,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.top.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -606,7 +607,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.bottom.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -618,7 +619,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.min_left.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -630,7 +631,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.max_left.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -642,7 +643,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.min_right.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -654,7 +655,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.max_right.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -666,7 +667,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.min_top.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -678,7 +679,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.max_top.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -690,7 +691,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.min_bottom.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -702,7 +703,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.max_bottom.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -717,7 +718,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.width.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -729,7 +730,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.height.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -739,7 +740,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.min_width.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -750,7 +751,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.max_width.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -761,7 +762,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.min_height.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -772,7 +773,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.max_height.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -791,7 +792,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_text(self, state: &mut State, text: &str) -> Entity {
         state.style.text.insert(self.entity(), text.to_owned());
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -812,7 +813,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_font(self, state: &mut State, font: &str) -> Entity {
         state.style.font.insert(self.entity(), font.to_owned());
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -831,7 +832,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_font_size(self, state: &mut State, value: f32) -> Entity {
         state.style.font_size.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -851,7 +852,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_color(self, state: &mut State, value: Color) -> Entity {
         state.style.font_color.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -860,7 +861,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_tooltip(self, state: &mut State, text: &str) -> Entity {
         state.style.tooltip.insert(self.entity(), text.to_owned());
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -889,7 +890,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_background_color(self, state: &mut State, value: Color) -> Entity {
         state.style.background_color.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -907,7 +908,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_background_image(self, state: &mut State, value: Rc<()>) -> Entity {
         state.style.background_image.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -930,9 +931,14 @@ pub trait PropSet: AsEntity + Sized {
     /// border-width: {}px | {}%
     /// ```
     fn set_border_width(self, state: &mut State, value: Units) -> Entity {
+        // Each side is set too, so that the sides set by style rules are overridden
         state.style.border_width.insert(self.entity(), value);
+        state.style.border_width_top.insert(self.entity(), value);
+        state.style.border_width_right.insert(self.entity(), value);
+        state.style.border_width_bottom.insert(self.entity(), value);
+        state.style.border_width_left.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -952,9 +958,212 @@ pub trait PropSet: AsEntity + Sized {
     /// border-color: color_name | #hex_code
     /// ```
     fn set_border_color(self, state: &mut State, value: Color) -> Entity {
+        // Each side is set too, so that the sides set by style rules are overridden
         state.style.border_color.insert(self.entity(), value);
+        state.style.border_color_top.insert(self.entity(), value);
+        state.style.border_color_right.insert(self.entity(), value);
+        state.style.border_color_bottom.insert(self.entity(), value);
+        state.style.border_color_left.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border style of the entity for all four sides.
+    ///
+    /// Borders can be solid (`BorderStyle::Solid`), which is the default, dashed (`BorderStyle::Dashed`) or dotted (`BorderStyle::Dotted`).
+    ///
+    /// # Example
+    /// ```
+    /// entity.set_border_width(state, Pixels(2.0)).set_border_style(state, BorderStyle::Dashed);
+    /// ```
+    ///
+    /// # CSS
+    /// ```css
+    /// border-style: solid | dashed | dotted
+    /// ```
+    fn set_border_style(self, state: &mut State, value: BorderStyle) -> Entity {
+        // Each side is set too, so that the sides set by style rules are overridden
+        state.style.border_style.insert(self.entity(), value);
+        state.style.border_style_top.insert(self.entity(), value);
+        state.style.border_style_right.insert(self.entity(), value);
+        state.style.border_style_bottom.insert(self.entity(), value);
+        state.style.border_style_left.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border width of the top side of the entity, overriding the border width for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-top-width: {}px | {}%
+    /// ```
+    fn set_border_top_width(self, state: &mut State, value: Units) -> Entity {
+        state.style.border_width_top.insert(self.entity(), value);
+
+        Entity::root().relayout(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border width of the right side of the entity, overriding the border width for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-right-width: {}px | {}%
+    /// ```
+    fn set_border_right_width(self, state: &mut State, value: Units) -> Entity {
+        state.style.border_width_right.insert(self.entity(), value);
+
+        Entity::root().relayout(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border width of the bottom side of the entity, overriding the border width for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-bottom-width: {}px | {}%
+    /// ```
+    fn set_border_bottom_width(self, state: &mut State, value: Units) -> Entity {
+        state.style.border_width_bottom.insert(self.entity(), value);
+
+        Entity::root().relayout(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border width of the left side of the entity, overriding the border width for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-left-width: {}px | {}%
+    /// ```
+    fn set_border_left_width(self, state: &mut State, value: Units) -> Entity {
+        state.style.border_width_left.insert(self.entity(), value);
+
+        Entity::root().relayout(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border color of the top side of the entity, overriding the border color for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-top-color: color_name | #hex_code
+    /// ```
+    fn set_border_top_color(self, state: &mut State, value: Color) -> Entity {
+        state.style.border_color_top.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border color of the right side of the entity, overriding the border color for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-right-color: color_name | #hex_code
+    /// ```
+    fn set_border_right_color(self, state: &mut State, value: Color) -> Entity {
+        state.style.border_color_right.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border color of the bottom side of the entity, overriding the border color for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-bottom-color: color_name | #hex_code
+    /// ```
+    fn set_border_bottom_color(self, state: &mut State, value: Color) -> Entity {
+        state.style.border_color_bottom.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border color of the left side of the entity, overriding the border color for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-left-color: color_name | #hex_code
+    /// ```
+    fn set_border_left_color(self, state: &mut State, value: Color) -> Entity {
+        state.style.border_color_left.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border style of the top side of the entity, overriding the border style for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-top-style: solid | dashed | dotted
+    /// ```
+    fn set_border_top_style(self, state: &mut State, value: BorderStyle) -> Entity {
+        state.style.border_style_top.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border style of the right side of the entity, overriding the border style for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-right-style: solid | dashed | dotted
+    /// ```
+    fn set_border_right_style(self, state: &mut State, value: BorderStyle) -> Entity {
+        state.style.border_style_right.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border style of the bottom side of the entity, overriding the border style for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-bottom-style: solid | dashed | dotted
+    /// ```
+    fn set_border_bottom_style(self, state: &mut State, value: BorderStyle) -> Entity {
+        state.style.border_style_bottom.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the border style of the left side of the entity, overriding the border style for all sides.
+    ///
+    /// # CSS
+    /// ```css
+    /// border-left-style: solid | dashed | dotted
+    /// ```
+    fn set_border_left_style(self, state: &mut State, value: BorderStyle) -> Entity {
+        state.style.border_style_left.insert(self.entity(), value);
+
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -980,7 +1189,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.border_shape_bottom_left.insert(self.entity(), value);
         state.style.border_shape_bottom_right.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -990,7 +1199,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_top_left_shape(self, state: &mut State, value: BorderCornerShape) -> Entity {
         state.style.border_shape_top_left.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1000,7 +1209,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_top_right_shape(self, state: &mut State, value: BorderCornerShape) -> Entity {
         state.style.border_shape_top_right.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1010,7 +1219,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_bottom_left_shape(self, state: &mut State, value: BorderCornerShape) -> Entity {
         state.style.border_shape_bottom_left.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1020,7 +1229,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_bottom_right_shape(self, state: &mut State, value: BorderCornerShape) -> Entity {
         state.style.border_shape_bottom_right.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1035,7 +1244,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.border_radius_bottom_left.insert(self.entity(), value);
         state.style.border_radius_bottom_right.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1044,7 +1253,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_radius_top_left(self, state: &mut State, value: Units) -> Entity {
         state.style.border_radius_top_left.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1052,7 +1261,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_radius_top_right(self, state: &mut State, value: Units) -> Entity {
         state.style.border_radius_top_right.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1060,7 +1269,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_radius_bottom_left(self, state: &mut State, value: Units) -> Entity {
         state.style.border_radius_bottom_left.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1068,7 +1277,7 @@ pub trait PropSet: AsEntity + Sized {
     fn set_border_radius_bottom_right(self, state: &mut State, value: Units) -> Entity {
         state.style.border_radius_bottom_right.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1104,11 +1313,111 @@ pub trait PropSet: AsEntity + Sized {
         self
     }
 
+    /// Set the list of shadows drawn around (or inside) the entity.
+    /// Shadows earlier in the list are drawn on top of later ones.
+    ///
+    /// # CSS
+    /// ```css
+    /// box-shadow: inset? {h-offset}px {v-offset}px {blur}px? {spread}px? color?, ...
+    /// ```
+    fn set_box_shadows(self, state: &mut State, value: Vec<BoxShadow>) -> Entity {
+        state.style.box_shadow.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    // Outline
+
+    /// Set the width of the outline drawn outside the border of the entity.
+    /// The outline does not affect layout.
+    ///
+    /// # CSS
+    /// ```css
+    /// outline-width: {}px
+    /// ```
+    fn set_outline_width(self, state: &mut State, value: Units) -> Entity {
+        state.style.outline_width.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the color of the outline.
+    ///
+    /// # CSS
+    /// ```css
+    /// outline-color: color
+    /// ```
+    fn set_outline_color(self, state: &mut State, value: Color) -> Entity {
+        state.style.outline_color.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the line style of the outline.
+    ///
+    /// # CSS
+    /// ```css
+    /// outline-style: solid | dashed | dotted
+    /// ```
+    fn set_outline_style(self, state: &mut State, value: BorderStyle) -> Entity {
+        state.style.outline_style.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Set the gap between the outline and the border of the entity.
+    ///
+    /// # CSS
+    /// ```css
+    /// outline-offset: {}px
+    /// ```
+    fn set_outline_offset(self, state: &mut State, value: Units) -> Entity {
+        state.style.outline_offset.insert(self.entity(), value);
+
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
     // Clipping
+    /// Sets the widget which the entity is clipped to, inside its border.
+    ///
+    /// If the clip widget has a border radius the entity is also clipped to its rounded corners. A clip widget which
+    /// is itself clipped by a rounded clip widget adds that shape too, so nested rounded clips intersect.
     fn set_clip_widget(self, state: &mut State, value: Entity) -> Entity {
         state.style.clip_widget.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Sets the size of the content of the entity, e.g. the pixel size of an image, which is used for an `Auto` width or height.
+    ///
+    /// This isn't a style property, so it doesn't replace the width and height set by the user. The entity is relaid out
+    /// if the size changed.
+    fn set_content_size(self, state: &mut State, value: Option<(f32, f32)>) -> Entity {
+        if state.style.content_size.get(self.entity()).cloned() != value {
+            match value {
+                Some(size) => {
+                    state.style.content_size.insert(self.entity(), size).ok();
+                }
+
+                None => {
+                    state.style.content_size.remove(self.entity());
+                }
+            }
+
+            self.entity().relayout(state);
+        }
 
         self.entity()
     }
@@ -1116,7 +1425,49 @@ pub trait PropSet: AsEntity + Sized {
     fn set_z_order(self, state: &mut State, value: i32) -> Entity {
         state.style.z_order.insert(self.entity(), value);
 
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Sets whether the entities with the z-index of this entity are cached in an offscreen image.
+    ///
+    /// # Examples
+    /// Caches a static background which is given its own z-index:
+    /// ```
+    /// entity.set_z_order(state, -1).set_layer(state, LayerMode::Retained);
+    /// ```
+    ///
+    /// # CSS
+    /// ```css
+    /// layer: auto | retained
+    /// ```
+    fn set_layer(self, state: &mut State, value: LayerMode) -> Entity {
+        state.style.layer.insert(self.entity(), value);
+
+        Entity::root().relayout(state);
+        self.entity().redraw(state);
+
+        self.entity()
+    }
+
+    /// Sets how the entity and its children are blended with the entities drawn behind them.
+    ///
+    /// # Examples
+    /// Darkens the entities behind a tinted overlay:
+    /// ```
+    /// entity.set_background_color(state, Color::rgb(255, 200, 0)).set_mix_blend_mode(state, BlendMode::Multiply);
+    /// ```
+    ///
+    /// # CSS
+    /// ```css
+    /// mix-blend-mode: normal | multiply | screen | add
+    /// ```
+    fn set_mix_blend_mode(self, state: &mut State, value: BlendMode) -> Entity {
+        state.style.mix_blend_mode.insert(self.entity(), value);
+
+        Entity::root().relayout(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1170,6 +1521,57 @@ pub trait PropSet: AsEntity + Sized {
         self.entity()
     }
 
+    /// Sets the position of the entity in the order in which Tab moves the focus.
+    ///
+    /// Entities with a positive tab-index are visited first, in increasing order, followed by the entities with a
+    /// tab-index of 0 (the default) in tree order. An entity with a negative tab-index is skipped by Tab but can
+    /// still be focused by clicking on it or with the arrow keys.
+    ///
+    /// # Examples
+    /// Visits a search field before the rest of the window:
+    /// ```
+    /// search_field.set_tab_index(state, 1);
+    /// ```
+    ///
+    /// # CSS
+    /// ```css
+    /// tab-index: <integer>
+    /// ```
+    fn set_tab_index(self, state: &mut State, value: i32) -> Entity {
+        state.style.tab_index.insert(self.entity(), value);
+
+        self.entity()
+    }
+
+    /// Sets whether the entity is a focus scope, which keeps the focus inside of it when moved with Tab and Shift-Tab.
+    ///
+    /// # Examples
+    /// Keeps the focus inside of a dialog while it's open:
+    /// ```
+    /// dialog.set_focus_scope(state, true);
+    /// state.set_focus(ok_button);
+    /// ```
+    fn set_focus_scope(self, state: &mut State, value: bool) -> Entity {
+        state.style.focus_scope.insert(self.entity(), value);
+
+        self.entity()
+    }
+
+    /// Makes the entity a composite widget whose members are focused with the arrow keys, and with Home and End.
+    ///
+    /// The members of a composite widget are a single stop in the tab order, which is the focused member if there is one.
+    ///
+    /// # Examples
+    /// Moves between the tabs of a tab bar with left and right:
+    /// ```
+    /// tab_bar.set_arrow_navigation(state, ArrowNavigation::Horizontal);
+    /// ```
+    fn set_arrow_navigation(self, state: &mut State, value: ArrowNavigation) -> Entity {
+        state.style.arrow_navigation.insert(self.entity(), value);
+
+        self.entity()
+    }
+
     /// Set the layout type of the entity.
     ///
     /// Layout type determines how child entities which are parent-directed will be positioned.
@@ -1189,7 +1591,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.layout_type.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         //flag_geo_change(state, self.entity());
 
@@ -1203,7 +1605,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.child_bottom.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1212,7 +1614,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.child_left.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1221,7 +1623,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.row_between.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1230,7 +1632,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.col_between.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1239,7 +1641,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.child_right.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1248,7 +1650,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.child_top.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1257,7 +1659,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.child_bottom.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1266,7 +1668,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.grid_rows.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1275,7 +1677,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.grid_cols.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1284,7 +1686,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.row_index.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1293,7 +1695,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.col_index.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1302,7 +1704,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.row_span.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
 
         self.entity()
     }
@@ -1311,7 +1713,7 @@ pub trait PropSet: AsEntity + Sized {
         state.style.col_span.insert(self.entity(), value);
 
         Entity::root().relayout(state);
-        Entity::root().redraw(state);
+        self.entity().redraw(state);
         
         self
     }
@@ -1379,6 +1781,11 @@ pub trait PropGet: Sized + AsEntity {
         state.style.background_color.get(self.entity()).cloned().unwrap_or_default()
     }
 
+    // Font Color
+    fn get_font_color(&self, state: &mut State) -> Color {
+        state.style.font_color.get(self.entity()).cloned().unwrap_or(Color::rgb(0, 0, 0))
+    }
+
     // Position
     fn get_left(&self, state: &mut State) -> Units;
     fn get_right(&self, state: &mut State) -> Units;
@@ -26,6 +26,8 @@ pub(crate) enum Property {
     Visibility(Visibility),
     Overflow(Overflow),
     Opacity(f32),
+    Layer(LayerMode),
+    MixBlendMode(BlendMode),
 
     // Positioning
     LayoutType(LayoutType),
@@ -72,6 +74,24 @@ pub(crate) enum Property {
     BorderBottomRightRadius(Units),
     BorderWidth(Units),
     BorderColor(Color),
+    BorderStyle(BorderStyle),
+    Border(Border),
+    BorderTop(Border),
+    BorderRight(Border),
+    BorderBottom(Border),
+    BorderLeft(Border),
+    BorderTopWidth(Units),
+    BorderRightWidth(Units),
+    BorderBottomWidth(Units),
+    BorderLeftWidth(Units),
+    BorderTopColor(Color),
+    BorderRightColor(Color),
+    BorderBottomColor(Color),
+    BorderLeftColor(Color),
+    BorderTopStyle(BorderStyle),
+    BorderRightStyle(BorderStyle),
+    BorderBottomStyle(BorderStyle),
+    BorderLeftStyle(BorderStyle),
     BorderCornerShape(BorderCornerShape),
     BorderTopLeftShape(BorderCornerShape),
     BorderTopRightShape(BorderCornerShape),
@@ -99,10 +119,20 @@ pub(crate) enum Property {
     InnerShadowBlur(Units),
     InnerShadowColor(Color),
 
+    BoxShadow(Vec<BoxShadow>),
+
+    Outline(Border),
+    OutlineWidth(Units),
+    OutlineColor(Color),
+    OutlineStyle(BorderStyle),
+    OutlineOffset(Units),
+
     Transition(Vec<Transition>),
 
     ZIndex(i32),
 
+    TabIndex(i32),
+
     Translate((f32, f32)),
     Rotate(f32),
     Scale((f32, f32)),
@@ -150,6 +180,8 @@ impl std::fmt::Display for Property {
             Property::Visibility(val) => write!(f, "visibility: {};", 2),
             Property::Overflow(val) => write!(f, "overflow: {};", 3),
             Property::Opacity(val) => write!(f, "opacity: {};", val),
+            Property::Layer(val) => write!(f, "layer: {:?};", val),
+            Property::MixBlendMode(val) => write!(f, "mix-blend-mode: {:?};", val),
 
             // Positioning
             Property::LayoutType(val) => write!(f, "layout-type: {};", val),
@@ -214,6 +246,7 @@ impl std::fmt::Display for Property {
             Property::Transition(val) => write!(f, "transition: {:?};", val),
 
             Property::ZIndex(val) => write!(f, "z-index: {};", val),
+            Property::TabIndex(val) => write!(f, "tab-index: {};", val),
 
             _=> write!(f, ""),
         }
@@ -1,14 +1,40 @@
-
 use crate::Color;
 
 use crate::Units;
 
+/// A shadow drawn around (or inside) the box of an entity.
+///
+/// An entity can have a list of shadows which are specified in css as a comma-separated list,
+/// e.g. `box-shadow: 0px 2px 4px 1px #00000040, inset 0px 0px 2px red;`.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub(crate) struct BoxShadow {
+pub struct BoxShadow {
     pub horizontal_offset: Units,
     pub vertical_offset: Units,
     pub blur_radius: Units,
+    /// Grows (or shrinks if negative) the shadow before it is blurred
+    pub spread_radius: Units,
     pub color: Color,
+    /// Draw the shadow inside the border instead of outside the box
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    pub fn new(horizontal_offset: Units, vertical_offset: Units, blur_radius: Units, spread_radius: Units, color: Color) -> Self {
+        Self {
+            horizontal_offset,
+            vertical_offset,
+            blur_radius,
+            spread_radius,
+            color,
+            inset: false,
+        }
+    }
+
+    /// Draw the shadow inside the border of the entity
+    pub fn inset(mut self) -> Self {
+        self.inset = true;
+        self
+    }
 }
 
 impl Default for BoxShadow {
@@ -17,7 +43,9 @@ impl Default for BoxShadow {
             horizontal_offset: Units::Auto,
             vertical_offset: Units::Auto,
             blur_radius: Units::Auto,
+            spread_radius: Units::Auto,
             color: Color::rgba(0, 0, 0, 128),
+            inset: false,
         }
     }
-}
\ No newline at end of file
+}
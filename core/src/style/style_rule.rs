@@ -97,6 +97,24 @@ impl StyleRule {
         self
     }
 
+    pub fn set_layer(mut self, value: LayerMode) -> Self {
+        self.properties.push(Property::Layer(value));
+
+        self
+    }
+
+    pub fn set_mix_blend_mode(mut self, value: BlendMode) -> Self {
+        self.properties.push(Property::MixBlendMode(value));
+
+        self
+    }
+
+    pub fn set_tab_index(mut self, value: i32) -> Self {
+        self.properties.push(Property::TabIndex(value));
+
+        self
+    }
+
     // Background
     pub fn set_background_color(mut self, value: Color) -> Self {
         self.properties.push(Property::BackgroundColor(value));
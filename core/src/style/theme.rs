@@ -279,6 +279,7 @@ fn parse_selectors<'i, 't>(
                     "checked" => selector.pseudo_classes.insert(PseudoClass::CHECKED),
                     "selected" => selector.pseudo_classes.insert(PseudoClass::SELECTED),
                     "custom" => selector.pseudo_classes.insert(PseudoClass::CUSTOM),
+                    "drag-over" => selector.pseudo_classes.insert(PseudoClass::DRAG_OVER),
 
                     _ => {
                         let parse_error = ParseError {
@@ -406,8 +407,30 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
             "font" => Property::Font(parse_string(input)?),
 
             // Border
+            "border" => Property::Border(parse_border(input)?),
             "border-width" => Property::BorderWidth(parse_units(input)?),
             "border-color" => Property::BorderColor(parse_color(input)?),
+            "border-style" => Property::BorderStyle(parse_border_style(input)?),
+
+            "border-top" => Property::BorderTop(parse_border(input)?),
+            "border-right" => Property::BorderRight(parse_border(input)?),
+            "border-bottom" => Property::BorderBottom(parse_border(input)?),
+            "border-left" => Property::BorderLeft(parse_border(input)?),
+
+            "border-top-width" => Property::BorderTopWidth(parse_units(input)?),
+            "border-right-width" => Property::BorderRightWidth(parse_units(input)?),
+            "border-bottom-width" => Property::BorderBottomWidth(parse_units(input)?),
+            "border-left-width" => Property::BorderLeftWidth(parse_units(input)?),
+
+            "border-top-color" => Property::BorderTopColor(parse_color(input)?),
+            "border-right-color" => Property::BorderRightColor(parse_color(input)?),
+            "border-bottom-color" => Property::BorderBottomColor(parse_color(input)?),
+            "border-left-color" => Property::BorderLeftColor(parse_color(input)?),
+
+            "border-top-style" => Property::BorderTopStyle(parse_border_style(input)?),
+            "border-right-style" => Property::BorderRightStyle(parse_border_style(input)?),
+            "border-bottom-style" => Property::BorderBottomStyle(parse_border_style(input)?),
+            "border-left-style" => Property::BorderLeftStyle(parse_border_style(input)?),
             // TODO - Support array for specifying each corner
             "border-radius" => Property::BorderRadius(parse_units(input)?),
 
@@ -424,6 +447,10 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
 
             "overflow" => Property::Overflow(parse_overflow(input)?),
 
+            "layer" => Property::Layer(parse_layer(input)?),
+
+            "mix-blend-mode" => Property::MixBlendMode(parse_blend_mode(input)?),
+
             "outer-shadow" => Property::OuterShadow(parse_box_shadow(input)?),
             "outer-shadow-h-offset" => Property::OuterShadowHOffset(parse_units(input)?),
             "outer-shadow-v-offset" => Property::OuterShadowVOffset(parse_units(input)?),
@@ -436,11 +463,20 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
             "inner-shadow-blur" => Property::InnerShadowBlur(parse_units(input)?),
             "inner-shadow-color" => Property::InnerShadowColor(parse_color(input)?),
 
+            "box-shadow" => Property::BoxShadow(parse_box_shadows(input)?),
+
+            "outline" => Property::Outline(parse_border(input)?),
+            "outline-width" => Property::OutlineWidth(parse_units(input)?),
+            "outline-color" => Property::OutlineColor(parse_color(input)?),
+            "outline-style" => Property::OutlineStyle(parse_border_style(input)?),
+            "outline-offset" => Property::OutlineOffset(parse_units(input)?),
+
             "transition" => {
                 Property::Transition(input.parse_comma_separated(|F| parse_transition2(F))?)
             }
 
             "z-index" => Property::ZIndex(parse_z_index(input)?),
+            "tab-index" => Property::TabIndex(parse_z_index(input)?),
 
             "cursor" => Property::Cursor(parse_cursor(input)?),
 
@@ -651,92 +687,82 @@ fn parse_z_index<'i, 't>(
 //     })
 // }
 
-//TODO
+// Parses a list of comma-separated box shadows, or `none` for no shadows
+fn parse_box_shadows<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<BoxShadow>, ParseError<'i, CustomParseError>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+        return Ok(Vec::new());
+    }
+
+    input.parse_comma_separated(parse_box_shadow)
+}
+
+// Parses a single shadow in the form `inset? <h-offset> <v-offset> <blur>? <spread>? <color>?`
+// where `inset` and the color can be placed before or after the lengths
 fn parse_box_shadow<'i, 't>(
     input: &mut Parser<'i, 't>,
 ) -> Result<BoxShadow, ParseError<'i, CustomParseError>> {
     let mut box_shadow = BoxShadow::default();
+    let mut lengths = Vec::new();
+    let mut color = None;
 
-    match parse_length2(input.next()?) {
-        Ok(units) => {
-            box_shadow.horizontal_offset = units;
-            match parse_length2(input.next()?) {
-                Ok(units) => {
-                    box_shadow.vertical_offset = units;
-                    let next_token = input.next()?;
-                    match parse_length2(next_token) {
-                        Ok(units) => {
-                            box_shadow.blur_radius = units;
-
-                            let next_token = input.next()?;
-                            match parse_color2(next_token) {
-                                Ok(color) => box_shadow.color = color,
-
-                                _ => {}
-                            }
-                        }
-                        _ => {
-                            // Parse a color
-                            match parse_color2(next_token) {
-                                Ok(color) => box_shadow.color = color,
+    loop {
+        if !box_shadow.inset && input.try_parse(|input| input.expect_ident_matching("inset")).is_ok() {
+            box_shadow.inset = true;
+            continue;
+        }
 
-                                _ => {}
-                            }
-                        }
-                    }
+        // The lengths must be specified together
+        if lengths.is_empty() {
+            while lengths.len() < 4 {
+                match input.try_parse(parse_units) {
+                    Ok(units) => lengths.push(units),
+                    Err(_) => break,
                 }
-                Err(error) => return Err(error),
             }
-        }
-
-        Err(error) => return Err(error),
-    }
-
-    Ok(box_shadow)
-
-    // match token {
-    //     Token::Number { value: x, .. } => {
-    //         box_shadow.horizontal_offset = Units::Pixels(*x);
 
-    //         match input.next()? {
-    //             Token::Number { value: x, .. } => {
-    //                 box_shadow.vertical_offset = Units::Pixels(*x);
-    //             }
+            if !lengths.is_empty() {
+                continue;
+            }
+        }
 
-    //             t => {}
-    //         }
-    //     }
+        if color.is_none() {
+            if let Ok(value) = input.try_parse(parse_color) {
+                color = Some(value);
+                continue;
+            }
+        }
 
-    //     Token::Percentage { unit_value: x, .. } => Units::Percentage(*x as f32),
+        break;
+    }
 
-    //     Token::Dimension { value: x, .. } => Units::Pixels(*x as f32),
+    // Both offsets are required
+    if lengths.len() < 2 {
+        let location = input.current_source_location();
+        let basic_error = BasicParseError {
+            kind: BasicParseErrorKind::UnexpectedToken(input.next()?.to_owned()),
+            location,
+        };
+        return Err(basic_error.into());
+    }
 
-    //     t => {
-    //         let basic_error = BasicParseError {
-    //             kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-    //             location: SourceLocation { line: 0, column: 0 },
-    //         };
-    //         return Err(basic_error.into());
-    //     }
-    // }
+    box_shadow.horizontal_offset = lengths[0];
+    box_shadow.vertical_offset = lengths[1];
 
-    // Ok(box_shadow)
-}
+    if let Some(blur_radius) = lengths.get(2) {
+        box_shadow.blur_radius = *blur_radius;
+    }
 
-fn parse_length2<'i>(token: &Token<'i>) -> Result<Units, ParseError<'i, CustomParseError>> {
-    match token {
-        Token::Number { value: x, .. } => Ok(Units::Pixels(*x as f32)),
-        Token::Percentage { unit_value: x, .. } => Ok(Units::Percentage(*x as f32)),
+    if let Some(spread_radius) = lengths.get(3) {
+        box_shadow.spread_radius = *spread_radius;
+    }
 
-        Token::Dimension { value: x, .. } => Ok(Units::Pixels(*x as f32)),
-        t => {
-            let basic_error = BasicParseError {
-                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                location: SourceLocation { line: 0, column: 0 },
-            };
-            return Err(basic_error.into());
-        }
+    if let Some(color) = color {
+        box_shadow.color = color;
     }
+
+    Ok(box_shadow)
 }
 
 fn parse_transition2<'i, 't>(
@@ -964,6 +990,62 @@ fn parse_visibility<'i, 't>(
     })
 }
 
+fn parse_layer<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<LayerMode, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "auto" => LayerMode::Auto,
+            "retained" => LayerMode::Retained,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_blend_mode<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<BlendMode, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "normal" => BlendMode::Normal,
+            "multiply" => BlendMode::Multiply,
+            "screen" => BlendMode::Screen,
+            "add" => BlendMode::Add,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
 fn parse_overflow<'i, 't>(
     input: &mut Parser<'i, 't>,
 ) -> Result<Overflow, ParseError<'i, CustomParseError>> {
@@ -1018,16 +1100,16 @@ fn parse_border_corner_shape<'i, 't>(
     })
 }
 
-fn parse_layout_type<'i, 't>(
+fn parse_border_style<'i, 't>(
     input: &mut Parser<'i, 't>,
-) -> Result<LayoutType, ParseError<'i, CustomParseError>> {
+) -> Result<BorderStyle, ParseError<'i, CustomParseError>> {
     let location = input.current_source_location();
 
     Ok(match input.next()? {
         Token::Ident(name) => match name.as_ref() {
-            "row" => LayoutType::Row,
-            "column" => LayoutType::Column,
-            "grid" => LayoutType::Grid,
+            "solid" => BorderStyle::Solid,
+            "dashed" => BorderStyle::Dashed,
+            "dotted" => BorderStyle::Dotted,
 
             _ => {
                 return Err(
@@ -1046,30 +1128,66 @@ fn parse_layout_type<'i, 't>(
     })
 }
 
-fn parse_color<'i, 't>(
+// Parses a border shorthand with a width, style and color in any order, e.g. `2px dashed red`
+fn parse_border<'i, 't>(
     input: &mut Parser<'i, 't>,
-) -> Result<Color, ParseError<'i, CustomParseError>> {
-    let location = input.current_source_location();
+) -> Result<Border, ParseError<'i, CustomParseError>> {
+    let mut border = Border::default();
+
+    loop {
+        if border.width.is_none() {
+            if let Ok(width) = input.try_parse(parse_units) {
+                border.width = Some(width);
+                continue;
+            }
+        }
 
-    Ok(match input.next()? {
-        Token::Ident(name) => {
-            // if input.try_parse(|input| input.expect_ident_matching("rgb")).is_ok() {
-            //     if input.expect_parenthesis_block().is_ok() {
-            //         input.parse_nested_block(parse: F)
-            //     }
-            // }
+        if border.style.is_none() {
+            if let Ok(style) = input.try_parse(parse_border_style) {
+                border.style = Some(style);
+                continue;
+            }
+        }
 
-            match css_color(&name) {
-                Some(color) => color,
-                None => {
-                    return Err(
-                        CustomParseError::UnrecognisedColorName(name.to_owned().to_string()).into(),
-                    );
-                }
+        if border.color.is_none() {
+            if let Ok(color) = input.try_parse(parse_color) {
+                border.color = Some(color);
+                continue;
             }
         }
 
-        Token::IDHash(hash) | Token::Hash(hash) => Color::from(hash.to_owned().to_string()),
+        break;
+    }
+
+    if border == Border::default() {
+        let location = input.current_source_location();
+        let basic_error = BasicParseError {
+            kind: BasicParseErrorKind::UnexpectedToken(input.next()?.to_owned()),
+            location,
+        };
+        return Err(basic_error.into());
+    }
+
+    Ok(border)
+}
+
+fn parse_layout_type<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<LayoutType, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "row" => LayoutType::Row,
+            "column" => LayoutType::Column,
+            "grid" => LayoutType::Grid,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
 
         t => {
             let basic_error = BasicParseError {
@@ -1081,8 +1199,12 @@ fn parse_color<'i, 't>(
     })
 }
 
-fn parse_color2<'i>(token: &Token<'i>) -> Result<Color, ParseError<'i, CustomParseError>> {
-    match token {
+fn parse_color<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Color, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
         Token::Ident(name) => {
             // if input.try_parse(|input| input.expect_ident_matching("rgb")).is_ok() {
             //     if input.expect_parenthesis_block().is_ok() {
@@ -1091,7 +1213,7 @@ fn parse_color2<'i>(token: &Token<'i>) -> Result<Color, ParseError<'i, CustomPar
             // }
 
             match css_color(&name) {
-                Some(color) => Ok(color),
+                Some(color) => color,
                 None => {
                     return Err(
                         CustomParseError::UnrecognisedColorName(name.to_owned().to_string()).into(),
@@ -1100,16 +1222,16 @@ fn parse_color2<'i>(token: &Token<'i>) -> Result<Color, ParseError<'i, CustomPar
             }
         }
 
-        Token::IDHash(hash) | Token::Hash(hash) => Ok(Color::from(hash.to_owned().to_string())),
+        Token::IDHash(hash) | Token::Hash(hash) => Color::from(hash.to_owned().to_string()),
 
         t => {
             let basic_error = BasicParseError {
                 kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                location: SourceLocation { line: 0, column: 0 },
+                location,
             };
             return Err(basic_error.into());
         }
-    }
+    })
 }
 
 fn parse_font_size<'i, 't>(
@@ -1162,3 +1284,31 @@ pub(crate) fn parse(s: &str) -> Vec<StyleRule> {
 
     rules.into_iter().filter_map(|rule| rule.ok()).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A list of shadows with optional blur, spread, color and inset
+    #[test]
+    fn box_shadow_list() {
+        let mut input = ParserInput::new("1px 2px 3px 4px red, inset 0px 1px #000000");
+        let mut parser = Parser::new(&mut input);
+        let shadows = parse_box_shadows(&mut parser).unwrap();
+
+        assert_eq!(shadows.len(), 2);
+        assert_eq!(shadows[0], BoxShadow::new(Pixels(1.0), Pixels(2.0), Pixels(3.0), Pixels(4.0), Color::rgb(255, 0, 0)));
+        assert!(shadows[1].inset);
+        assert_eq!(shadows[1].vertical_offset, Pixels(1.0));
+        assert_eq!(shadows[1].blur_radius, Auto);
+        assert_eq!(shadows[1].color, Color::rgb(0, 0, 0));
+    }
+
+    /// A shadow needs both offsets
+    #[test]
+    fn box_shadow_missing_offset() {
+        let mut input = ParserInput::new("2px red");
+        let mut parser = Parser::new(&mut input);
+        assert!(parse_box_shadows(&mut parser).is_err());
+    }
+}
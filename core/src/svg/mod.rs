@@ -0,0 +1,151 @@
+//! # SVG
+//!
+//! A small subset of SVG used for drawing vector icons. The supported elements are `svg`, `g`, `path`, `rect` and `circle`,
+//! along with the `fill`, `stroke`, `stroke-width`, `stroke-linecap`, `stroke-linejoin`, `fill-rule`, `opacity`,
+//! `fill-opacity`, `stroke-opacity` and `transform` attributes (or the equivalent `style` declarations).
+//! Other elements and their children are ignored.
+//!
+//! SVG resources are added to the application with `state.add_svg()` and are parsed once into cached paths.
+
+mod path;
+pub use path::*;
+
+mod parser;
+
+use crate::{Canvas, Color, FillRule, LineCap, LineJoin, Paint, Path};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgError {
+    // The file could not be read
+    Io(String),
+    // The document does not contain an svg element
+    NoRoot,
+    // A tag was not closed
+    UnclosedTag,
+    // The path data could not be parsed
+    InvalidPathData(String),
+}
+
+/// Describes how a shape is filled or stroked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SvgPaint {
+    None,
+    Color(Color),
+    /// Use the `color` property of the entity drawing the SVG
+    CurrentColor,
+}
+
+/// A single filled and/or stroked path
+pub struct SvgShape {
+    pub commands: Vec<PathCommand>,
+    pub fill: SvgPaint,
+    pub stroke: SvgPaint,
+    pub stroke_width: f32,
+    pub fill_rule: FillRule,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    pub fill_opacity: f32,
+    pub stroke_opacity: f32,
+
+    // Cached path built from the commands
+    path: Path,
+}
+
+impl SvgShape {
+    pub(crate) fn new(commands: Vec<PathCommand>) -> Self {
+        let mut path = Path::new();
+        for command in commands.iter() {
+            match *command {
+                PathCommand::MoveTo(x, y) => path.move_to(x, y),
+                PathCommand::LineTo(x, y) => path.line_to(x, y),
+                PathCommand::BezierTo(x1, y1, x2, y2, x, y) => path.bezier_to(x1, y1, x2, y2, x, y),
+                PathCommand::QuadTo(x1, y1, x, y) => path.quad_to(x1, y1, x, y),
+                PathCommand::Close => path.close(),
+            }
+        }
+
+        Self {
+            commands,
+            fill: SvgPaint::Color(Color::black()),
+            stroke: SvgPaint::None,
+            stroke_width: 1.0,
+            fill_rule: FillRule::NonZero,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            path,
+        }
+    }
+}
+
+/// A parsed SVG document
+pub struct Svg {
+    /// The intrinsic width of the document
+    pub width: f32,
+    /// The intrinsic height of the document
+    pub height: f32,
+    /// The region of user space (x, y, width, height) mapped to the bounds when drawing
+    pub view_box: (f32, f32, f32, f32),
+    pub shapes: Vec<SvgShape>,
+}
+
+impl Svg {
+    /// Parse an SVG document from a string
+    pub fn parse(text: &str) -> Result<Self, SvgError> {
+        parser::parse_svg(text)
+    }
+
+    /// Draw the SVG into the bounds (x, y, width, height), preserving the aspect ratio of the view box.
+    ///
+    /// Shapes which use `currentColor` are drawn with `color`. If `recolor` is true all fills and strokes are
+    /// drawn with `color` instead of the colors specified in the document.
+    pub fn draw(&mut self, canvas: &mut Canvas, bounds: (f32, f32, f32, f32), color: Color, recolor: bool, opacity: f32) {
+        let (x, y, width, height) = bounds;
+        let (vx, vy, vw, vh) = self.view_box;
+
+        if vw <= 0.0 || vh <= 0.0 || width <= 0.0 || height <= 0.0 {
+            return;
+        }
+
+        let scale = (width / vw).min(height / vh);
+        let offset_x = (width - vw * scale) / 2.0;
+        let offset_y = (height - vh * scale) / 2.0;
+
+        canvas.save();
+        canvas.translate(x + offset_x, y + offset_y);
+        canvas.scale(scale, scale);
+        canvas.translate(-vx, -vy);
+
+        let resolve = |paint: SvgPaint, alpha: f32| -> Option<femtovg::Color> {
+            let color = match paint {
+                SvgPaint::None => return None,
+                SvgPaint::Color(_) if recolor => color,
+                SvgPaint::Color(color) => color,
+                SvgPaint::CurrentColor => color,
+            };
+
+            let mut color: femtovg::Color = color.into();
+            color.set_alphaf(color.a * alpha * opacity);
+            Some(color)
+        };
+
+        for shape in self.shapes.iter_mut() {
+            if let Some(fill_color) = resolve(shape.fill, shape.fill_opacity) {
+                let mut paint = Paint::color(fill_color);
+                paint.set_fill_rule(shape.fill_rule);
+                canvas.fill_path(&mut shape.path, paint);
+            }
+
+            if let Some(stroke_color) = resolve(shape.stroke, shape.stroke_opacity) {
+                let mut paint = Paint::color(stroke_color);
+                paint.set_line_width(shape.stroke_width);
+                paint.set_line_cap(shape.line_cap);
+                paint.set_line_join(shape.line_join);
+                canvas.stroke_path(&mut shape.path, paint);
+            }
+        }
+
+        canvas.restore();
+    }
+}
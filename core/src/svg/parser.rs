@@ -0,0 +1,533 @@
+use crate::{Color, FillRule, LineCap, LineJoin, Transform2D};
+
+use super::{parse_path_data, PathCommand, Svg, SvgError, SvgPaint, SvgShape};
+
+// Magic constant for approximating a quarter circle with a cubic bezier
+const KAPPA90: f32 = 0.5522847493;
+
+// Presentation attributes inherited by child elements
+#[derive(Clone)]
+struct Presentation {
+    fill: SvgPaint,
+    stroke: SvgPaint,
+    stroke_width: f32,
+    fill_rule: FillRule,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    opacity: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    transform: Transform2D,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Self {
+            fill: SvgPaint::Color(Color::black()),
+            stroke: SvgPaint::None,
+            stroke_width: 1.0,
+            fill_rule: FillRule::NonZero,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            opacity: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            transform: Transform2D::identity(),
+        }
+    }
+}
+
+impl Presentation {
+    // Returns the presentation of a child element with the given attributes
+    fn apply(&self, attributes: &[(String, String)]) -> Self {
+        let mut result = self.clone();
+        for (name, value) in attributes.iter() {
+            let value = value.trim();
+            match name.as_ref() {
+                "fill" => {
+                    if let Some(paint) = parse_paint(value) {
+                        result.fill = paint;
+                    }
+                }
+
+                "stroke" => {
+                    if let Some(paint) = parse_paint(value) {
+                        result.stroke = paint;
+                    }
+                }
+
+                "stroke-width" => {
+                    if let Some(width) = parse_length(value) {
+                        result.stroke_width = width;
+                    }
+                }
+
+                "fill-rule" => match value {
+                    "evenodd" => result.fill_rule = FillRule::EvenOdd,
+                    "nonzero" => result.fill_rule = FillRule::NonZero,
+                    _ => {}
+                },
+
+                "stroke-linecap" => match value {
+                    "butt" => result.line_cap = LineCap::Butt,
+                    "round" => result.line_cap = LineCap::Round,
+                    "square" => result.line_cap = LineCap::Square,
+                    _ => {}
+                },
+
+                "stroke-linejoin" => match value {
+                    "miter" => result.line_join = LineJoin::Miter,
+                    "round" => result.line_join = LineJoin::Round,
+                    "bevel" => result.line_join = LineJoin::Bevel,
+                    _ => {}
+                },
+
+                // Group opacity is approximated by multiplying it into the opacity of each shape
+                "opacity" => {
+                    if let Ok(opacity) = value.parse::<f32>() {
+                        result.opacity *= opacity.max(0.0).min(1.0);
+                    }
+                }
+
+                "fill-opacity" => {
+                    if let Ok(opacity) = value.parse::<f32>() {
+                        result.fill_opacity = opacity.max(0.0).min(1.0);
+                    }
+                }
+
+                "stroke-opacity" => {
+                    if let Ok(opacity) = value.parse::<f32>() {
+                        result.stroke_opacity = opacity.max(0.0).min(1.0);
+                    }
+                }
+
+                "transform" => {
+                    let mut transform = parse_transform(value);
+                    transform.multiply(&self.transform);
+                    result.transform = transform;
+                }
+
+                _ => {}
+            }
+        }
+
+        result
+    }
+}
+
+pub(crate) fn parse_svg(text: &str) -> Result<Svg, SvgError> {
+    let mut svg: Option<Svg> = None;
+
+    let mut stack: Vec<Presentation> = Vec::new();
+    // Depth of elements whose contents are ignored (e.g. defs, title)
+    let mut skip_depth = 0usize;
+
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+
+        // Comments, processing instructions and declarations
+        if rest.starts_with("<!--") {
+            rest = skip_past(rest, "-->")?;
+            continue;
+        }
+
+        if rest.starts_with("<?") {
+            rest = skip_past(rest, "?>")?;
+            continue;
+        }
+
+        if rest.starts_with("<!") {
+            rest = skip_past(rest, ">")?;
+            continue;
+        }
+
+        // Closing tag
+        if rest.starts_with("</") {
+            rest = skip_past(rest, ">")?;
+            if skip_depth > 0 {
+                skip_depth -= 1;
+            } else {
+                stack.pop();
+            }
+            continue;
+        }
+
+        let end = find_tag_end(rest).ok_or(SvgError::UnclosedTag)?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+
+        let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+        let name = &tag[..name_end];
+        let attributes = parse_attributes(&tag[name_end..]);
+
+        if skip_depth > 0 {
+            if !self_closing {
+                skip_depth += 1;
+            }
+            continue;
+        }
+
+        let parent = stack.last().cloned().unwrap_or_default();
+        let presentation = parent.apply(&attributes);
+
+        match name {
+            "svg" => {
+                if svg.is_none() {
+                    let width = attribute(&attributes, "width").and_then(parse_length);
+                    let height = attribute(&attributes, "height").and_then(parse_length);
+                    let view_box = attribute(&attributes, "viewBox").and_then(|value| {
+                        let values = parse_numbers(value);
+                        if values.len() == 4 {
+                            Some((values[0], values[1], values[2], values[3]))
+                        } else {
+                            None
+                        }
+                    });
+
+                    let view_box = view_box.unwrap_or((0.0, 0.0, width.unwrap_or(0.0), height.unwrap_or(0.0)));
+
+                    svg = Some(Svg {
+                        width: width.unwrap_or(view_box.2),
+                        height: height.unwrap_or(view_box.3),
+                        view_box,
+                        shapes: Vec::new(),
+                    });
+                }
+            }
+
+            "g" => {}
+
+            "path" | "rect" | "circle" => {
+                if let Some(svg) = svg.as_mut() {
+                    let commands = match name {
+                        "path" => parse_path_data(attribute(&attributes, "d").unwrap_or(""))?,
+                        "rect" => rect_commands(&attributes),
+                        _ => circle_commands(&attributes),
+                    };
+
+                    if !commands.is_empty() {
+                        svg.shapes.push(build_shape(commands, &presentation));
+                    }
+                }
+            }
+
+            // Any other element is ignored along with its children
+            _ => {
+                if !self_closing {
+                    skip_depth += 1;
+                }
+                continue;
+            }
+        }
+
+        if !self_closing {
+            stack.push(presentation);
+        }
+    }
+
+    svg.ok_or(SvgError::NoRoot)
+}
+
+fn build_shape(commands: Vec<PathCommand>, presentation: &Presentation) -> SvgShape {
+    let transform = &presentation.transform;
+    let commands = commands.iter().map(|command| command.transformed(transform)).collect();
+
+    // Approximate the scaling of the stroke width by the transform
+    let scale = (transform[0] * transform[3] - transform[1] * transform[2]).abs().sqrt();
+
+    let mut shape = SvgShape::new(commands);
+    shape.fill = presentation.fill;
+    shape.stroke = presentation.stroke;
+    shape.stroke_width = presentation.stroke_width * scale;
+    shape.fill_rule = presentation.fill_rule;
+    shape.line_cap = presentation.line_cap;
+    shape.line_join = presentation.line_join;
+    shape.fill_opacity = presentation.fill_opacity * presentation.opacity;
+    shape.stroke_opacity = presentation.stroke_opacity * presentation.opacity;
+    shape
+}
+
+fn rect_commands(attributes: &[(String, String)]) -> Vec<PathCommand> {
+    let get = |name| attribute(attributes, name).and_then(parse_length);
+
+    let x = get("x").unwrap_or(0.0);
+    let y = get("y").unwrap_or(0.0);
+    let w = get("width").unwrap_or(0.0);
+    let h = get("height").unwrap_or(0.0);
+
+    if w <= 0.0 || h <= 0.0 {
+        return Vec::new();
+    }
+
+    // If only one of rx and ry is specified the other takes the same value
+    let (rx, ry) = (get("rx"), get("ry"));
+    let (rx, ry) = (rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0));
+    let rx = rx.max(0.0).min(w / 2.0);
+    let ry = ry.max(0.0).min(h / 2.0);
+
+    if rx == 0.0 || ry == 0.0 {
+        return vec![
+            PathCommand::MoveTo(x, y),
+            PathCommand::LineTo(x + w, y),
+            PathCommand::LineTo(x + w, y + h),
+            PathCommand::LineTo(x, y + h),
+            PathCommand::Close,
+        ];
+    }
+
+    let kx = rx * (1.0 - KAPPA90);
+    let ky = ry * (1.0 - KAPPA90);
+
+    vec![
+        PathCommand::MoveTo(x + rx, y),
+        PathCommand::LineTo(x + w - rx, y),
+        PathCommand::BezierTo(x + w - kx, y, x + w, y + ky, x + w, y + ry),
+        PathCommand::LineTo(x + w, y + h - ry),
+        PathCommand::BezierTo(x + w, y + h - ky, x + w - kx, y + h, x + w - rx, y + h),
+        PathCommand::LineTo(x + rx, y + h),
+        PathCommand::BezierTo(x + kx, y + h, x, y + h - ky, x, y + h - ry),
+        PathCommand::LineTo(x, y + ry),
+        PathCommand::BezierTo(x, y + ky, x + kx, y, x + rx, y),
+        PathCommand::Close,
+    ]
+}
+
+fn circle_commands(attributes: &[(String, String)]) -> Vec<PathCommand> {
+    let get = |name| attribute(attributes, name).and_then(parse_length);
+
+    let cx = get("cx").unwrap_or(0.0);
+    let cy = get("cy").unwrap_or(0.0);
+    let r = get("r").unwrap_or(0.0);
+
+    if r <= 0.0 {
+        return Vec::new();
+    }
+
+    let k = r * KAPPA90;
+
+    vec![
+        PathCommand::MoveTo(cx + r, cy),
+        PathCommand::BezierTo(cx + r, cy + k, cx + k, cy + r, cx, cy + r),
+        PathCommand::BezierTo(cx - k, cy + r, cx - r, cy + k, cx - r, cy),
+        PathCommand::BezierTo(cx - r, cy - k, cx - k, cy - r, cx, cy - r),
+        PathCommand::BezierTo(cx + k, cy - r, cx + r, cy - k, cx + r, cy),
+        PathCommand::Close,
+    ]
+}
+
+// Returns the remainder of the text after the first occurrence of the pattern
+fn skip_past<'a>(text: &'a str, pattern: &str) -> Result<&'a str, SvgError> {
+    text.find(pattern)
+        .map(|index| &text[index + pattern.len()..])
+        .ok_or(SvgError::UnclosedTag)
+}
+
+// Finds the closing '>' of a tag, ignoring any inside quoted attribute values
+fn find_tag_end(text: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+// Parses the attributes of a tag. Declarations in a `style` attribute are appended
+// after the other attributes so that they take precedence.
+fn parse_attributes(text: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut style = Vec::new();
+    let mut rest = text;
+
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+
+        let quote = match after.chars().next() {
+            Some(q) if q == '"' || q == '\'' => q,
+            _ => break,
+        };
+
+        let value_end = match after[1..].find(quote) {
+            Some(end) => end + 1,
+            None => break,
+        };
+
+        let value = after[1..value_end].to_string();
+        rest = &after[value_end + 1..];
+
+        if name == "style" {
+            for declaration in value.split(';') {
+                if let Some(colon) = declaration.find(':') {
+                    style.push((
+                        declaration[..colon].trim().to_string(),
+                        declaration[colon + 1..].trim().to_string(),
+                    ));
+                }
+            }
+        } else {
+            attributes.push((name, value));
+        }
+    }
+
+    attributes.extend(style);
+    attributes
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes.iter().rev().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+}
+
+// Parses a length in user units. Percentages and other units are not supported.
+fn parse_length(value: &str) -> Option<f32> {
+    value.trim().trim_end_matches("px").trim().parse::<f32>().ok()
+}
+
+fn parse_numbers(value: &str) -> Vec<f32> {
+    value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse::<f32>().ok())
+        .collect()
+}
+
+fn parse_paint(value: &str) -> Option<SvgPaint> {
+    match value {
+        "none" | "transparent" => Some(SvgPaint::None),
+        "currentColor" => Some(SvgPaint::CurrentColor),
+        "black" => Some(SvgPaint::Color(Color::black())),
+        "white" => Some(SvgPaint::Color(Color::white())),
+        "red" => Some(SvgPaint::Color(Color::rgb(255, 0, 0))),
+        "green" => Some(SvgPaint::Color(Color::rgb(0, 128, 0))),
+        "blue" => Some(SvgPaint::Color(Color::rgb(0, 0, 255))),
+        "gray" | "grey" => Some(SvgPaint::Color(Color::rgb(128, 128, 128))),
+
+        _ => {
+            if let Some(hex) = value.strip_prefix('#') {
+                match hex.len() {
+                    3 => {
+                        let expanded: String = hex.chars().flat_map(|c| std::iter::repeat(c).take(2)).collect();
+                        u32::from_str_radix(&expanded, 16)
+                            .ok()
+                            .map(|x| SvgPaint::Color(Color { data: 0xFF00_0000 | x }))
+                    }
+
+                    6 => u32::from_str_radix(hex, 16)
+                        .ok()
+                        .map(|x| SvgPaint::Color(Color { data: 0xFF00_0000 | x })),
+
+                    _ => None,
+                }
+            } else if let Some(args) = value.strip_prefix("rgb(").and_then(|v| v.strip_suffix(')')) {
+                let values = parse_numbers(args);
+                if values.len() == 3 {
+                    Some(SvgPaint::Color(Color::rgb(values[0] as u8, values[1] as u8, values[2] as u8)))
+                } else {
+                    None
+                }
+            } else {
+                // Unsupported paint servers such as gradients
+                None
+            }
+        }
+    }
+}
+
+// Parses a transform list such as "translate(10 10) rotate(45)"
+fn parse_transform(value: &str) -> Transform2D {
+    let mut transforms = Vec::new();
+    let mut rest = value;
+
+    while let Some(open) = rest.find('(') {
+        let name = rest[..open].trim().trim_start_matches(',').trim();
+        let close = match rest[open..].find(')') {
+            Some(close) => open + close,
+            None => break,
+        };
+
+        let args = parse_numbers(&rest[open + 1..close]);
+        rest = &rest[close + 1..];
+
+        let arg = |index: usize, default: f32| args.get(index).cloned().unwrap_or(default);
+
+        let transform = match name {
+            "matrix" if args.len() == 6 => Transform2D::new(args[0], args[1], args[2], args[3], args[4], args[5]),
+            "translate" => Transform2D::new(1.0, 0.0, 0.0, 1.0, arg(0, 0.0), arg(1, 0.0)),
+            "scale" => Transform2D::new(arg(0, 1.0), 0.0, 0.0, arg(1, arg(0, 1.0)), 0.0, 0.0),
+            "rotate" => {
+                let (sin, cos) = arg(0, 0.0).to_radians().sin_cos();
+                let (cx, cy) = (arg(1, 0.0), arg(2, 0.0));
+                // Rotation about (cx, cy)
+                Transform2D::new(cos, sin, -sin, cos, cx - cos * cx + sin * cy, cy - sin * cx - cos * cy)
+            }
+            "skewX" => Transform2D::new(1.0, 0.0, arg(0, 0.0).to_radians().tan(), 1.0, 0.0, 0.0),
+            "skewY" => Transform2D::new(1.0, arg(0, 0.0).to_radians().tan(), 0.0, 1.0, 0.0, 0.0),
+            _ => Transform2D::identity(),
+        };
+
+        transforms.push(transform);
+    }
+
+    // The rightmost transform in the list is applied first
+    let mut result = Transform2D::identity();
+    for transform in transforms.iter().rev() {
+        result.multiply(transform);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICON: &str = r##"<?xml version="1.0"?>
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+            <!-- A comment -->
+            <defs><path d="M0 0 L1 1"/></defs>
+            <g fill="none" stroke="currentColor" style="stroke-width: 2">
+                <circle cx="12" cy="12" r="10"/>
+                <path d="M12 6 v6 l4 2" stroke-linecap="round"/>
+            </g>
+            <rect x="2" y="2" width="4" height="4" fill="#f00"/>
+        </svg>
+    "##;
+
+    /// Shapes in groups inherit the group presentation attributes
+    #[test]
+    fn parse_icon() {
+        let svg = parse_svg(ICON).unwrap();
+        assert_eq!(svg.view_box, (0.0, 0.0, 24.0, 24.0));
+        assert_eq!(svg.shapes.len(), 3);
+
+        assert_eq!(svg.shapes[0].fill, SvgPaint::None);
+        assert_eq!(svg.shapes[0].stroke, SvgPaint::CurrentColor);
+        assert_eq!(svg.shapes[0].stroke_width, 2.0);
+
+        assert_eq!(svg.shapes[2].fill, SvgPaint::Color(Color::rgb(255, 0, 0)));
+        assert_eq!(svg.shapes[2].stroke, SvgPaint::None);
+    }
+
+    /// Group transforms are applied to the path points
+    #[test]
+    fn group_transform() {
+        let svg = parse_svg(r#"<svg viewBox="0 0 10 10"><g transform="translate(5, 5) scale(2)"><path d="M1 1"/></g></svg>"#).unwrap();
+        assert_eq!(svg.shapes[0].commands, vec![PathCommand::MoveTo(7.0, 7.0)]);
+    }
+
+    /// A document without an svg element is an error
+    #[test]
+    fn no_root() {
+        assert_eq!(parse_svg("<path d=\"M0 0\"/>").err(), Some(SvgError::NoRoot));
+    }
+}
@@ -0,0 +1,428 @@
+use std::f32::consts::PI;
+
+use crate::Transform2D;
+
+use super::SvgError;
+
+/// A path command with absolute coordinates.
+///
+/// SVG path data is normalised into this reduced set of commands when parsed. Relative, horizontal, vertical,
+/// smooth and arc commands are converted to their absolute line and bezier equivalents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    BezierTo(f32, f32, f32, f32, f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    Close,
+}
+
+impl PathCommand {
+    /// Returns a copy of the command with all points transformed by the matrix
+    pub fn transformed(&self, t: &Transform2D) -> Self {
+        match *self {
+            PathCommand::MoveTo(x, y) => {
+                let (x, y) = t.transform_point(x, y);
+                PathCommand::MoveTo(x, y)
+            }
+
+            PathCommand::LineTo(x, y) => {
+                let (x, y) = t.transform_point(x, y);
+                PathCommand::LineTo(x, y)
+            }
+
+            PathCommand::BezierTo(x1, y1, x2, y2, x, y) => {
+                let (x1, y1) = t.transform_point(x1, y1);
+                let (x2, y2) = t.transform_point(x2, y2);
+                let (x, y) = t.transform_point(x, y);
+                PathCommand::BezierTo(x1, y1, x2, y2, x, y)
+            }
+
+            PathCommand::QuadTo(x1, y1, x, y) => {
+                let (x1, y1) = t.transform_point(x1, y1);
+                let (x, y) = t.transform_point(x, y);
+                PathCommand::QuadTo(x1, y1, x, y)
+            }
+
+            PathCommand::Close => PathCommand::Close,
+        }
+    }
+}
+
+// Splits path data into commands, numbers and flags
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            data: data.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.data.len() && (self.data[self.pos].is_ascii_whitespace() || self.data[self.pos] == b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.pos >= self.data.len()
+    }
+
+    fn next_command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        match self.data.get(self.pos) {
+            Some(&c) if c.is_ascii_alphabetic() && c != b'e' && c != b'E' => {
+                self.pos += 1;
+                Some(c)
+            }
+
+            _ => None,
+        }
+    }
+
+    fn has_number(&mut self) -> bool {
+        self.skip_separators();
+        match self.data.get(self.pos) {
+            Some(&c) => c.is_ascii_digit() || c == b'.' || c == b'-' || c == b'+',
+            None => false,
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, SvgError> {
+        self.skip_separators();
+        let start = self.pos;
+
+        if let Some(b'-') | Some(b'+') = self.data.get(self.pos) {
+            self.pos += 1;
+        }
+
+        let mut digits = self.digits();
+
+        if let Some(b'.') = self.data.get(self.pos) {
+            self.pos += 1;
+            digits += self.digits();
+        }
+
+        if digits == 0 {
+            return Err(self.error(start));
+        }
+
+        if let Some(b'e') | Some(b'E') = self.data.get(self.pos) {
+            let exponent = self.pos;
+            self.pos += 1;
+            if let Some(b'-') | Some(b'+') = self.data.get(self.pos) {
+                self.pos += 1;
+            }
+
+            if self.digits() == 0 {
+                self.pos = exponent;
+            }
+        }
+
+        let text = std::str::from_utf8(&self.data[start..self.pos]).map_err(|_| self.error(start))?;
+        text.parse::<f32>().map_err(|_| self.error(start))
+    }
+
+    // Arc flags may be written without separators, e.g. "a1 1 0 011 1"
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        self.skip_separators();
+        match self.data.get(self.pos) {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            }
+
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            }
+
+            _ => Err(self.error(self.pos)),
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.pos < self.data.len() && self.data[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+
+        self.pos - start
+    }
+
+    fn error(&self, start: usize) -> SvgError {
+        let end = (start + 10).min(self.data.len());
+        SvgError::InvalidPathData(String::from_utf8_lossy(&self.data[start..end]).to_string())
+    }
+}
+
+/// Parses the `d` attribute of an SVG path element.
+pub fn parse_path_data(data: &str) -> Result<Vec<PathCommand>, SvgError> {
+    let mut lexer = Lexer::new(data);
+    let mut commands = Vec::new();
+
+    // Current point
+    let (mut cx, mut cy) = (0.0f32, 0.0f32);
+    // Start of the current subpath
+    let (mut sx, mut sy) = (0.0f32, 0.0f32);
+    // Last control points, used by the smooth curve commands
+    let mut last_cubic: Option<(f32, f32)> = None;
+    let mut last_quad: Option<(f32, f32)> = None;
+
+    let mut prev: Option<u8> = None;
+
+    while !lexer.at_end() {
+        let command = match lexer.next_command() {
+            Some(command) => command,
+            // Repeated arguments without a command letter repeat the previous command
+            None => match prev {
+                Some(b'M') if lexer.has_number() => b'L',
+                Some(b'm') if lexer.has_number() => b'l',
+                Some(c) if c != b'Z' && c != b'z' && lexer.has_number() => c,
+                _ => return Err(lexer.error(lexer.pos)),
+            },
+        };
+
+        if prev.is_none() && command != b'M' && command != b'm' {
+            return Err(SvgError::InvalidPathData(data.chars().take(10).collect()));
+        }
+
+        let relative = command.is_ascii_lowercase();
+        let (ox, oy) = if relative { (cx, cy) } else { (0.0, 0.0) };
+
+        let mut cubic = None;
+        let mut quad = None;
+
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                cx = lexer.number()? + ox;
+                cy = lexer.number()? + oy;
+                sx = cx;
+                sy = cy;
+                commands.push(PathCommand::MoveTo(cx, cy));
+            }
+
+            b'L' => {
+                cx = lexer.number()? + ox;
+                cy = lexer.number()? + oy;
+                commands.push(PathCommand::LineTo(cx, cy));
+            }
+
+            b'H' => {
+                cx = lexer.number()? + ox;
+                commands.push(PathCommand::LineTo(cx, cy));
+            }
+
+            b'V' => {
+                cy = lexer.number()? + oy;
+                commands.push(PathCommand::LineTo(cx, cy));
+            }
+
+            b'C' | b'S' => {
+                let (x1, y1) = if command.to_ascii_uppercase() == b'C' {
+                    (lexer.number()? + ox, lexer.number()? + oy)
+                } else {
+                    // Reflection of the previous control point about the current point
+                    last_cubic.map(|(lx, ly)| (2.0 * cx - lx, 2.0 * cy - ly)).unwrap_or((cx, cy))
+                };
+
+                let x2 = lexer.number()? + ox;
+                let y2 = lexer.number()? + oy;
+                cx = lexer.number()? + ox;
+                cy = lexer.number()? + oy;
+                commands.push(PathCommand::BezierTo(x1, y1, x2, y2, cx, cy));
+                cubic = Some((x2, y2));
+            }
+
+            b'Q' | b'T' => {
+                let (x1, y1) = if command.to_ascii_uppercase() == b'Q' {
+                    (lexer.number()? + ox, lexer.number()? + oy)
+                } else {
+                    last_quad.map(|(lx, ly)| (2.0 * cx - lx, 2.0 * cy - ly)).unwrap_or((cx, cy))
+                };
+
+                cx = lexer.number()? + ox;
+                cy = lexer.number()? + oy;
+                commands.push(PathCommand::QuadTo(x1, y1, cx, cy));
+                quad = Some((x1, y1));
+            }
+
+            b'A' => {
+                let rx = lexer.number()?;
+                let ry = lexer.number()?;
+                let rotation = lexer.number()?;
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let x = lexer.number()? + ox;
+                let y = lexer.number()? + oy;
+                arc_to_beziers(cx, cy, rx, ry, rotation, large_arc, sweep, x, y, &mut commands);
+                cx = x;
+                cy = y;
+            }
+
+            b'Z' => {
+                commands.push(PathCommand::Close);
+                cx = sx;
+                cy = sy;
+            }
+
+            _ => {
+                return Err(SvgError::InvalidPathData((command as char).to_string()));
+            }
+        }
+
+        last_cubic = cubic;
+        last_quad = quad;
+        prev = Some(command);
+    }
+
+    Ok(commands)
+}
+
+/// Converts an SVG elliptical arc into cubic bezier segments of at most 90 degrees.
+///
+/// See the endpoint to center parameterization in the SVG specification (appendix F.6).
+#[allow(clippy::too_many_arguments)]
+fn arc_to_beziers(
+    x1: f32,
+    y1: f32,
+    rx: f32,
+    ry: f32,
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    x2: f32,
+    y2: f32,
+    commands: &mut Vec<PathCommand>,
+) {
+    if x1 == x2 && y1 == y2 {
+        return;
+    }
+
+    let mut rx = rx.abs();
+    let mut ry = ry.abs();
+
+    if rx == 0.0 || ry == 0.0 {
+        commands.push(PathCommand::LineTo(x2, y2));
+        return;
+    }
+
+    let (sin_phi, cos_phi) = rotation.to_radians().sin_cos();
+
+    let dx = (x1 - x2) / 2.0;
+    let dy = (y1 - y2) / 2.0;
+    let x1p = cos_phi * dx + sin_phi * dy;
+    let y1p = -sin_phi * dx + cos_phi * dy;
+
+    // Scale up the radii if they are too small to span the end points
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+    let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+
+    let cxp = coef * rx * y1p / ry;
+    let cyp = -coef * ry * x1p / rx;
+
+    let center_x = cos_phi * cxp - sin_phi * cyp + (x1 + x2) / 2.0;
+    let center_y = sin_phi * cxp + cos_phi * cyp + (y1 + y2) / 2.0;
+
+    let u = ((x1p - cxp) / rx, (y1p - cyp) / ry);
+    let v = ((-x1p - cxp) / rx, (-y1p - cyp) / ry);
+
+    let theta = angle_between((1.0, 0.0), u);
+    let mut delta_theta = angle_between(u, v);
+
+    if !sweep && delta_theta > 0.0 {
+        delta_theta -= 2.0 * PI;
+    } else if sweep && delta_theta < 0.0 {
+        delta_theta += 2.0 * PI;
+    }
+
+    let segments = (delta_theta.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let delta = delta_theta / segments as f32;
+    let t = 4.0 / 3.0 * (delta / 4.0).tan();
+
+    // Maps a point on the unit circle to the ellipse
+    let map = |x: f32, y: f32| {
+        let x = x * rx;
+        let y = y * ry;
+        (cos_phi * x - sin_phi * y + center_x, sin_phi * x + cos_phi * y + center_y)
+    };
+
+    let mut angle = theta;
+    for segment in 0..segments {
+        let (s1, c1) = angle.sin_cos();
+        let (s2, c2) = (angle + delta).sin_cos();
+
+        let (cx1, cy1) = map(c1 - t * s1, s1 + t * c1);
+        let (cx2, cy2) = map(c2 + t * s2, s2 - t * c2);
+
+        // Use the exact end point for the last segment to avoid accumulating error
+        let (x, y) = if segment == segments - 1 { (x2, y2) } else { map(c2, s2) };
+
+        commands.push(PathCommand::BezierTo(cx1, cy1, cx2, cy2, x, y));
+        angle += delta;
+    }
+}
+
+fn angle_between(u: (f32, f32), v: (f32, f32)) -> f32 {
+    (u.0 * v.1 - u.1 * v.0).atan2(u.0 * v.0 + u.1 * v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Relative and absolute commands produce the same absolute points
+    #[test]
+    fn relative_commands() {
+        let absolute = parse_path_data("M10 10 L20 10 L20 20 Z").unwrap();
+        let relative = parse_path_data("m10,10 l10,0 v10 z").unwrap();
+        assert_eq!(absolute, relative);
+    }
+
+    /// Numbers can be packed without separators
+    #[test]
+    fn packed_numbers() {
+        let commands = parse_path_data("M1.5.5-2-3").unwrap();
+        assert_eq!(commands, vec![PathCommand::MoveTo(1.5, 0.5), PathCommand::LineTo(-2.0, -3.0)]);
+    }
+
+    /// Smooth curves reflect the previous control point
+    #[test]
+    fn smooth_cubic() {
+        let commands = parse_path_data("M0 0 C0 10 10 10 10 0 S20 -10 20 0").unwrap();
+        assert_eq!(commands[2], PathCommand::BezierTo(10.0, -10.0, 20.0, -10.0, 20.0, 0.0));
+    }
+
+    /// Arcs are converted to beziers ending exactly at the arc end point
+    #[test]
+    fn arc() {
+        let commands = parse_path_data("M0 0 A10 10 0 0 1 20 0").unwrap();
+        assert_eq!(commands.len(), 3);
+        match commands.last() {
+            Some(PathCommand::BezierTo(_, _, _, _, x, y)) => assert_eq!((*x, *y), (20.0, 0.0)),
+            _ => panic!("expected a bezier"),
+        }
+    }
+
+    /// Path data must start with a move command
+    #[test]
+    fn missing_move() {
+        assert!(parse_path_data("L10 10").is_err());
+    }
+}
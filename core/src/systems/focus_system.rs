@@ -0,0 +1,179 @@
+use crate::{ArrowNavigation, Code, Display, Entity, PseudoClass, State, TreeExt, Visibility};
+
+/// Returns true if the entity can be focused with the keyboard.
+///
+/// The entity must be focusable and not disabled, and neither it nor any of its ancestors can be hidden.
+pub fn is_keyboard_focusable(state: &State, entity: Entity) -> bool {
+    if !state.data.get_focusable(entity) {
+        return false;
+    }
+
+    let disabled = state
+        .style
+        .pseudo_classes
+        .get(entity)
+        .cloned()
+        .unwrap_or_default()
+        .contains(PseudoClass::DISABLED);
+
+    if disabled || state.data.get_opacity(entity) == 0.0 {
+        return false;
+    }
+
+    entity.parent_iter(&state.tree).all(|ancestor| {
+        state.data.get_visibility(ancestor) != Visibility::Invisible
+            && state.style.display.get(ancestor) != Some(&Display::None)
+    })
+}
+
+/// Returns the focus scope containing the entity, which is the closest ancestor (or the entity itself) marked with
+/// `set_focus_scope`, or the root if there isn't one.
+pub fn focus_scope(state: &State, entity: Entity) -> Entity {
+    entity
+        .parent_iter(&state.tree)
+        .find(|ancestor| state.style.focus_scope.get(*ancestor) == Some(&true))
+        .unwrap_or(Entity::root())
+}
+
+/// Returns the entities inside of a focus scope which are visited by Tab, in order.
+///
+/// Entities with a positive tab-index come first, in increasing order of tab-index, followed by the entities with a
+/// tab-index of 0 (the default) in tree order. Entities with a negative tab-index are skipped, as are the entities
+/// inside of a nested focus scope. The members of a composite widget with arrow navigation are a single stop, which is
+/// the focused member or else the first member.
+pub fn tab_order(state: &State, scope: Entity) -> Vec<Entity> {
+    let mut order = Vec::new();
+    // The composite widgets which already have a stop
+    let mut composites = Vec::new();
+
+    for entity in scope.branch_iter(&state.tree).skip(1) {
+        let tab_index = state.style.tab_index.get(entity).cloned().unwrap_or_default();
+
+        if tab_index < 0 || focus_scope(state, entity) != scope || !is_keyboard_focusable(state, entity) {
+            continue;
+        }
+
+        if let Some(composite) = composite_of(state, entity) {
+            if composite_of(state, state.focused) == Some(composite) {
+                if entity != state.focused {
+                    continue;
+                }
+            } else if composites.contains(&composite) {
+                continue;
+            } else {
+                composites.push(composite);
+            }
+        }
+
+        order.push((tab_index, entity));
+    }
+
+    // Positive tab-indices come before the default, and the sort is stable so ties stay in tree order
+    order.sort_by_key(|(tab_index, _)| if *tab_index > 0 { (0, *tab_index) } else { (1, 0) });
+
+    order.into_iter().map(|(_, entity)| entity).collect()
+}
+
+/// Moves the focus to the next entity in the tab order of the focus scope, wrapping around to the first.
+///
+/// A next entity set with `set_next_focus` takes precedence over the tab order.
+pub fn focus_next(state: &mut State) {
+    let next = state.style.focus_order.get(state.focused).map_or(Entity::null(), |focus_order| focus_order.next);
+    if next != Entity::null() {
+        state.set_focus(next);
+        return;
+    }
+
+    let order = tab_order(state, focus_scope(state, state.focused));
+
+    let next = match order.iter().position(|entity| *entity == state.focused) {
+        Some(index) => order.get(index + 1).or(order.first()),
+        None => order.first(),
+    };
+
+    if let Some(next) = next.cloned() {
+        state.set_focus(next);
+    }
+}
+
+/// Moves the focus to the previous entity in the tab order of the focus scope, wrapping around to the last.
+///
+/// A previous entity set with `set_prev_focus` takes precedence over the tab order.
+pub fn focus_prev(state: &mut State) {
+    let prev = state.style.focus_order.get(state.focused).map_or(Entity::null(), |focus_order| focus_order.prev);
+    if prev != Entity::null() {
+        state.set_focus(prev);
+        return;
+    }
+
+    let order = tab_order(state, focus_scope(state, state.focused));
+
+    let prev = match order.iter().position(|entity| *entity == state.focused) {
+        Some(index) if index > 0 => order.get(index - 1),
+        _ => order.last(),
+    };
+
+    if let Some(prev) = prev.cloned() {
+        state.set_focus(prev);
+    }
+}
+
+/// Moves the focus between the members of the composite widget containing the focused entity, in response to a key.
+///
+/// The arrow keys allowed by the [ArrowNavigation] of the composite move to the previous or next member, wrapping
+/// around, and Home and End move to the first and last member. Returns true if the key moved the focus.
+pub fn focus_arrow(state: &mut State, code: Code) -> bool {
+    let composite = match composite_of(state, state.focused) {
+        Some(composite) => composite,
+        None => return false,
+    };
+
+    let navigation = state.style.arrow_navigation.get(composite).cloned();
+    let horizontal = navigation != Some(ArrowNavigation::Vertical);
+    let vertical = navigation != Some(ArrowNavigation::Horizontal);
+
+    let members = composite
+        .branch_iter(&state.tree)
+        .skip(1)
+        .filter(|entity| composite_of(state, *entity) == Some(composite) && is_keyboard_focusable(state, *entity))
+        .collect::<Vec<_>>();
+
+    let index = match members.iter().position(|entity| *entity == state.focused) {
+        Some(index) => index,
+        None => return false,
+    };
+
+    let target = match code {
+        Code::ArrowLeft if horizontal => members.get(index.wrapping_sub(1)).or(members.last()),
+        Code::ArrowUp if vertical => members.get(index.wrapping_sub(1)).or(members.last()),
+        Code::ArrowRight if horizontal => members.get(index + 1).or(members.first()),
+        Code::ArrowDown if vertical => members.get(index + 1).or(members.first()),
+        Code::Home => members.first(),
+        Code::End => members.last(),
+        _ => None,
+    };
+
+    match target.cloned() {
+        Some(target) => {
+            state.set_focus(target);
+            true
+        }
+
+        None => false,
+    }
+}
+
+// Returns the closest ancestor with arrow navigation, which doesn't include the entity itself or anything beyond its focus scope
+fn composite_of(state: &State, entity: Entity) -> Option<Entity> {
+    for ancestor in entity.parent_iter(&state.tree).skip(1) {
+        if state.style.arrow_navigation.get(ancestor).is_some() {
+            return Some(ancestor);
+        }
+
+        if state.style.focus_scope.get(ancestor) == Some(&true) {
+            return None;
+        }
+    }
+
+    None
+}
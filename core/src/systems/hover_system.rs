@@ -93,6 +93,7 @@ pub fn apply_hover(state: &mut State) {
             && cy >= clip_region.y
             && cy < (posy + height)
             && cy < (clip_region.y + clip_region.h)
+            && state.data.get_rounded_clips(entity).iter().all(|clip| clip.contains(cx, cy))
         {
             hovered_widget = entity;
             if entity.is_over(state) == false {
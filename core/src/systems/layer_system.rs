@@ -1,36 +1,92 @@
-use crate::{Entity, State, Tree, TreeExt};
+use crate::{Entity, Layer, LayerMode, State, Tree};
 
+use std::collections::HashMap;
 
 
 // Calculates which layers entities should be drawn to
-// 
-// fn apply_layers(state: &mut State, tree: &Tree) {
-
-//     for (_, layer) in state.layers.iter_mut() {
-//         layer.count = 0;
-//     }
-
-//     for entity in tree.into_iter() {
-        
-//         // Skip Window
-//         if entity == Entity::root() {
-//             continue;
-//         }
-
-//         let parent = entity.parent(tree).unwrap();
-    
-//         let z_index = state.data.get_z_index(entity);
-        
-//         if let Some(layer) = state.layers.get(&z_index) {
-//             layer.count += 1;
-//         } else {
-
-//         }
-//     }
-// }
-
-// Each z-index gets its own layer
-// Need a way to figure out if no entities are linked a particular z-index
-//     Loop through layers and set internal count to 0
-//     Loop through entities and increment count for layer
-//     Remove layers which have 0 count
\ No newline at end of file
+//
+// Each z-index gets its own layer, which is retained if any of its entities has a `layer: retained` style.
+// Layers which no longer have any entities are left with a count of 0 so that the event manager
+// can delete their images before removing them. A layer which gains or loses entities needs repainting.
+pub(crate) fn apply_layers(state: &mut State, tree: &Tree) {
+    let mut previous_counts = HashMap::new();
+
+    for (z_index, layer) in state.layers.iter_mut() {
+        previous_counts.insert(*z_index, layer.count);
+        layer.count = 0;
+        layer.retained = false;
+    }
+
+    for entity in tree.into_iter() {
+        // Skip Window
+        if entity == Entity::root() {
+            continue;
+        }
+
+        let z_index = state.data.get_z_index(entity);
+        let retained = state.style.layer.get(entity).cloned().unwrap_or_default() == LayerMode::Retained;
+
+        let layer = state.layers.entry(z_index).or_insert_with(|| Layer {
+            dirty: true,
+            ..Default::default()
+        });
+
+        layer.count += 1;
+        layer.retained |= retained;
+    }
+
+    for (z_index, layer) in state.layers.iter_mut() {
+        if previous_counts.get(z_index) != Some(&layer.count) {
+            layer.dirty = true;
+        }
+    }
+}
+
+// Marks the layer which an entity is drawn into as needing to be repainted.
+// A null entity or the root marks every layer, e.g. for a change which could affect any entity.
+pub(crate) fn invalidate_layers(state: &mut State, entity: Entity) {
+    if entity == Entity::null() || entity == Entity::root() {
+        for (_, layer) in state.layers.iter_mut() {
+            layer.dirty = true;
+        }
+    } else {
+        let z_index = state.data.get_z_index(entity);
+        if let Some(layer) = state.layers.get_mut(&z_index) {
+            layer.dirty = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Layers are counted per z-index, retained by style, and only the layer of a redrawn entity is dirtied
+    #[test]
+    fn layers_per_z_index() {
+        let mut state = State::new();
+        let background = state.add(Entity::root());
+        let meter = state.add(Entity::root());
+
+        state.data.set_z_index(background, -1);
+        state.style.layer.insert(background, LayerMode::Retained);
+
+        let tree = state.tree.clone();
+        apply_layers(&mut state, &tree);
+
+        assert_eq!(state.layers[&-1].count, 1);
+        assert!(state.layers[&-1].retained);
+        assert!(!state.layers[&0].retained);
+
+        for (_, layer) in state.layers.iter_mut() {
+            layer.dirty = false;
+        }
+
+        invalidate_layers(&mut state, meter);
+        assert!(state.layers[&0].dirty);
+        assert!(!state.layers[&-1].dirty);
+
+        invalidate_layers(&mut state, Entity::root());
+        assert!(state.layers[&-1].dirty);
+    }
+}
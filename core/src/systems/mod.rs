@@ -3,8 +3,8 @@
 extern crate cssparser;
 
 mod style_system;
-pub(crate) use style_system::{apply_styles, apply_visibility, apply_z_ordering};
-pub use style_system::apply_clipping;
+pub(crate) use style_system::{apply_visibility, apply_z_ordering};
+pub use style_system::{apply_clipping, apply_styles};
 
 // mod style_system_new;
 // pub(crate) use style_system_new::apply_styles2;
@@ -12,11 +12,11 @@ pub use style_system::apply_clipping;
 mod hover_system;
 pub use hover_system::*;
 
+mod focus_system;
+pub use focus_system::*;
+
 mod new_layout;
 pub(crate) use new_layout::*;
 
 mod layer_system;
-pub use layer_system::*;
-
-// pub mod draw_system;
-// pub use draw_system::*;
+pub(crate) use layer_system::{apply_layers, invalidate_layers};
@@ -1,4 +1,4 @@
-use crate::{BoundingBox, Display, Entity, Overflow, PropGet, PropSet, Property, SelectorRelation, Rule, Selector, State, Tree, TreeExt, Visibility};
+use crate::{BlendMode, BoundingBox, Display, Entity, Group, Overflow, ProfiledSystem, PropGet, PropSet, Property, SelectorRelation, Rule, RoundedClip, Selector, State, Tree, TreeExt, Units, Visibility};
 
 
 pub fn apply_z_ordering(state: &mut State, tree: &Tree) {
@@ -20,33 +20,39 @@ pub fn apply_z_ordering(state: &mut State, tree: &Tree) {
 
 pub fn apply_clipping(state: &mut State, tree: &Tree) {
     //println!("Apply Clipping");
+    let profile_start = state.profiler.start();
+    let mut clipped = 0;
+
     for entity in tree.into_iter() {
         if entity == Entity::root() {
             continue;
         }
 
+        clipped += 1;
+
         let parent = tree.get_parent(entity).unwrap();
 
         let mut parent_clip_region = state.data.get_clip_region(parent);
-        let parent_border_width = state.style.border_width.get(parent).cloned().unwrap_or_default().value_or(0.0, 0.0);
+        let [parent_border_top, parent_border_right, parent_border_bottom, parent_border_left] = border_widths(state, parent);
 
         //println!("Parent border width: {}", parent_border_width);
-        parent_clip_region.x += parent_border_width / 2.0;
-        parent_clip_region.y += parent_border_width / 2.0;
-        parent_clip_region.w -= parent_border_width;
-        parent_clip_region.h -= parent_border_width;
+        parent_clip_region.x += parent_border_left / 2.0;
+        parent_clip_region.y += parent_border_top / 2.0;
+        parent_clip_region.w -= (parent_border_left + parent_border_right) / 2.0;
+        parent_clip_region.h -= (parent_border_top + parent_border_bottom) / 2.0;
 
 
 
         let root_clip_region = state.data.get_clip_region(Entity::root());
+        let parent_rounded_clip = state.data.get_rounded_clip(parent);
 
         if entity.get_overflow(state) == Overflow::Hidden {
             if let Some(clip_widget) = state.style.clip_widget.get(entity).cloned() {
-                let clip_widget_border_width = state.style.border_width.get(clip_widget).cloned().unwrap_or_default().value_or(0.0, 0.0);
-                let clip_x = state.data.get_posx(clip_widget) + clip_widget_border_width;
-                let clip_y = state.data.get_posy(clip_widget) + clip_widget_border_width;
-                let clip_w = state.data.get_width(clip_widget) - 2.0 * clip_widget_border_width;
-                let clip_h = state.data.get_height(clip_widget) - 2.0 * clip_widget_border_width;
+                let [border_top, border_right, border_bottom, border_left] = border_widths(state, clip_widget);
+                let clip_x = state.data.get_posx(clip_widget) + border_left;
+                let clip_y = state.data.get_posy(clip_widget) + border_top;
+                let clip_w = state.data.get_width(clip_widget) - border_left - border_right;
+                let clip_h = state.data.get_height(clip_widget) - border_top - border_bottom;
 
                 let mut intersection = BoundingBox::default();
                 intersection.x = clip_x.max(parent_clip_region.x);
@@ -65,16 +71,84 @@ pub fn apply_clipping(state: &mut State, tree: &Tree) {
                 };
 
                 state.data.set_clip_region(entity, intersection);
+
+                // The outer rounded clips are found through the clip widget, see `get_rounded_clips`
+                let rounded_clip = rounded_clip(state, clip_widget).or(parent_rounded_clip);
+                state.data.set_rounded_clip(entity, rounded_clip);
             } else {
                 state.data.set_clip_region(entity, parent_clip_region);
+                state.data.set_rounded_clip(entity, parent_rounded_clip);
             }
         } else {
             state.data.set_clip_region(entity, root_clip_region);
+            state.data.set_rounded_clip(entity, None);
         }
 
         //let clip_region = state.data.get_clip_region(entity);
         //println!("Entity: {}  Clip Region: {:?}", entity, clip_region);
     }
+
+    state.profiler.record(ProfiledSystem::Clipping, profile_start, clipped);
+}
+
+// Returns the clip shape inside the border of a clip widget, or None if the clip widget has no rounded corners
+fn rounded_clip(state: &State, clip_widget: Entity) -> Option<RoundedClip> {
+    let style = &state.style;
+    let bounds = state.data.get_bounds(clip_widget);
+    let [border_top, border_right, border_bottom, border_left] = border_widths(state, clip_widget);
+
+    let radius = |value: Option<&Units>, border: f32| {
+        let radius = match value.cloned().unwrap_or_default() {
+            Units::Pixels(val) => val,
+            Units::Percentage(val) => bounds.w.min(bounds.h) * (val / 100.0),
+            _ => 0.0,
+        };
+
+        (radius - border).max(0.0)
+    };
+
+    let radii = [
+        radius(style.border_radius_top_left.get(clip_widget), border_left.max(border_top)),
+        radius(style.border_radius_top_right.get(clip_widget), border_right.max(border_top)),
+        radius(style.border_radius_bottom_right.get(clip_widget), border_right.max(border_bottom)),
+        radius(style.border_radius_bottom_left.get(clip_widget), border_left.max(border_bottom)),
+    ];
+
+    if radii.iter().all(|radius| *radius <= 0.0) {
+        return None;
+    }
+
+    Some(RoundedClip {
+        entity: clip_widget,
+        bounds: BoundingBox {
+            x: bounds.x + border_left,
+            y: bounds.y + border_top,
+            w: bounds.w - border_left - border_right,
+            h: bounds.h - border_top - border_bottom,
+        },
+        radii,
+        shapes: [
+            style.border_shape_top_left.get(clip_widget).cloned().unwrap_or_default(),
+            style.border_shape_top_right.get(clip_widget).cloned().unwrap_or_default(),
+            style.border_shape_bottom_right.get(clip_widget).cloned().unwrap_or_default(),
+            style.border_shape_bottom_left.get(clip_widget).cloned().unwrap_or_default(),
+        ],
+    })
+}
+
+// Returns the border widths of an entity in pixels, ordered top, right, bottom, left
+fn border_widths(state: &State, entity: Entity) -> [f32; 4] {
+    let style = &state.style;
+    let side = |width: Option<&Units>| {
+        width.or_else(|| style.border_width.get(entity)).cloned().unwrap_or_default().value_or(0.0, 0.0)
+    };
+
+    [
+        side(style.border_width_top.get(entity)),
+        side(style.border_width_right.get(entity)),
+        side(style.border_width_bottom.get(entity)),
+        side(style.border_width_left.get(entity)),
+    ]
 }
 
 pub fn apply_visibility(state: &mut State, tree: &Tree) {
@@ -112,8 +186,34 @@ pub fn apply_visibility(state: &mut State, tree: &Tree) {
         let parent_opacity = state.data.get_opacity(parent);
 
         let opacity = state.style.opacity.get(entity).cloned().unwrap_or_default();
+        let blend_mode = state.style.mix_blend_mode.get(entity).cloned().unwrap_or_default();
+
+        // A translucent entity with children is composited as a group, so that its children fade as one
+        // rather than showing through each other. The opacity of a group is applied when it is composited.
+        // Entities which are fully transparent aren't drawn at all, so they don't need a group.
+        let has_children = tree.get_first_child(entity).is_some();
+        let group = if opacity.0 > 0.0 && ((opacity.0 < 1.0 && has_children) || blend_mode != BlendMode::Normal) {
+            Some(Group {
+                opacity: opacity.0,
+                blend_mode,
+            })
+        } else {
+            None
+        };
 
-        state.data.set_opacity(entity, opacity.0 * parent_opacity);
+        let computed_opacity = if group.is_some() {
+            parent_opacity
+        } else {
+            opacity.0 * parent_opacity
+        };
+
+        // Changing the opacity of a group changes how its descendants are drawn, so each entity is checked
+        if state.data.get_opacity(entity) != computed_opacity || state.data.get_group(entity) != group {
+            state.damage.mark(entity);
+        }
+
+        state.data.set_opacity(entity, computed_opacity);
+        state.data.set_group(entity, group);
     }
 }
 
@@ -171,8 +271,12 @@ fn check_match(state: &State, entity: Entity, selector: &Selector) -> bool {
     return true;
 }
 
+/// Links the style properties of each entity of the tree to the most specific style rules which match it.
 pub fn apply_styles(state: &mut State, tree: &Tree) {
     //println!("RESTYLE");
+    let profile_start = state.profiler.start();
+    let mut restyled = 0;
+
     // Loop through all entities
     for entity in tree.into_iter() {
         // Skip the root
@@ -180,6 +284,8 @@ pub fn apply_styles(state: &mut State, tree: &Tree) {
             continue;
         }
 
+        restyled += 1;
+
         // Create a list of style rules that match this entity
         let mut matched_rules: Vec<Rule> = Vec::new();
 
@@ -265,6 +371,19 @@ pub fn apply_styles(state: &mut State, tree: &Tree) {
             should_redraw = true;
         }
 
+        if state.style.layer.link(entity, &matched_rules) {
+            should_relayout = true;
+            should_redraw = true;
+        }
+
+        if state.style.mix_blend_mode.link(entity, &matched_rules) {
+            should_relayout = true;
+            should_redraw = true;
+        }
+
+        // Only read when the focus moves, so nothing needs updating
+        state.style.tab_index.link(entity, &matched_rules);
+
         // Currently doesn't do anything - TODO
         state.style.overflow.link(entity, &matched_rules);
 
@@ -349,6 +468,63 @@ pub fn apply_styles(state: &mut State, tree: &Tree) {
             should_redraw = true;
         }
 
+        if state.style.border_style.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        // Border Sides
+        if state.style.border_width_top.link(entity, &matched_rules) {
+            should_relayout = true;
+            should_redraw = true;
+        }
+
+        if state.style.border_width_right.link(entity, &matched_rules) {
+            should_relayout = true;
+            should_redraw = true;
+        }
+
+        if state.style.border_width_bottom.link(entity, &matched_rules) {
+            should_relayout = true;
+            should_redraw = true;
+        }
+
+        if state.style.border_width_left.link(entity, &matched_rules) {
+            should_relayout = true;
+            should_redraw = true;
+        }
+
+        if state.style.border_color_top.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_color_right.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_color_bottom.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_color_left.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_style_top.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_style_right.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_style_bottom.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.border_style_left.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
         if state.style.border_shape_top_left.link(entity, &matched_rules) {
             should_redraw = true;
         }
@@ -526,6 +702,28 @@ pub fn apply_styles(state: &mut State, tree: &Tree) {
             should_redraw = true;
         }
 
+        // Box Shadows
+        if state.style.box_shadow.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        // Outline
+        if state.style.outline_width.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.outline_color.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.outline_style.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.outline_offset.link(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
         if state.style.child_left.link(entity, &matched_rules) {
             should_relayout = true;
             should_redraw = true;
@@ -589,9 +787,10 @@ pub fn apply_styles(state: &mut State, tree: &Tree) {
         }
 
         if should_redraw {
-            Entity::root().redraw(state);
+            entity.redraw(state);
             //state.needs_redraw = true;
         }
     }
 
+    state.profiler.record(ProfiledSystem::Style, profile_start, restyled);
 }
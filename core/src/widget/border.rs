@@ -0,0 +1,291 @@
+use crate::{BorderCornerShape, BorderStyle, BoundingBox, Canvas, LineCap, Paint, Path, Solidity};
+
+use std::f32::consts::PI;
+
+// Number of line segments used to approximate each corner (must be even so a corner can be split between its two sides)
+const CORNER_SEGMENTS: usize = 16;
+
+/// One side of a border with the width resolved to pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BorderSide {
+    pub width: f32,
+    pub color: femtovg::Color,
+    pub style: BorderStyle,
+}
+
+/// One corner of a border with the radius resolved to pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BorderCorner {
+    pub radius: f32,
+    pub shape: BorderCornerShape,
+}
+
+/// The outer and inner edges of a border with a different width on each side.
+///
+/// Sides are ordered top, right, bottom, left and corners are ordered top-left, top-right, bottom-right, bottom-left.
+/// Corner `i` joins side `i - 1` to side `i`, going clockwise. Each corner is sampled into points so that the border
+/// can be split between adjacent sides at the middle of the corner.
+pub(crate) struct BorderGeometry {
+    outer: [Vec<(f32, f32)>; 4],
+    inner: [Vec<(f32, f32)>; 4],
+}
+
+impl BorderGeometry {
+    pub fn new(bounds: BoundingBox, widths: [f32; 4], corners: [BorderCorner; 4]) -> Self {
+        let BoundingBox { x, y, w, h } = bounds;
+        let [top, right, bottom, left] = widths;
+
+        let max_radius = 0.5 * w.min(h);
+
+        let mut outer: [Vec<(f32, f32)>; 4] = Default::default();
+        let mut inner: [Vec<(f32, f32)>; 4] = Default::default();
+
+        for (index, corner) in corners.iter().enumerate() {
+            let radius = corner.radius.max(0.0).min(max_radius);
+
+            // Widths of the vertical and horizontal sides adjacent to the corner
+            let (wx, wy) = match index {
+                0 => (left, top),
+                1 => (right, top),
+                2 => (right, bottom),
+                _ => (left, bottom),
+            };
+
+            // Direction from the corner of the bounds towards the center
+            let (dx, dy) = match index {
+                0 => (1.0, 1.0),
+                1 => (-1.0, 1.0),
+                2 => (-1.0, -1.0),
+                _ => (1.0, -1.0),
+            };
+
+            let (corner_x, corner_y) = match index {
+                0 => (x, y),
+                1 => (x + w, y),
+                2 => (x + w, y + h),
+                _ => (x, y + h),
+            };
+
+            let inner_rx = (radius - wx).max(0.0);
+            let inner_ry = (radius - wy).max(0.0);
+
+            let outer_center = (corner_x + dx * radius, corner_y + dy * radius);
+            let inner_center = (corner_x + dx * (wx + inner_rx), corner_y + dy * (wy + inner_ry));
+
+            let start_angle = PI + index as f32 * PI / 2.0;
+
+            outer[index] = sample_corner(outer_center, radius, radius, start_angle, corner.shape);
+            inner[index] = sample_corner(inner_center, inner_rx, inner_ry, start_angle, corner.shape);
+        }
+
+        Self { outer, inner }
+    }
+
+    /// Returns a path around the outer edge of the border
+    pub fn outer_path(&self) -> Path {
+        let mut path = Path::new();
+        contour(&mut path, self.outer.iter().flatten());
+        path
+    }
+
+    /// Draws each side of the border with its own width, color and style
+    pub fn draw(&self, canvas: &mut Canvas, sides: &[BorderSide; 4]) {
+        // A uniform solid border is filled as a single ring to avoid seams between the sides
+        if sides.iter().all(|side| side.style == BorderStyle::Solid && side.color == sides[0].color) {
+            if sides[0].color.a > 0.0 && sides.iter().any(|side| side.width > 0.0) {
+                let mut path = self.outer_path();
+                contour(&mut path, self.inner.iter().flatten().rev());
+                path.solidity(Solidity::Hole);
+                canvas.fill_path(&mut path, Paint::color(sides[0].color));
+            }
+
+            return;
+        }
+
+        for (index, side) in sides.iter().enumerate() {
+            if side.width <= 0.0 || side.color.a <= 0.0 {
+                continue;
+            }
+
+            let next = (index + 1) % 4;
+            let half = CORNER_SEGMENTS / 2;
+
+            // The side runs from the middle of its starting corner to the middle of its ending corner
+            let outer = self.outer[index][half..].iter().chain(self.outer[next][..=half].iter());
+            let inner = self.inner[index][half..].iter().chain(self.inner[next][..=half].iter());
+
+            match side.style {
+                BorderStyle::Solid => {
+                    let mut path = Path::new();
+                    let points = outer.chain(inner.collect::<Vec<_>>().into_iter().rev());
+                    contour(&mut path, points);
+                    canvas.fill_path(&mut path, Paint::color(side.color));
+                }
+
+                BorderStyle::Dashed | BorderStyle::Dotted => {
+                    let center = outer
+                        .zip(inner)
+                        .map(|(o, i)| (0.5 * (o.0 + i.0), 0.5 * (o.1 + i.1)))
+                        .collect::<Vec<_>>();
+
+                    if side.style == BorderStyle::Dashed {
+                        draw_dashes(canvas, &center, side);
+                    } else {
+                        draw_dots(canvas, &center, side);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draws an outline around the bounds of an entity, separated from the bounds by the offset.
+///
+/// Rounded corners of the entity are followed by the outline, with the radius grown to keep the outline a constant width.
+pub(crate) fn stroke_outline(canvas: &mut Canvas, bounds: BoundingBox, corners: [BorderCorner; 4], side: BorderSide, offset: f32) {
+    if side.width <= 0.0 || side.color.a <= 0.0 {
+        return;
+    }
+
+    let grow = offset + side.width;
+
+    let outline_bounds = BoundingBox {
+        x: bounds.x - grow,
+        y: bounds.y - grow,
+        w: bounds.w + 2.0 * grow,
+        h: bounds.h + 2.0 * grow,
+    };
+
+    if outline_bounds.w <= 0.0 || outline_bounds.h <= 0.0 {
+        return;
+    }
+
+    let mut outline_corners = corners;
+    for corner in outline_corners.iter_mut() {
+        if corner.radius > 0.0 {
+            corner.radius = (corner.radius + grow).max(0.0);
+        }
+    }
+
+    BorderGeometry::new(outline_bounds, [side.width; 4], outline_corners).draw(canvas, &[side; 4]);
+}
+
+// Samples a quarter ellipse (or a bevel line between its end points) starting at the given angle
+fn sample_corner(center: (f32, f32), rx: f32, ry: f32, start_angle: f32, shape: BorderCornerShape) -> Vec<(f32, f32)> {
+    let point = |t: f32| {
+        let angle = start_angle + t * PI / 2.0;
+        (center.0 + rx * angle.cos(), center.1 + ry * angle.sin())
+    };
+
+    let start = point(0.0);
+    let end = point(1.0);
+
+    (0..=CORNER_SEGMENTS)
+        .map(|segment| {
+            let t = segment as f32 / CORNER_SEGMENTS as f32;
+            match shape {
+                BorderCornerShape::Round => point(t),
+                BorderCornerShape::Bevel => (start.0 + (end.0 - start.0) * t, start.1 + (end.1 - start.1) * t),
+            }
+        })
+        .collect()
+}
+
+fn contour<'a>(path: &mut Path, mut points: impl Iterator<Item = &'a (f32, f32)>) {
+    if let Some(&(x, y)) = points.next() {
+        path.move_to(x, y);
+        for &(x, y) in points {
+            path.line_to(x, y);
+        }
+        path.close();
+    }
+}
+
+// Cumulative distance along a polyline at each point
+fn distances(points: &[(f32, f32)]) -> Vec<f32> {
+    let mut total = 0.0;
+    let mut result = Vec::with_capacity(points.len());
+    for (index, point) in points.iter().enumerate() {
+        if index > 0 {
+            let prev = points[index - 1];
+            total += ((point.0 - prev.0).powi(2) + (point.1 - prev.1).powi(2)).sqrt();
+        }
+        result.push(total);
+    }
+
+    result
+}
+
+// Returns the point at a distance along a polyline
+fn point_at(points: &[(f32, f32)], distances: &[f32], distance: f32) -> (f32, f32) {
+    for index in 1..points.len() {
+        if distances[index] >= distance {
+            let length = distances[index] - distances[index - 1];
+            let t = if length > 0.0 { (distance - distances[index - 1]) / length } else { 0.0 };
+            let (a, b) = (points[index - 1], points[index]);
+            return (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+        }
+    }
+
+    points.last().cloned().unwrap_or_default()
+}
+
+fn draw_dashes(canvas: &mut Canvas, points: &[(f32, f32)], side: &BorderSide) {
+    let distances = distances(points);
+    let length = distances.last().cloned().unwrap_or_default();
+
+    if length <= 0.0 {
+        return;
+    }
+
+    // Stretch the pattern so that the side starts and ends with a dash
+    let dash = 3.0 * side.width;
+    let gap = 2.0 * side.width;
+    let count = ((length + gap) / (dash + gap)).round().max(1.0);
+    let scale = (length + gap) / (count * (dash + gap));
+    let (dash, gap) = (dash * scale, gap * scale);
+
+    let mut path = Path::new();
+    for n in 0..count as usize {
+        let start = n as f32 * (dash + gap);
+        let end = (start + dash).min(length);
+
+        let (x, y) = point_at(points, &distances, start);
+        path.move_to(x, y);
+        for index in 1..points.len() {
+            if distances[index] > start && distances[index] < end {
+                path.line_to(points[index].0, points[index].1);
+            }
+        }
+        let (x, y) = point_at(points, &distances, end);
+        path.line_to(x, y);
+    }
+
+    let mut paint = Paint::color(side.color);
+    paint.set_line_width(side.width);
+    paint.set_line_cap(LineCap::Butt);
+    canvas.stroke_path(&mut path, paint);
+}
+
+fn draw_dots(canvas: &mut Canvas, points: &[(f32, f32)], side: &BorderSide) {
+    let distances = distances(points);
+    let length = distances.last().cloned().unwrap_or_default();
+    let radius = 0.5 * side.width;
+
+    if length <= 0.0 {
+        return;
+    }
+
+    // Dots are spaced one diameter apart, stretched so that the side starts and ends with a dot
+    let count = (length / (2.0 * side.width)).round().max(1.0) as usize;
+    let spacing = if count > 1 { (length - side.width) / (count - 1) as f32 } else { 0.0 };
+
+    let mut path = Path::new();
+    for n in 0..count {
+        let distance = if count > 1 { radius + n as f32 * spacing } else { 0.5 * length };
+        let (x, y) = point_at(points, &distances, distance);
+        path.circle(x, y, radius);
+    }
+
+    canvas.fill_path(&mut path, Paint::color(side.color));
+}
@@ -195,6 +195,36 @@ impl<'a,T> Builder<'a,T> {
         self
     }
 
+    pub fn set_layer(self, value: LayerMode) -> Self {
+        self.state.style.layer.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_mix_blend_mode(self, value: BlendMode) -> Self {
+        self.state.style.mix_blend_mode.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_tab_index(self, value: i32) -> Self {
+        self.state.style.tab_index.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_focus_scope(self, value: bool) -> Self {
+        self.entity().set_focus_scope(self.state, value);
+
+        self
+    }
+
+    pub fn set_arrow_navigation(self, value: ArrowNavigation) -> Self {
+        self.entity().set_arrow_navigation(self.state, value);
+
+        self
+    }
+
     // Background
     pub fn set_background_color(self, value: Color) -> Self {
         self.state.style.background_color.insert(self.entity, value);
@@ -282,6 +312,38 @@ impl<'a,T> Builder<'a,T> {
         self
     }
 
+    // Box Shadows
+    pub fn set_box_shadows(self, value: Vec<BoxShadow>) -> Self {
+        self.state.style.box_shadow.insert(self.entity, value);
+
+        self
+    }
+
+    // Outline
+    pub fn set_outline_width(self, value: Units) -> Self {
+        self.state.style.outline_width.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_outline_color(self, value: Color) -> Self {
+        self.state.style.outline_color.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_outline_style(self, value: BorderStyle) -> Self {
+        self.state.style.outline_style.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_outline_offset(self, value: Units) -> Self {
+        self.state.style.outline_offset.insert(self.entity, value);
+
+        self
+    }
+
     // Positioning
 
     pub fn set_space(self, value: Units) -> Self {
@@ -411,6 +473,84 @@ impl<'a,T> Builder<'a,T> {
         self
     }
 
+    pub fn set_border_style(self, value: BorderStyle) -> Self {
+        self.state.style.border_style.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_top_width(self, value: Units) -> Self {
+        self.state.style.border_width_top.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_right_width(self, value: Units) -> Self {
+        self.state.style.border_width_right.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_bottom_width(self, value: Units) -> Self {
+        self.state.style.border_width_bottom.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_left_width(self, value: Units) -> Self {
+        self.state.style.border_width_left.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_top_color(self, value: Color) -> Self {
+        self.state.style.border_color_top.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_right_color(self, value: Color) -> Self {
+        self.state.style.border_color_right.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_bottom_color(self, value: Color) -> Self {
+        self.state.style.border_color_bottom.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_left_color(self, value: Color) -> Self {
+        self.state.style.border_color_left.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_top_style(self, value: BorderStyle) -> Self {
+        self.state.style.border_style_top.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_right_style(self, value: BorderStyle) -> Self {
+        self.state.style.border_style_right.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_bottom_style(self, value: BorderStyle) -> Self {
+        self.state.style.border_style_bottom.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_border_left_style(self, value: BorderStyle) -> Self {
+        self.state.style.border_style_left.insert(self.entity, value);
+
+        self
+    }
+
     pub fn set_border_corner_shape(self, value: BorderCornerShape) -> Self {
         self.state.style.border_shape_top_left.insert(self.entity, value);
         self.state.style.border_shape_top_right.insert(self.entity, value);
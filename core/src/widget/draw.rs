@@ -0,0 +1,569 @@
+//! Helpers for drawing the parts of a widget described by its computed style.
+//!
+//! The default [Widget::on_draw](crate::Widget::on_draw) calls these in order, so a custom widget can override `on_draw`
+//! and still draw, for example, the styled background and border around its own content:
+//! ```
+//! fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas) {
+//!     draw_background(state, entity, canvas);
+//!     // Custom drawing here
+//!     draw_border(state, entity, canvas);
+//! }
+//! ```
+use crate::{Align, Baseline, Canvas, Entity, FontOrId, ImageFilter, ImageFlags, Paint, Path, PixelFormat, RenderTarget, State};
+use crate::{BorderCornerShape, BorderStyle, BoundingBox, GradientDirection, Units};
+
+use super::{draw_inset_box_shadow, draw_outer_box_shadow, stroke_outline};
+use super::{BorderCorner, BorderGeometry, BorderSide};
+
+// Length proportional to radius of a cubic bezier handle for 90deg arcs.
+const KAPPA90: f32 = 0.5522847493;
+
+// The box of an entity with the border resolved to pixels
+struct WidgetBox {
+    bounds: BoundingBox,
+    opacity: f32,
+    // Ordered top, right, bottom, left
+    sides: [BorderSide; 4],
+    // Ordered top-left, top-right, bottom-right, bottom-left
+    corners: [BorderCorner; 4],
+    // Only used for a border which is not the same solid line on every side
+    geometry: Option<BorderGeometry>,
+}
+
+impl WidgetBox {
+    // Returns None for an entity with no width or no height
+    fn resolve(state: &State, entity: Entity) -> Option<Self> {
+        let bounds = state.data.get_bounds(entity);
+
+        if bounds.w == 0.0 || bounds.h == 0.0 {
+            return None;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let resolve_width = |units: &Units| match units {
+            Units::Pixels(val) => *val,
+            Units::Percentage(val) => bounds.w.min(bounds.h) * (val / 100.0),
+            _ => 0.0,
+        };
+
+        let resolve_color = |color: &crate::Color| {
+            let mut color: femtovg::Color = (*color).into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let border_width = resolve_width(&state.style.border_width.get(entity).cloned().unwrap_or_default());
+        let border_color = resolve_color(&state.style.border_color.get(entity).cloned().unwrap_or_default());
+        let border_style = state.style.border_style.get(entity).cloned().unwrap_or_default();
+
+        // Resolve each side of the border, falling back to the values for all sides
+        let border_side = |width: Option<&Units>, color: Option<&crate::Color>, style: Option<&BorderStyle>| BorderSide {
+            width: width.map(resolve_width).unwrap_or(border_width),
+            color: color.map(resolve_color).unwrap_or(border_color),
+            style: style.cloned().unwrap_or(border_style),
+        };
+
+        let sides = [
+            border_side(
+                state.style.border_width_top.get(entity),
+                state.style.border_color_top.get(entity),
+                state.style.border_style_top.get(entity),
+            ),
+            border_side(
+                state.style.border_width_right.get(entity),
+                state.style.border_color_right.get(entity),
+                state.style.border_style_right.get(entity),
+            ),
+            border_side(
+                state.style.border_width_bottom.get(entity),
+                state.style.border_color_bottom.get(entity),
+                state.style.border_style_bottom.get(entity),
+            ),
+            border_side(
+                state.style.border_width_left.get(entity),
+                state.style.border_color_left.get(entity),
+                state.style.border_style_left.get(entity),
+            ),
+        ];
+
+        let border_corner = |radius: Option<&Units>, shape: Option<&BorderCornerShape>| BorderCorner {
+            radius: resolve_width(&radius.cloned().unwrap_or_default()),
+            shape: shape.cloned().unwrap_or_default(),
+        };
+
+        let corners = [
+            border_corner(state.style.border_radius_top_left.get(entity), state.style.border_shape_top_left.get(entity)),
+            border_corner(state.style.border_radius_top_right.get(entity), state.style.border_shape_top_right.get(entity)),
+            border_corner(state.style.border_radius_bottom_right.get(entity), state.style.border_shape_bottom_right.get(entity)),
+            border_corner(state.style.border_radius_bottom_left.get(entity), state.style.border_shape_bottom_left.get(entity)),
+        ];
+
+        // A border which is the same solid line on every side is stroked along the shape, otherwise each side is drawn separately
+        let geometry = if sides.iter().all(|side| *side == sides[0]) && sides[0].style == BorderStyle::Solid {
+            None
+        } else {
+            Some(BorderGeometry::new(
+                bounds,
+                [sides[0].width, sides[1].width, sides[2].width, sides[3].width],
+                corners,
+            ))
+        };
+
+        Some(WidgetBox {
+            bounds,
+            opacity,
+            sides,
+            corners,
+            geometry,
+        })
+    }
+
+    fn radii(&self) -> [f32; 4] {
+        [
+            self.corners[0].radius,
+            self.corners[1].radius,
+            self.corners[2].radius,
+            self.corners[3].radius,
+        ]
+    }
+
+    // The path which is filled with the background and, for a uniform solid border, stroked with the border
+    fn path(&self) -> Path {
+        if let Some(geometry) = &self.geometry {
+            return geometry.outer_path();
+        }
+
+        let bounds = self.bounds;
+        let border_width = self.sides[0].width;
+        let [top_left, top_right, bottom_right, bottom_left] = self.corners;
+
+        let mut path = Path::new();
+
+        let circle_radius = (bounds.w - 2.0 * border_width) / 2.0;
+
+        if self.corners.iter().all(|corner| corner.radius == circle_radius) {
+            path.circle(
+                bounds.x + (border_width / 2.0) + (bounds.w - border_width) / 2.0,
+                bounds.y + (border_width / 2.0) + (bounds.h - border_width) / 2.0,
+                bounds.w / 2.0,
+            );
+
+            return path;
+        }
+
+        let x = bounds.x + border_width / 2.0;
+        let y = bounds.y + border_width / 2.0;
+        let w = bounds.w - border_width;
+        let h = bounds.h - border_width;
+        let halfw = w.abs() * 0.5;
+        let halfh = h.abs() * 0.5;
+
+        let rx_bl = bottom_left.radius.min(halfw) * w.signum();
+        let ry_bl = bottom_left.radius.min(halfh) * h.signum();
+
+        let rx_br = bottom_right.radius.min(halfw) * w.signum();
+        let ry_br = bottom_right.radius.min(halfh) * h.signum();
+
+        let rx_tr = top_right.radius.min(halfw) * w.signum();
+        let ry_tr = top_right.radius.min(halfh) * h.signum();
+
+        let rx_tl = top_left.radius.min(halfw) * w.signum();
+        let ry_tl = top_left.radius.min(halfh) * h.signum();
+
+        path.move_to(x, y + ry_tl);
+        path.line_to(x, y + h - ry_bl);
+        if bottom_left.radius != 0.0 {
+            if bottom_left.shape == BorderCornerShape::Round {
+                path.bezier_to(x, y + h - ry_bl * (1.0 - KAPPA90), x + rx_bl * (1.0 - KAPPA90), y + h, x + rx_bl, y + h);
+            } else {
+                path.line_to(x + rx_bl, y + h);
+            }
+        }
+
+        path.line_to(x + w - rx_br, y + h);
+
+        if bottom_right.radius != 0.0 {
+            if bottom_right.shape == BorderCornerShape::Round {
+                path.bezier_to(x + w - rx_br * (1.0 - KAPPA90), y + h, x + w, y + h - ry_br * (1.0 - KAPPA90), x + w, y + h - ry_br);
+            } else {
+                path.line_to(x + w, y + h - ry_br);
+            }
+        }
+
+        path.line_to(x + w, y + ry_tr);
+
+        if top_right.radius != 0.0 {
+            if top_right.shape == BorderCornerShape::Round {
+                path.bezier_to(x + w, y + ry_tr * (1.0 - KAPPA90), x + w - rx_tr * (1.0 - KAPPA90), y, x + w - rx_tr, y);
+            } else {
+                path.line_to(x + w - rx_tr, y);
+            }
+        }
+
+        path.line_to(x + rx_tl, y);
+
+        if top_left.radius != 0.0 {
+            if top_left.shape == BorderCornerShape::Round {
+                path.bezier_to(x + rx_tl * (1.0 - KAPPA90), y, x, y + ry_tl * (1.0 - KAPPA90), x, y + ry_tl);
+            } else {
+                path.line_to(x, y + ry_tl);
+            }
+        }
+
+        path.close();
+
+        path
+    }
+}
+
+/// Draws the shadows outside of the box of an entity, described by the `outer-shadow` and (non-inset) `box-shadow` style properties.
+pub fn draw_outer_shadow(state: &mut State, entity: Entity, canvas: &mut Canvas) {
+    let widget_box = match WidgetBox::resolve(state, entity) {
+        Some(widget_box) => widget_box,
+        None => return,
+    };
+
+    let bounds = widget_box.bounds;
+    let opacity = widget_box.opacity;
+
+    if let Some(outer_shadow_color) = state.style.outer_shadow_color.get(entity).cloned() {
+        let resolve_length = |units: Option<&Units>| match units.cloned().unwrap_or_default() {
+            Units::Pixels(val) => val,
+            Units::Percentage(val) => bounds.w * (val / 100.0),
+            _ => 0.0,
+        };
+
+        let outer_shadow_h_offset = resolve_length(state.style.outer_shadow_h_offset.get(entity));
+        let outer_shadow_v_offset = resolve_length(state.style.outer_shadow_v_offset.get(entity));
+        let outer_shadow_blur = resolve_length(state.style.outer_shadow_blur.get(entity));
+
+        let mut outer_shadow_color: femtovg::Color = outer_shadow_color.into();
+        outer_shadow_color.set_alphaf(outer_shadow_color.a * opacity);
+
+        let sigma = outer_shadow_blur / 2.0;
+        let d = (sigma * 5.0).ceil();
+
+        // The shadow is rasterised in physical pixels, so the image is recreated if the scale factor changes
+        let scale_factor = state.scale_factor();
+        let image_width = ((bounds.w + d) * scale_factor).ceil() as usize;
+        let image_height = ((bounds.h + d) * scale_factor).ceil() as usize;
+
+        let create_image = |canvas: &mut Canvas| {
+            canvas
+                .create_image_empty(image_width, image_height, PixelFormat::Rgba8, ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED)
+                .expect("Failed to create image")
+        };
+
+        let (source, target) = match state.data.shadow_image.get(&entity).cloned() {
+            Some((source, target)) => {
+                let size = canvas.image_size(source).expect("Failed to get image");
+
+                if size.0 != image_width || size.1 != image_height {
+                    canvas.delete_image(source);
+                    canvas.delete_image(target);
+                    (create_image(canvas), create_image(canvas))
+                } else {
+                    (source, target)
+                }
+            }
+
+            None => (create_image(canvas), create_image(canvas)),
+        };
+
+        state.data.shadow_image.insert(entity, (source, target));
+
+        canvas.save();
+        canvas.set_render_target(RenderTarget::Image(source));
+        canvas.clear_rect(0, 0, image_width as u32, image_height as u32, crate::Color::rgba(0, 0, 0, 0));
+        canvas.reset();
+        canvas.scale(scale_factor, scale_factor);
+        canvas.translate(-bounds.x + d / 2.0, -bounds.y + d / 2.0);
+        let mut outer_shadow = widget_box.path();
+        canvas.fill_path(&mut outer_shadow, Paint::color(outer_shadow_color));
+        canvas.restore();
+
+        let target_image = if outer_shadow_blur > 0.0 {
+            canvas.filter_image(target, ImageFilter::GaussianBlur { sigma: sigma * scale_factor }, source);
+            target
+        } else {
+            source
+        };
+
+        canvas.set_render_target(state.render_target);
+
+        canvas.save();
+        canvas.translate(outer_shadow_h_offset, outer_shadow_v_offset);
+        let mut path = Path::new();
+        path.rect(bounds.x - d / 2.0, bounds.y - d / 2.0, bounds.w + d, bounds.h + d);
+        canvas.fill_path(
+            &mut path,
+            Paint::image(target_image, bounds.x - d / 2.0, bounds.y - d / 2.0, bounds.w + d, bounds.h + d, 0.0, 1.0),
+        );
+        canvas.restore();
+    }
+
+    let box_shadows = state.style.box_shadow.get(entity).cloned().unwrap_or_default();
+
+    // The first shadow in the list is drawn on top
+    for shadow in box_shadows.iter().rev().filter(|shadow| !shadow.inset) {
+        draw_outer_box_shadow(canvas, bounds, widget_box.radii(), shadow, opacity);
+    }
+}
+
+/// Draws the background of an entity, described by the `background-color` and `background-gradient` style properties.
+///
+/// The background fills the shape of the border, including any rounded or bevelled corners.
+pub fn draw_background(state: &State, entity: Entity, canvas: &mut Canvas) {
+    let widget_box = match WidgetBox::resolve(state, entity) {
+        Some(widget_box) => widget_box,
+        None => return,
+    };
+
+    let bounds = widget_box.bounds;
+
+    let mut background_color: femtovg::Color = state.style.background_color.get(entity).cloned().unwrap_or_default().into();
+    background_color.set_alphaf(background_color.a * widget_box.opacity);
+
+    let mut paint = Paint::color(background_color);
+
+    // Gradient overrides background color
+    if let Some(background_gradient) = state.style.background_gradient.get(entity) {
+        let parent = state.tree.get_parent(entity).unwrap_or(entity);
+        let parent_width = state.data.get_width(parent);
+        let parent_height = state.data.get_height(parent);
+
+        let (end_x, end_y, parent_length) = match background_gradient.direction {
+            GradientDirection::LeftToRight => (bounds.w, 0.0, parent_width),
+            GradientDirection::TopToBottom => (0.0, bounds.h, parent_height),
+            _ => (bounds.w, 0.0, parent_width),
+        };
+
+        paint = Paint::linear_gradient_stops(
+            bounds.x,
+            bounds.y,
+            bounds.x + end_x,
+            bounds.y + end_y,
+            background_gradient
+                .get_stops(parent_length)
+                .iter()
+                .map(|stop| {
+                    let col: femtovg::Color = stop.1.into();
+                    (stop.0, col)
+                })
+                .collect::<Vec<_>>()
+                .as_slice(),
+        );
+    }
+
+    canvas.fill_path(&mut widget_box.path(), paint);
+}
+
+/// Draws the shadows inside the border of an entity, described by the inset `box-shadow` style properties.
+pub fn draw_inner_shadow(state: &State, entity: Entity, canvas: &mut Canvas) {
+    let box_shadows = match state.style.box_shadow.get(entity) {
+        Some(box_shadows) if box_shadows.iter().any(|shadow| shadow.inset) => box_shadows,
+        _ => return,
+    };
+
+    let widget_box = match WidgetBox::resolve(state, entity) {
+        Some(widget_box) => widget_box,
+        None => return,
+    };
+
+    let bounds = widget_box.bounds;
+    let [border_top, border_right, border_bottom, border_left] = [
+        widget_box.sides[0].width,
+        widget_box.sides[1].width,
+        widget_box.sides[2].width,
+        widget_box.sides[3].width,
+    ];
+
+    let padding_box = BoundingBox {
+        x: bounds.x + border_left,
+        y: bounds.y + border_top,
+        w: bounds.w - border_left - border_right,
+        h: bounds.h - border_top - border_bottom,
+    };
+
+    let [radius_top_left, radius_top_right, radius_bottom_right, radius_bottom_left] = widget_box.radii();
+
+    let padding_radii = [
+        (radius_top_left - border_left.max(border_top)).max(0.0),
+        (radius_top_right - border_right.max(border_top)).max(0.0),
+        (radius_bottom_right - border_right.max(border_bottom)).max(0.0),
+        (radius_bottom_left - border_left.max(border_bottom)).max(0.0),
+    ];
+
+    // The first shadow in the list is drawn on top
+    for shadow in box_shadows.iter().rev().filter(|shadow| shadow.inset) {
+        draw_inset_box_shadow(canvas, padding_box, padding_radii, shadow, widget_box.opacity);
+    }
+}
+
+/// Draws the border of an entity, described by the `border-*` style properties.
+pub fn draw_border(state: &State, entity: Entity, canvas: &mut Canvas) {
+    let widget_box = match WidgetBox::resolve(state, entity) {
+        Some(widget_box) => widget_box,
+        None => return,
+    };
+
+    if let Some(geometry) = &widget_box.geometry {
+        geometry.draw(canvas, &widget_box.sides);
+    } else {
+        let mut paint = Paint::color(widget_box.sides[0].color);
+        paint.set_line_width(widget_box.sides[0].width);
+        canvas.stroke_path(&mut widget_box.path(), paint);
+    }
+}
+
+/// Draws the text of an entity, described by the `text`, `font`, `font-size` and `color` style properties.
+///
+/// The text is aligned within the entity using the `child-*` spacing, so `child-space: 1s` centers the text.
+pub fn draw_text_content(state: &State, entity: Entity, canvas: &mut Canvas) {
+    let text = match state.style.text.get(entity) {
+        Some(text) => text,
+        None => return,
+    };
+
+    let widget_box = match WidgetBox::resolve(state, entity) {
+        Some(widget_box) => widget_box,
+        None => return,
+    };
+
+    let bounds = widget_box.bounds;
+    let sides = widget_box.sides;
+
+    let font = state.style.font.get(entity).cloned().unwrap_or_default();
+
+    // TODO - This should probably be cached in state to save look-up time
+    let default_font = state
+        .resource_manager
+        .fonts
+        .get(&state.style.default_font)
+        .and_then(|font| match font {
+            FontOrId::Id(id) => Some(id),
+            _ => None,
+        })
+        .expect("Failed to find default font");
+
+    let font_id = state
+        .resource_manager
+        .fonts
+        .get(&font)
+        .and_then(|font| match font {
+            FontOrId::Id(id) => Some(id),
+            _ => None,
+        })
+        .unwrap_or(default_font);
+
+    let mut x = bounds.x;
+    let mut y = bounds.y;
+
+    // TODO - Move this to a text layout system and include constraints
+    let child_left = state.style.child_left.get(entity).cloned().unwrap_or_default();
+    let child_right = state.style.child_right.get(entity).cloned().unwrap_or_default();
+    let child_top = state.style.child_top.get(entity).cloned().unwrap_or_default();
+    let child_bottom = state.style.child_bottom.get(entity).cloned().unwrap_or_default();
+
+    let align = match child_left {
+        Units::Pixels(val) => match child_right {
+            Units::Stretch(_) | Units::Auto => {
+                x += val + sides[3].width;
+                Align::Left
+            }
+
+            _ => Align::Left,
+        },
+
+        Units::Stretch(_) => match child_right {
+            Units::Pixels(val) => {
+                x += bounds.w - val - sides[1].width;
+                Align::Right
+            }
+
+            Units::Stretch(_) => {
+                x += 0.5 * bounds.w;
+                Align::Center
+            }
+
+            _ => Align::Right,
+        },
+
+        _ => Align::Left,
+    };
+
+    let baseline = match child_top {
+        Units::Pixels(val) => match child_bottom {
+            Units::Stretch(_) | Units::Auto => {
+                y += val + sides[0].width;
+                Baseline::Top
+            }
+
+            _ => Baseline::Top,
+        },
+
+        Units::Stretch(_) => match child_bottom {
+            Units::Pixels(val) => {
+                y += bounds.h - val - sides[2].width;
+                Baseline::Bottom
+            }
+
+            Units::Stretch(_) => {
+                y += 0.5 * bounds.h;
+                Baseline::Middle
+            }
+
+            _ => Baseline::Bottom,
+        },
+
+        _ => Baseline::Top,
+    };
+
+    let mut font_color: femtovg::Color = state.style.font_color.get(entity).cloned().unwrap_or(crate::Color::rgb(0, 0, 0)).into();
+    font_color.set_alphaf(font_color.a * widget_box.opacity);
+
+    let font_size = state.style.font_size.get(entity).cloned().unwrap_or(16.0);
+
+    let mut paint = Paint::color(font_color);
+    paint.set_font_size(font_size);
+    paint.set_font(&[font_id.clone()]);
+    paint.set_text_align(align);
+    paint.set_text_baseline(baseline);
+    paint.set_anti_alias(false);
+
+    canvas.fill_text(x, y, text, paint).unwrap();
+}
+
+/// Draws the outline outside of the border of an entity, described by the `outline-*` style properties.
+pub fn draw_outline(state: &State, entity: Entity, canvas: &mut Canvas) {
+    let outline_width = match state.style.outline_width.get(entity).cloned().unwrap_or_default() {
+        Units::Pixels(val) => val,
+        _ => 0.0,
+    };
+
+    if outline_width <= 0.0 {
+        return;
+    }
+
+    let widget_box = match WidgetBox::resolve(state, entity) {
+        Some(widget_box) => widget_box,
+        None => return,
+    };
+
+    let outline_offset = match state.style.outline_offset.get(entity).cloned().unwrap_or_default() {
+        Units::Pixels(val) => val,
+        _ => 0.0,
+    };
+
+    let mut outline_color: femtovg::Color = state.style.outline_color.get(entity).cloned().unwrap_or(crate::Color::black()).into();
+    outline_color.set_alphaf(outline_color.a * widget_box.opacity);
+
+    let outline = BorderSide {
+        width: outline_width,
+        color: outline_color,
+        style: state.style.outline_style.get(entity).cloned().unwrap_or_default(),
+    };
+
+    stroke_outline(canvas, widget_box.bounds, widget_box.corners, outline, outline_offset);
+}
@@ -44,6 +44,15 @@
 mod widget;
 pub use widget::Widget;
 
+mod border;
+pub(crate) use border::*;
+
+mod shadow;
+pub(crate) use shadow::*;
+
+pub mod draw;
+pub use draw::*;
+
 mod builder;
 pub use builder::Builder;
 
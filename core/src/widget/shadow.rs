@@ -0,0 +1,91 @@
+use crate::{BoundingBox, BoxShadow, Canvas, Paint, Path, Solidity, Units};
+
+// Resolves a shadow length to pixels, with percentages relative to the width of the entity
+fn resolve_length(units: Units, width: f32) -> f32 {
+    match units {
+        Units::Pixels(val) => val,
+        Units::Percentage(val) => width * (val / 100.0),
+        _ => 0.0,
+    }
+}
+
+fn shadow_color(shadow: &BoxShadow, opacity: f32) -> femtovg::Color {
+    let mut color: femtovg::Color = shadow.color.into();
+    color.set_alphaf(color.a * opacity);
+    color
+}
+
+/// Draws a shadow outside of the box of an entity. The region covered by the box is left empty so that
+/// the shadow does not show through a transparent background.
+///
+/// The corner radii are ordered top-left, top-right, bottom-right, bottom-left. The shadow uses the largest of the radii.
+pub(crate) fn draw_outer_box_shadow(canvas: &mut Canvas, bounds: BoundingBox, radii: [f32; 4], shadow: &BoxShadow, opacity: f32) {
+    let color = shadow_color(shadow, opacity);
+    if color.a <= 0.0 {
+        return;
+    }
+
+    let h_offset = resolve_length(shadow.horizontal_offset, bounds.w);
+    let v_offset = resolve_length(shadow.vertical_offset, bounds.w);
+    let blur = resolve_length(shadow.blur_radius, bounds.w).max(0.0);
+    let spread = resolve_length(shadow.spread_radius, bounds.w);
+
+    let x = bounds.x + h_offset - spread;
+    let y = bounds.y + v_offset - spread;
+    let w = bounds.w + 2.0 * spread;
+    let h = bounds.h + 2.0 * spread;
+
+    if w <= 0.0 || h <= 0.0 {
+        return;
+    }
+
+    let radius = (radii.iter().cloned().fold(0.0, f32::max) + spread).max(0.0);
+
+    let mut path = Path::new();
+    path.rect(x - blur, y - blur, w + 2.0 * blur, h + 2.0 * blur);
+    path.rounded_rect_varying(bounds.x, bounds.y, bounds.w, bounds.h, radii[0], radii[1], radii[2], radii[3]);
+    path.solidity(Solidity::Hole);
+
+    let paint = Paint::box_gradient(x, y, w, h, radius, blur.max(1.0), color, femtovg::Color::rgba(0, 0, 0, 0));
+
+    canvas.fill_path(&mut path, paint);
+}
+
+/// Draws a shadow inside the padding box (the box inside the border) of an entity.
+///
+/// The corner radii are those of the padding box, ordered top-left, top-right, bottom-right, bottom-left.
+pub(crate) fn draw_inset_box_shadow(canvas: &mut Canvas, padding_box: BoundingBox, radii: [f32; 4], shadow: &BoxShadow, opacity: f32) {
+    let color = shadow_color(shadow, opacity);
+    if color.a <= 0.0 || padding_box.w <= 0.0 || padding_box.h <= 0.0 {
+        return;
+    }
+
+    let h_offset = resolve_length(shadow.horizontal_offset, padding_box.w);
+    let v_offset = resolve_length(shadow.vertical_offset, padding_box.w);
+    let blur = resolve_length(shadow.blur_radius, padding_box.w).max(0.0);
+    let spread = resolve_length(shadow.spread_radius, padding_box.w);
+
+    // The shadow covers everything outside of this box
+    let x = padding_box.x + h_offset + spread;
+    let y = padding_box.y + v_offset + spread;
+    let w = (padding_box.w - 2.0 * spread).max(0.0);
+    let h = (padding_box.h - 2.0 * spread).max(0.0);
+
+    let radius = (radii.iter().cloned().fold(0.0, f32::max) - spread).max(0.0);
+
+    let mut path = Path::new();
+    path.rounded_rect_varying(
+        padding_box.x,
+        padding_box.y,
+        padding_box.w,
+        padding_box.h,
+        radii[0],
+        radii[1],
+        radii[2],
+        radii[3],
+    );
+
+    let paint = Paint::box_gradient(x, y, w, h, radius, blur.max(1.0), femtovg::Color::rgba(0, 0, 0, 0), color);
+
+    canvas.fill_path(&mut path, paint);
+}
@@ -1,8 +1,10 @@
-use crate::{Entity, Event, State, Widget, WindowEvent, apply_hover};
+use crate::{Entity, Event, ProfiledSystem, State, Widget, WindowEvent, apply_hover};
 
-use crate::systems::{apply_styles, apply_visibility, apply_z_ordering, apply_transform};
+use crate::systems::{apply_layers, apply_styles, apply_visibility, apply_z_ordering, apply_transform};
 use crate::layout::geometry_changed;
 
+use std::time::Instant;
+
 
 /// A widget which represents the main window.
 #[derive(Clone)]
@@ -37,6 +39,11 @@ impl Widget for WindowWidget {
                     println!("{}", val);
                 }
 
+                WindowEvent::ReloadStyles => {
+                    // A stylesheet which can't be read leaves the current styles in place
+                    state.reload_styles().ok();
+                }
+
                 WindowEvent::Restyle => {
                     //state.needs_restyle = true;
                     //println!("Restyle");
@@ -44,14 +51,16 @@ impl Widget for WindowWidget {
                     // apply_styles(state, &state.tree.clone());
                     // apply_visibility(state, &state.tree.clone());
                     // let start = std::time::Instant::now();
+                    let start = Instant::now();
                     let tree = state.tree.clone();
                     apply_styles(state, &tree);
+                    state.scheduler.record_style(start.elapsed());
                     // println!("{:.2?} seconds to restyle. {}", start.elapsed(), event.origin);
                 }
 
                 WindowEvent::Relayout => {
                     //state.needs_relayout = true;
-                    //let start = std::time::Instant::now();
+                    let start = Instant::now();
                     let tree = state.tree.clone();
                     state.needs_redraw = true;
                     //println!("Relayout");
@@ -63,12 +72,17 @@ impl Widget for WindowWidget {
                     apply_z_ordering(state, &tree);
                     //apply_transform(state, &tree);
                     apply_visibility(state, &tree);
+                    apply_layers(state, &tree);
                     //apply_layout(state, &tree);
                     //apply_layout2(state, &tree);
+                    let profile_start = state.profiler.start();
                     morphorm::layout(&mut state.data, &state.tree, &mut state.style);
+                    let laid_out = if profile_start.is_some() { tree.into_iter().count() } else { 0 };
+                    state.profiler.record(ProfiledSystem::Layout, profile_start, laid_out);
                     apply_transform(state, &tree);
                     
                     geometry_changed(state, &tree);
+                    state.scheduler.record_layout(start.elapsed());
                     
                     apply_hover(state);
                     //println!("{:.2?} seconds to relayout. {}", start.elapsed(), event.origin);
@@ -1,9 +1,11 @@
 use crate::{CursorIcon, state::MouseButton};
-use crate::Entity;
+use crate::{Entity, RenderError};
 
 use keyboard_types::{Code, Key};
 use morphorm::GeometryChanged;
 
+use std::path::PathBuf;
+
 
 
 // // Used by the GeometryChanged event to signal that either posx, posy, width, or height of the entity have changed
@@ -23,6 +25,8 @@ pub enum WindowEvent {
     WindowClose,
     /// Emitted when a window is opened
     WindowResize(f32, f32),
+    /// Emitted to all entities when the ratio of physical to logical pixels of the window changes
+    ScaleFactorChanged(f32),
     /// Emitted when a mouse button is double clicked
     MouseDoubleClick(MouseButton),
     /// Emitted when a mouse button is pressed
@@ -52,6 +56,22 @@ pub enum WindowEvent {
     KeyDown(Code, Option<Key>),
     /// Emitted when a keyboard key is released
     KeyUp(Code, Option<Key>),
+    /// Emitted when a file is dragged over the window, once for each file, targeting the hovered entity.
+    /// When the hovered entity changes during the drag it is sent the files again. Most platforms don't report the
+    /// cursor moving while a file is dragged, in which case the entity under the last known cursor position is used.
+    FileHovered(PathBuf),
+    /// Emitted when a file is dropped onto the window, once for each file, targeting the hovered entity
+    FileDropped(PathBuf),
+    /// Emitted when files dragged over the window are moved out of it, the drag is cancelled, or the files are
+    /// moved over another entity
+    FileHoverCancelled,
+    /// Reloads the stylesheets, sent to the window by the default F5 shortcut.
+    /// The current styles are kept if a stylesheet can't be read.
+    ReloadStyles,
+    /// Emitted to every entity when the named image resource has been loaded and its size is available
+    ImageLoaded(String),
+    /// Emitted to every entity when the named image resource can't be decoded. The image is removed.
+    ImageLoadFailed(String, RenderError),
     /// Sets the mouse cursor icon
     SetCursor(CursorIcon),
     /// Grabs the mouse cursor, preventing it from leaving the window
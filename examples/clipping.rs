@@ -15,6 +15,8 @@ fn main() {
                 .set_background_color(Color::red())
                 .set_border_width(Pixels(10.0))
                 .set_border_color(Color::black())
+                // Children clipped by this widget are also clipped to its rounded corners
+                .set_border_radius(Pixels(40.0))
                 .set_min_width(Pixels(0.0))
                 .set_min_height(Pixels(0.0))
         );
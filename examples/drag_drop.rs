@@ -0,0 +1,140 @@
+extern crate tuix;
+use tuix::*;
+use tuix::widgets::*;
+
+const STYLE: &str = r#"
+    .preset {
+        background-color: #404040;
+        child-space: 1s;
+    }
+
+    .ghost {
+        background-color: #606060;
+        opacity: 0.8;
+        child-space: 1s;
+    }
+
+    .slot {
+        background-color: #202020;
+        border-color: #606060;
+        border-width: 1px;
+        child-space: 1s;
+    }
+
+    .slot:drag-over {
+        border-color: #4080ff;
+        border-width: 2px;
+    }
+"#;
+
+// The name of a dragged preset
+#[derive(Clone)]
+struct PresetName(String);
+
+// A preset which can be dragged onto a slot
+struct Preset {
+    name: String,
+}
+
+impl Preset {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Widget for Preset {
+    type Ret = Entity;
+    type Data = ();
+
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_text(state, &self.name).class(state, "preset")
+    }
+
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(WindowEvent::MouseDown(MouseButton::Left)) = event.message.downcast::<WindowEvent>() {
+            state.start_drag(entity, PresetName(self.name.clone()));
+        }
+
+        if let Some(DragEvent::DragStart(_)) = event.message.downcast::<DragEvent>() {
+            let ghost = Label::new(&self.name).build(state, Entity::root(), |builder| {
+                builder
+                    .set_width(Pixels(100.0))
+                    .set_height(Pixels(30.0))
+                    .class("ghost")
+            });
+
+            state.set_drag_ghost(ghost);
+        }
+    }
+}
+
+// A slot which takes the preset dropped onto it
+#[derive(Default)]
+struct Slot;
+
+impl Widget for Slot {
+    type Ret = Entity;
+    type Data = ();
+
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_text(state, "Empty").class(state, "slot")
+    }
+
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(drag_event) = event.message.downcast() {
+            match drag_event {
+                DragEvent::DragEnter(payload) => {
+                    if payload.is::<PresetName>() {
+                        state.accept_drag(entity);
+                        event.consume();
+                    }
+                }
+
+                DragEvent::Drop(payload) => {
+                    if let Some(PresetName(name)) = payload.get::<PresetName>() {
+                        entity.set_text(state, name);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+fn main() {
+    let window_description = WindowDescription::new().with_title("Drag and Drop").with_inner_size(400, 200);
+    let app = Application::new(window_description, |state, window| {
+        state.add_theme(STYLE);
+
+        let presets = Row::new().build(state, window, |builder| {
+            builder
+                .set_height(Pixels(50.0))
+                .set_child_space(Stretch(1.0))
+                .set_col_between(Pixels(10.0))
+        });
+
+        for name in ["Bright", "Warm", "Dark"].iter() {
+            Preset::new(name).build(state, presets, |builder| {
+                builder.set_width(Pixels(100.0)).set_height(Pixels(30.0))
+            });
+        }
+
+        let slots = Row::new().build(state, window, |builder| {
+            builder
+                .set_height(Pixels(50.0))
+                .set_child_space(Stretch(1.0))
+                .set_col_between(Pixels(10.0))
+        });
+
+        for _ in 0..2 {
+            Slot::default().build(state, slots, |builder| {
+                builder.set_width(Pixels(100.0)).set_height(Pixels(30.0))
+            });
+        }
+    });
+
+    app.run();
+}
@@ -2,8 +2,6 @@ use std::collections::HashMap;
 use std::collections::binary_heap::IntoIter;
 use std::collections::btree_map::Entry;
 
-use femtovg::renderer::OpenGl;
-use femtovg::{ImageFlags, ImageId, PixelFormat, RenderTarget};
 use tuix::*;
 use tuix::widgets::*;
 
@@ -1368,7 +1366,7 @@ impl Widget for ColorButton {
         }
     }
     
-    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut femtovg::Canvas<OpenGl>) {
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut tuix::renderer::Canvas) {
         let grid_size: usize = 16;
         
         if self.image.is_none() {
@@ -1394,7 +1392,7 @@ impl Widget for ColorButton {
                 canvas.set_render_target(RenderTarget::Image(image_id));
                 
     
-                canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, femtovg::Color::rgb(0, 0, 0));
+                canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, Color::rgb(0, 0, 0));
                 
                 for x in 0..(size.0 / grid_size) {
                     for y in 0..(size.1 / grid_size) {
@@ -1406,11 +1404,11 @@ impl Widget for ColorButton {
                             (grid_size) as u32,
                             
                             match (x % 2, y % 2) {
-                                (0, 0) => femtovg::Color::rgb(125, 125, 125),
-                                (1, 0) => femtovg::Color::rgb(155, 155, 155),
-                                (0, 1) => femtovg::Color::rgb(155, 155, 155),
-                                (1, 1) => femtovg::Color::rgb(125, 125, 125),
-                                _ => femtovg::Color::rgb(255, 0, 255),
+                                (0, 0) => Color::rgb(125, 125, 125),
+                                (1, 0) => Color::rgb(155, 155, 155),
+                                (0, 1) => Color::rgb(155, 155, 155),
+                                (1, 1) => Color::rgb(125, 125, 125),
+                                _ => Color::rgb(255, 0, 255),
                             },
                         );
                     }
@@ -1430,18 +1428,18 @@ impl Widget for ColorButton {
 
             //println!("Bounds: {:?}", bounds);
 
-            let mut path = femtovg::Path::new();
+            let mut path = Path::new();
             path.rect(bounds.x, bounds.y, bounds.w, bounds.h);
             canvas.fill_path(
                 &mut path,
-                femtovg::Paint::image(image_id, bounds.x, bounds.y, bounds.w, bounds.h, 0f32, 1f32),
+                Paint::image(image_id, bounds.x, bounds.y, bounds.w, bounds.h, 0f32, 1f32),
             );
 
             let background_color: femtovg::Color = state.style.background_color.get(entity).cloned().unwrap_or_default().into();
 
             canvas.fill_path(
                 &mut path,
-                femtovg::Paint::color(background_color),
+                Paint::color(background_color),
             );
             canvas.restore();
         }
@@ -1,7 +1,6 @@
 
 
 use tuix::*;
-use femtovg::{Path, Paint};
 
 pub struct Overlay {
     selected: Entity,
@@ -29,7 +28,7 @@ impl Widget for Overlay {
         }
     }
 
-    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut femtovg::Canvas<femtovg::renderer::OpenGl>) {
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas) {
 
         if self.selected == Entity::null() {
             return;
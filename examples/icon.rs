@@ -0,0 +1,43 @@
+/// A simple tuix application showing SVG icons recoloured with the color property
+use tuix::*;
+use tuix::widgets::*;
+
+const CLOCK: &str = r#"
+<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+    <g fill="none" stroke="black" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+        <circle cx="12" cy="12" r="10"/>
+        <path d="M12 6v6l4 2"/>
+    </g>
+</svg>
+"#;
+
+const STYLE: &str = r#"
+    icon {
+        width: 48px;
+        height: 48px;
+        color: #404040;
+    }
+
+    icon:hover {
+        color: #2196f3;
+    }
+"#;
+
+fn main() {
+    let app = Application::new(WindowDescription::new().with_title("Icon"),|state, window| {
+
+        state.add_theme(STYLE);
+        state.add_svg_mem("clock", CLOCK).expect("Failed to parse svg");
+
+        window.entity().set_layout_type(state, LayoutType::Row).set_child_space(state, Stretch(1.0)).set_col_between(state, Pixels(10.0));
+
+        Icon::new("clock").build(state, window.entity(), |builder| builder);
+
+        // Keeps the colours in the document
+        Icon::new("clock")
+            .with_recolor(false)
+            .build(state, window.entity(), |builder| builder);
+    });
+
+    app.run();
+}
@@ -1,27 +1,41 @@
-/// A simple tuix application showing a widget with an image background
+/// A simple tuix application showing the image widget with different fit modes
 use tuix::*;
 use tuix::widgets::*;
-//use image::GenericImageView;
 
 fn main() {
     let app = Application::new(WindowDescription::new().with_title("Image"),|state, window| {
-        // let image = image::open("resources/icons/calculator_dark-128.png").unwrap();
-
-        // let image_id = state.add_image(image);
-
-        Element::new().build(state, window.entity(), |builder| {
-            builder
-                .set_width(Units::Pixels(100.0))
-                .set_height(Units::Pixels(100.0))
-                //.set_background_image(image_id.clone())
-        });
-
-        Element::new().build(state, window.entity(), |builder| {
-            builder
-                .set_width(Units::Pixels(100.0))
-                .set_height(Units::Pixels(100.0))
-                //.set_background_image(image_id.clone())
-        });
+
+        state.add_image("calculator", "resources/icons/calculator_dark-128.png").expect("Failed to load image");
+
+        window.entity().set_layout_type(state, LayoutType::Row).set_child_space(state, Stretch(1.0)).set_col_between(state, Pixels(10.0));
+
+        // Sized to the image dimensions
+        Image::new("calculator").build(state, window.entity(), |builder| builder);
+
+        Image::new("calculator")
+            .with_fit(ImageFit::Contain)
+            .build(state, window.entity(), |builder| {
+                builder
+                    .set_width(Pixels(200.0))
+                    .set_height(Pixels(100.0))
+                    .set_background_color(Color::rgb(200, 200, 200))
+            });
+
+        Image::new("calculator")
+            .with_fit(ImageFit::Cover)
+            .build(state, window.entity(), |builder| {
+                builder
+                    .set_width(Pixels(200.0))
+                    .set_height(Pixels(100.0))
+            });
+
+        Image::new("calculator")
+            .with_nine_slice(32.0, 32.0, 32.0, 32.0)
+            .build(state, window.entity(), |builder| {
+                builder
+                    .set_width(Pixels(250.0))
+                    .set_height(Pixels(150.0))
+            });
     });
 
     app.run();
@@ -29,9 +29,18 @@ fn main() {
 
         Button::with_label("Item 1").build(state, menu1, |builder| builder.class("item"));
         Button::with_label("Item 2").build(state, menu1, |builder| builder.class("item"));
-        Button::with_label("Item 3")
-            .on_press(|widget, state, button|{
-                button.emit(state, WindowEvent::WindowClose);
+
+        let quit = Shortcut::new(Chord::new(Code::KeyQ).ctrl());
+        state
+            .shortcuts
+            .bind(quit.clone(), ShortcutScope::Global, Entity::root(), WindowEvent::WindowClose)
+            .unwrap()
+            .set_description("Quit");
+
+        MenuItem::new("Quit")
+            .with_shortcut(quit)
+            .on_press(|_, state, item| {
+                item.emit(state, WindowEvent::WindowClose);
             })
             .build(state, menu1, |builder| builder.class("item"));
         Button::with_label("Item 4").build(state, menu1, |builder| builder.class("item"));
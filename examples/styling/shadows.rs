@@ -0,0 +1,43 @@
+use tuix::*;
+use tuix::widgets::*;
+// Buttons with layered box shadows and a focus outline which does not affect layout
+
+const STYLE: &str = r#"
+    button {
+        width: 120px;
+        height: 40px;
+        child-space: 1s;
+        border-radius: 4px;
+        background-color: #f0f0f0;
+        box-shadow: 0px 1px 2px #00000040, 0px 4px 8px 2px #00000020;
+    }
+
+    button:hover {
+        box-shadow: 0px 2px 4px #00000040, 0px 8px 16px 4px #00000030;
+    }
+
+    button:active {
+        box-shadow: inset 0px 2px 4px #00000060;
+    }
+
+    button:focus {
+        outline: 2px solid #2196f3;
+        outline-offset: 2px;
+    }
+"#;
+
+fn main() {
+    let app = Application::new(
+        WindowDescription::new().with_title("Shadows"),
+        |state, window| {
+            state.add_theme(STYLE);
+
+            window.entity().set_layout_type(state, LayoutType::Row).set_child_space(state, Stretch(1.0)).set_col_between(state, Pixels(20.0));
+
+            Button::with_label("Button 1").build(state, window.entity(), |builder| builder);
+            Button::with_label("Button 2").build(state, window.entity(), |builder| builder);
+        },
+    );
+
+    app.run();
+}
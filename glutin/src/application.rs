@@ -10,20 +10,22 @@ use crate::window::Window;
 use tuix_core::{BoundingBox, Units, Widget};
 use tuix_core::{Entity, State, PropSet};
 
-use tuix_core::{MouseButton, MouseButtonState};
+use tuix_core::{ModifiersState, MouseButton, MouseButtonState};
 
-use tuix_core::events::{Event, EventManager, Propagation};
+use tuix_core::events::{EventManager, EventProxy, EventTracer, InputEvent, InputState};
 
-use tuix_core::TreeExt;
+use tuix_core::{WindowDescription, WindowWidget};
 
-use tuix_core::style::{Display, Visibility};
-
-use tuix_core::{WindowDescription, WindowEvent, WindowWidget};
-
-use tuix_core::{apply_hover, apply_clipping};
+use tuix_core::apply_clipping;
 
 use glutin::event::VirtualKeyCode;
 
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
 type GEvent<'a, T> = glutin::event::Event<'a, T>;
 
 
@@ -46,6 +48,7 @@ pub struct Application {
     event_manager: EventManager,
     on_idle: Option<Box<dyn Fn(&mut State)>>,
     should_poll: bool,
+    record_input: Option<BufWriter<File>>,
 }
 
 impl Application {
@@ -81,6 +84,8 @@ impl Application {
         state.add_font_mem("arabic", arabic_font);
 
         let mut window = Window::new(&event_loop, &window_description);
+
+        state.set_scale_factor(window.handle.window().scale_factor() as f32);
         
         event_manager.load_resources(&mut state, &mut window.canvas);
 
@@ -128,6 +133,7 @@ impl Application {
             state: state,
             on_idle: None,
             should_poll: false,
+            record_input: None,
         }
     }
 
@@ -153,12 +159,37 @@ impl Application {
         self
     } 
 
+    /// Returns a proxy which sends events into the application from other threads, waking the event loop.
+    pub fn event_proxy(&self) -> EventProxy {
+        self.state.event_proxy()
+    }
+
+    /// Makes the event loop run continuously instead of sleeping until the next OS event, animation frame or wake-up.
     pub fn should_poll(mut self) -> Self {
         self.should_poll = true;
 
         self
     }
 
+    /// Writes a trace of every dispatched event to a file, as a line of JSON per event.
+    ///
+    /// See [EventTracer] for the contents of the trace. Returns an error if the file can't be created.
+    pub fn trace_events<P: AsRef<Path>>(mut self, path: P) -> io::Result<Self> {
+        self.event_manager.set_tracer(EventTracer::create(path)?);
+
+        Ok(self)
+    }
+
+    /// Records the input of the window and saves it to a file when the application closes.
+    ///
+    /// The recording can be loaded with `InputRecording::load` and replayed to reproduce the session.
+    /// The file is created straight away, returning an error if it can't be.
+    pub fn record_input<P: AsRef<Path>>(mut self, path: P) -> io::Result<Self> {
+        self.record_input = Some(BufWriter::new(File::create(path)?));
+
+        Ok(self)
+    }
+
     /// The `run` method starts the application event loop, passing events from the OS to
     /// the input system and then on to the widgets via the `on_event` method of the [Widget] trait.
     /// The event loop is also responsible for redrawing the main window when required.
@@ -182,68 +213,65 @@ impl Application {
 
         let event_loop_proxy = self.event_loop.create_proxy();
 
+        // Events sent from other threads wake the event loop with a user event
+        let waker_proxy = Mutex::new(self.event_loop.create_proxy());
+        state.event_proxy().set_waker(move || {
+            if let Ok(proxy) = waker_proxy.lock() {
+                proxy.send_event(()).ok();
+            }
+        });
+
         state.needs_redraw = true;
 
-        let mut click_time = std::time::Instant::now();
-        let double_click_interval = std::time::Duration::from_millis(500);
-        let mut double_click = false;
-        let mut click_pos = (0.0, 0.0);
+        let mut input_state = InputState::new();
+
+        let mut record_input = self.record_input;
+        if record_input.is_some() {
+            input_state.start_recording();
+        }
 
         let mut on_idle = self.on_idle;
 
+        // Set when a frame is waiting for its redraw, so that redraws requested by the OS don't end a frame twice
+        let mut frame_pending = false;
+
         let should_poll = self.should_poll;
 
         self.event_loop.run(move |event, _, control_flow| {
-            if should_poll {
-                *control_flow = ControlFlow::Poll;
-            } else {
-                *control_flow = ControlFlow::Wait;
-            }
-            
 
             match event {
-                GEvent::LoopDestroyed => return,
+                GEvent::LoopDestroyed => {
+                    // The application is closing, so a failed write has nowhere to be reported
+                    if let (Some(file), Some(recording)) = (record_input.as_mut(), input_state.stop_recording()) {
+                        write!(file, "{}", recording).and_then(|_| file.flush()).ok();
+                    }
 
-                GEvent::UserEvent(_) => {
-                    if let Some(mut window_event_handler) = state.event_handlers.remove(&Entity::root()) {
-                        if let Some(window) = window_event_handler.downcast::<Window>() {
-                            window.handle.window().request_redraw();
-                        }
+                    // Drops the tracer, which writes the rest of the trace
+                    event_manager.take_tracer();
 
-                        state.event_handlers.insert(Entity::root(), window_event_handler);
-                    }
+                    return;
                 }
 
+                // Wakes the event loop for events sent from other threads or queued by the idle callback, which are
+                // handled by `MainEventsCleared` and only redraw the window if they change something
+                GEvent::UserEvent(_) => {}
+
                 GEvent::MainEventsCleared => {
                     
-                    //let start = std::time::Instant::now();
-                    
-                    while !state.event_queue.is_empty() {
+                    state.scheduler.begin_frame(Instant::now());
+                    state.update_timers(Instant::now());
+                    state.receive_proxied_events();
+
+                    if !state.event_queue.is_empty() {
                         event_manager.flush_events(&mut state);
                     }
-                    
-                    //println!("{:.2?} seconds to run loop.", start.elapsed());
 
+                    // Playing animations, including any started by the events, request the next frame from the scheduler
                     if state.apply_animations() {
+                        Entity::root().relayout(&mut state);
 
-                        *control_flow = ControlFlow::Poll;
-
-                        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
-
-                        event_loop_proxy.send_event(()).unwrap();
-                        //window.handle.window().request_redraw();
-                        if let Some(mut window_event_handler) = state.event_handlers.remove(&Entity::root()) {
-                            if let Some(window) = window_event_handler.downcast::<Window>() {
-                                window.handle.window().request_redraw();
-                            }
-
-                            state.event_handlers.insert(Entity::root(), window_event_handler);
-                        }
-                    } else {
-                        if should_poll {
-                            *control_flow = ControlFlow::Poll;
-                        } else {
-                            *control_flow = ControlFlow::Wait;
+                        if !state.event_queue.is_empty() {
+                            event_manager.flush_events(&mut state);
                         }
                     }
 
@@ -260,6 +288,11 @@ impl Application {
                             state.event_handlers.insert(Entity::root(), window_event_handler);
                         }
                         state.needs_redraw = false;
+                        frame_pending = true;
+                    } else {
+                        // Nothing to draw so the frame ends here
+                        state.scheduler.end_frame(Instant::now());
+                        state.profiler.end_frame();
                     }
 
                     if let Some(idle_callback) = &on_idle {
@@ -275,7 +308,6 @@ impl Application {
                 // REDRAW
 
                 GEvent::RedrawRequested(_) => {
-                    //let start = std::time::Instant::now();
                     if let Some(mut window_event_handler) = state.event_handlers.remove(&Entity::root()) {
                         if let Some(window) = window_event_handler.downcast::<Window>() {
                             event_manager.draw(&mut state, &mut window.canvas);
@@ -288,8 +320,24 @@ impl Application {
                         state.event_handlers.insert(Entity::root(), window_event_handler);
 
                     }
-                    //println!("{:.2?} seconds to draw everything.", start.elapsed());
-                    // Swap buffers
+
+                    if frame_pending {
+                        frame_pending = false;
+                        state.scheduler.end_frame(Instant::now());
+                        state.profiler.end_frame();
+                    }
+                }
+
+                // Sleep until the next OS event, unless the scheduler needs an animation frame or a wake-up sooner
+                GEvent::RedrawEventsCleared => {
+                    *control_flow = if should_poll {
+                        ControlFlow::Poll
+                    } else {
+                        match state.scheduler.next_wake(Instant::now()) {
+                            Some(time) => ControlFlow::WaitUntil(time),
+                            None => ControlFlow::Wait,
+                        }
+                    };
                 }
 
                 GEvent::WindowEvent {
@@ -302,50 +350,37 @@ impl Application {
                         // Close Window //
                         //////////////////
                         glutin::event::WindowEvent::CloseRequested => {
-                            state.insert_event(Event::new(WindowEvent::WindowClose));
+                            input_state.handle(&mut state, InputEvent::CloseRequested);
                             should_quit = true;
                         }
 
-                        //TODO
                         ///////////////////////
                         // Modifiers Changed //
                         ///////////////////////
                         glutin::event::WindowEvent::ModifiersChanged(modifiers_state) => {
-                            state.modifiers.shift = modifiers_state.shift();
-                            state.modifiers.ctrl = modifiers_state.ctrl();
-                            state.modifiers.alt = modifiers_state.alt();
-                            state.modifiers.logo = modifiers_state.logo();
+                            input_state.handle(
+                                &mut state,
+                                InputEvent::Modifiers(ModifiersState {
+                                    shift: modifiers_state.shift(),
+                                    ctrl: modifiers_state.ctrl(),
+                                    alt: modifiers_state.alt(),
+                                    logo: modifiers_state.logo(),
+                                }),
+                            );
                         }
 
                         ////////////////////
                         // Focused Window //
                         ////////////////////
-                        glutin::event::WindowEvent::Focused(_) => {
-                            // state.insert_event(
-                            //     Event::new(WindowEvent::Restyle)
-                            //         .target(Entity::root())
-                            //         .origin(Entity::root()),
-                            // );
-                            // state.insert_event(
-                            //     Event::new(WindowEvent::Relayout)
-                            //         .target(Entity::root())
-                            //         .origin(Entity::root()),
-                            // );
-
-                            Entity::root().restyle(&mut state);
-                            Entity::root().relayout(&mut state);
-                            Entity::root().redraw(&mut state);
+                        glutin::event::WindowEvent::Focused(focused) => {
+                            input_state.handle(&mut state, InputEvent::Focused(focused));
                         }
 
                         ////////////////////
-                        // Focused Window //
+                        // Character Input //
                         ////////////////////
                         glutin::event::WindowEvent::ReceivedCharacter(input) => {
-                            state.insert_event(
-                                Event::new(WindowEvent::CharInput(input))
-                                    .target(state.focused)
-                                    .propagate(Propagation::Down),
-                            );
+                            input_state.handle(&mut state, InputEvent::Text(input));
                         }
 
                         glutin::event::WindowEvent::KeyboardInput {
@@ -358,160 +393,22 @@ impl Application {
                                 glutin::event::ElementState::Released => MouseButtonState::Released,
                             };
 
-	                        // Prefer virtual keycodes to scancodes, as scancodes aren't uniform between platforms
-	                        let code = if let Some(vkey) = input.virtual_keycode {
-		                        vcode_to_code(vkey)
-	                        } else {
-		                        scan_to_code(input.scancode)
-	                        };
+                            // Prefer virtual keycodes to scancodes, as scancodes aren't uniform between platforms
+                            let code = if let Some(vkey) = input.virtual_keycode {
+                                vcode_to_code(vkey)
+                            } else {
+                                scan_to_code(input.scancode)
+                            };
 
                             let key = vk_to_key(
                                 input.virtual_keycode.unwrap_or(VirtualKeyCode::NoConvert),
                             );
 
-                            if let Some(virtual_keycode) = input.virtual_keycode {
-                                if virtual_keycode == VirtualKeyCode::F5
-                                    && s == MouseButtonState::Pressed
-                                {
-                                    state.reload_styles().unwrap();
-                                }
-
-                                if virtual_keycode == VirtualKeyCode::H && s == MouseButtonState::Pressed {
-                                    //println!("Focused Widget: {}", state.focused);
-                                    
-                                    // println!("Tree");
-                                    // for entity in state.tree.into_iter() {
-                                    //     println!("Entity: {} Parent: {:?} posx: {} posy: {} width: {} height: {}", entity, entity.parent(&state.tree), state.data.get_posx(entity), state.data.get_posy(entity), state.data.get_width(entity), state.data.get_height(entity));
-                                    // }
-                                }
-
-                                if virtual_keycode == VirtualKeyCode::Tab
-                                    && s == MouseButtonState::Pressed
-                                {
-                                    let next_focus = state
-                                        .style
-                                        .focus_order
-                                        .get(state.focused)
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .next;
-                                    let prev_focus = state
-                                        .style
-                                        .focus_order
-                                        .get(state.focused)
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .prev;
-
-                                    if state.modifiers.shift {
-                                        if prev_focus != Entity::null() {
-                                            // state.focused.set_focus(&mut state, false);
-                                            // state.focused = prev_focus;
-                                            // state.focused.set_focus(&mut state, true);
-                                            state.set_focus(prev_focus);
-                                        } else {
-                                            // TODO impliment reverse iterator for tree
-                                            // state.focused = match state.focused.into_iter(&state.tree).next() {
-                                            //     Some(val) => val,
-                                            //     None => Entity::root(),
-                                            // };
-                                        }
-                                    } else {
-                                        let tree = state.tree.clone();
-
-
-                                        //let next = iter.next();
-
-                                        println!("Focused: {}", state.focused);
-
-
-
-
-                                        if next_focus != Entity::null() {
-                                            // state.focused.set_focus(&mut state, false);
-                                            // state.focused = next_focus;
-                                            // state.focused.set_focus(&mut state, true);
-                                            state.set_focus(next_focus);
-                                        } else {
-
-                                            //state.focused.set_focus(&mut state, false);
-
-                                            let mut iter =  state.focused.tree_iter(&tree);
-                                            iter.next();
-
-
-                                            if let Some(mut temp) = iter.next() {
-                                                while !state.data.get_focusable(temp)
-                                                    || state.data.get_visibility(temp) == Visibility::Invisible
-                                                    || state.data.get_opacity(temp) == 0.0
-                                                    || state.style.display.get(temp) == Some(&Display::None)
-                                                {
-                                                    temp = match iter.next() {
-                                                        Some(e) => e,
-                                                        None => {
-                                                            Entity::root()
-                                                        }
-                                                    };
-
-                                                    if temp == Entity::root() {
-                                                        break;
-                                                    }
-                                                }
-
-                                                state.set_focus(temp);
-                                            } else {
-                                                state.set_focus(Entity::root());
-                                            }
-
-                                            //state.focused.set_focus(&mut state, true);
-                                        }
-                                    }
-
-                                    Entity::root().restyle(&mut state);
-
-                                }
-                            }
-
-                            match s {
-                                MouseButtonState::Pressed => {
-                                    if state.focused != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyDown(code, key))
-                                                .target(state.focused)
-                                                .propagate(Propagation::DownUp),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyDown(code, key))
-                                                .target(state.hovered)
-                                                .propagate(Propagation::DownUp),
-                                        );
-                                    }
-                                }
-
-                                MouseButtonState::Released => {
-                                    if state.focused != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyUp(code, key))
-                                                .target(state.focused)
-                                                .propagate(Propagation::DownUp),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyUp(code, key))
-                                                .target(state.hovered)
-                                                .propagate(Propagation::DownUp),
-                                        );
-                                    }
-                                }
-                            }
+                            input_state.handle(&mut state, InputEvent::Key(code, key, s));
                         }
 
-
                         // Window Resize Event
                         glutin::event::WindowEvent::Resized(physical_size) => {
-                            
-
                             if let Some(mut window_event_handler) = state.event_handlers.remove(&Entity::root()) {
                                 if let Some(window) = window_event_handler.downcast::<Window>() {
                                     window.handle.resize(physical_size);
@@ -519,37 +416,19 @@ impl Application {
                                 state.event_handlers.insert(Entity::root(), window_event_handler);
                             }
 
-                            state
-                                .style
-                                .width
-                                .insert(Entity::root(), Units::Pixels(physical_size.width as f32));
-                            state
-                                .style
-                                .height
-                                .insert(Entity::root(), Units::Pixels(physical_size.height as f32));
-
-                            state
-                                .data
-                                .set_width(Entity::root(), physical_size.width as f32);
-                            state
-                                .data
-                                .set_height(Entity::root(), physical_size.height as f32);
-
-                            let mut bounding_box = BoundingBox::default();
-                            bounding_box.w = physical_size.width as f32;
-                            bounding_box.h = physical_size.height as f32;
-
-                            state.data.set_clip_region(Entity::root(), bounding_box);
-
-                            // state.insert_event(Event::new(WindowEvent::Restyle).origin(Entity::root()).target(Entity::root()));
-                            // state.insert_event(
-                            //     Event::new(WindowEvent::Relayout).target(Entity::root()),
-                            // );
-
-                            Entity::root().restyle(&mut state);
-                            Entity::root().relayout(&mut state);
-                            Entity::root().redraw(&mut state);
+                            // The layout of the window is in logical pixels
+                            let logical_width = physical_size.width as f32 / state.scale_factor();
+                            let logical_height = physical_size.height as f32 / state.scale_factor();
+
+                            input_state.handle(&mut state, InputEvent::Resized(logical_width, logical_height));
+                        }
 
+                        // Scale Factor Changed Event, followed by a resize to the new physical size
+                        glutin::event::WindowEvent::ScaleFactorChanged {
+                            scale_factor,
+                            new_inner_size: _,
+                        } => {
+                            input_state.handle(&mut state, InputEvent::ScaleFactor(scale_factor as f32));
                         }
 
                         // Cursor Moved Event 
@@ -558,26 +437,11 @@ impl Application {
                             position,
                             modifiers: _,
                         } => {
-                            let cursorx = (position.x) as f32;
-                            let cursory = (position.y) as f32;
-
-                            state.mouse.cursorx = cursorx as f32;
-                            state.mouse.cursory = cursory as f32;
-
-                            apply_hover(&mut state);
-
-                            if state.captured != Entity::null() {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseMove(cursorx, cursory))
-                                        .target(state.captured)
-                                        .propagate(Propagation::Direct),
-                                );
-                            } else if state.hovered != Entity::root() {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseMove(cursorx, cursory))
-                                        .target(state.hovered),
-                                );
-                            }
+                            // Convert the cursor position to logical pixels
+                            let cursorx = (position.x) as f32 / state.scale_factor();
+                            let cursory = (position.y) as f32 / state.scale_factor();
+
+                            input_state.handle(&mut state, InputEvent::PointerMoved(cursorx, cursory));
                         }
 
                         // Mouse Input Event
@@ -599,147 +463,7 @@ impl Application {
                                 glutin::event::MouseButton::Other(id) => MouseButton::Other(id),
                             };
 
-                            match b {
-                                MouseButton::Left => {
-                                    state.mouse.left.state = s;
-                                }
-
-                                MouseButton::Right => {
-                                    state.mouse.right.state = s;
-                                }
-
-                                MouseButton::Middle => {
-                                    state.mouse.middle.state = s;
-                                }
-
-                                _ => {}
-                            }
-
-                            match s {
-                                MouseButtonState::Pressed => {
-                                    // if state.hovered != Entity::null()
-                                    //     && state.active != state.hovered
-                                    // {
-                                    //     state.active = state.hovered;
-                                    //     state.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
-                                    //     state.needs_restyle = true;
-                                    // }
-
-                                    let new_click_time = std::time::Instant::now();
-                                    let click_duration = new_click_time - click_time;
-                                    let new_click_pos = (state.mouse.cursorx, state.mouse.cursory);
-
-                                    if click_duration <= double_click_interval && new_click_pos == click_pos{
-                                        if !double_click {
-                                            let _target = if state.captured != Entity::null() {
-                                                state.insert_event(
-                                                    Event::new(WindowEvent::MouseDoubleClick(b))
-                                                        .target(state.captured)
-                                                        .propagate(Propagation::Direct),
-                                                );
-                                                state.captured
-                                            } else {
-                                                state.insert_event(
-                                                    Event::new(WindowEvent::MouseDoubleClick(b))
-                                                        .target(state.hovered),
-                                                );
-                                                state.hovered
-                                            };
-                                            double_click = true;
-                                        }
-                                        
-                                    } else {
-                                        double_click = false;
-                                    }
-                                    
-                                    click_time = new_click_time;
-                                    click_pos = new_click_pos;
-
-                                    let _target = if state.captured != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseDown(b))
-                                                .target(state.captured)
-                                                .propagate(Propagation::Direct),
-                                        );
-                                        state.captured
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseDown(b))
-                                                .target(state.hovered),
-                                        );
-                                        state.hovered
-                                    };
-
-                                    // if let Some(event_handler) = state.event_handlers.get_mut(&target) {
-                                    //     if let Some(callback) = event_manager.callbacks.get_mut(&target) {
-                                    //         (callback)(event_handler, &mut state, target);
-                                    //     }
-                                    // }
-
-                                    match b {
-                                        MouseButton::Left => {
-                                            state.mouse.left.pos_down =
-                                                (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.pressed = state.hovered;
-                                        }
-
-                                        MouseButton::Middle => {
-                                            state.mouse.middle.pos_down =
-                                                (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.pressed = state.hovered;
-                                        }
-
-                                        MouseButton::Right => {
-                                            state.mouse.right.pos_down =
-                                                (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.pressed = state.hovered;
-                                        }
-
-                                        _ => {}
-                                    }
-                                }
-
-                                MouseButtonState::Released => {
-                                    //state.active = Entity::null();
-                                    //state.insert_event(Event::new(WindowEvent::Restyle));
-                                    //state.needs_restyle = true;
-
-                                    if state.captured != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseUp(b))
-                                                .target(state.captured)
-                                                .propagate(Propagation::Direct),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseUp(b))
-                                                .target(state.hovered),
-                                        );
-                                    }
-
-                                    match b {
-                                        MouseButton::Left => {
-                                            state.mouse.left.pos_up =
-                                                (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.released = state.hovered;
-                                        }
-
-                                        MouseButton::Middle => {
-                                            state.mouse.middle.pos_up =
-                                                (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.released = state.hovered;
-                                        }
-
-                                        MouseButton::Right => {
-                                            state.mouse.right.pos_up =
-                                                (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.released = state.hovered;
-                                        }
-
-                                        _ => {}
-                                    }
-                                }
-                            }
+                            input_state.handle(&mut state, InputEvent::Button(b, s));
                         }
 
                         glutin::event::WindowEvent::MouseWheel {
@@ -753,18 +477,20 @@ impl Application {
                                 _ => (0.0, 0.0),
                             };
 
-                            if state.captured != Entity::null() {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseScroll(x, y))
-                                        .target(state.captured)
-                                        .propagate(Propagation::Direct),
-                                );
-                            } else {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseScroll(x, y))
-                                        .target(state.hovered),
-                                );
-                            }
+                            input_state.handle(&mut state, InputEvent::Wheel(x, y));
+                        }
+
+                        // Files dragged from other applications
+                        glutin::event::WindowEvent::HoveredFile(path) => {
+                            input_state.handle(&mut state, InputEvent::FileHovered(path));
+                        }
+
+                        glutin::event::WindowEvent::DroppedFile(path) => {
+                            input_state.handle(&mut state, InputEvent::FileDropped(path));
+                        }
+
+                        glutin::event::WindowEvent::HoveredFileCancelled => {
+                            input_state.handle(&mut state, InputEvent::FileHoverCancelled);
                         }
 
                         _ => {}
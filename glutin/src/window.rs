@@ -4,14 +4,14 @@ use glutin::event_loop::EventLoop;
 use glutin::window::{CursorIcon, WindowBuilder};
 use glutin::ContextBuilder;
 
-use femtovg::{renderer::OpenGl, Canvas, Color};
+use femtovg::{renderer::OpenGl, Canvas};
 
-use tuix_core::{Entity, State, Widget, WindowDescription, WindowWidget, entity};
+use tuix_core::{Color, Entity, FemtovgRenderer, Renderer, State, Widget, WindowDescription, WindowWidget, entity};
 
 pub struct Window {
     pub id: WindowId,
     pub handle: glutin::WindowedContext<glutin::PossiblyCurrent>,
-    pub canvas: Canvas<OpenGl>,
+    pub canvas: FemtovgRenderer<OpenGl>,
     pub window_widget: WindowWidget,
 }
 
@@ -28,11 +28,12 @@ impl Window {
 
         window_builder = window_builder
             .with_title(&window_description.title)
-            .with_inner_size(PhysicalSize::new(
+            // Window sizes are in logical pixels, which are scaled to the display by the OS
+            .with_inner_size(LogicalSize::new(
                 window_description.inner_size.width,
                 window_description.inner_size.height,
             ))
-            .with_min_inner_size(PhysicalSize::new(
+            .with_min_inner_size(LogicalSize::new(
                 window_description.min_inner_size.width,
                 window_description.min_inner_size.height,
             ))
@@ -59,7 +60,7 @@ impl Window {
 
         let renderer = OpenGl::new(|s| handle.context().get_proc_address(s) as *const _)
             .expect("Cannot create renderer");
-        let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
+        let mut canvas = FemtovgRenderer::new(Canvas::new(renderer).expect("Cannot create canvas"));
 
         let dpi_factor = handle.window().scale_factor();
         let size = handle.window().inner_size();
@@ -104,7 +105,11 @@ impl Widget for Window {
                 }
 
                 tuix_core::WindowEvent::SetCursorPosition(x, y) => {
-                    self.handle.window().set_cursor_position(Position::Physical(PhysicalPosition::new(*x as i32, *y as i32)));
+                    let scale_factor = state.scale_factor();
+                    self.handle.window().set_cursor_position(Position::Physical(PhysicalPosition::new(
+                        (*x as f32 * scale_factor) as i32,
+                        (*y as f32 * scale_factor) as i32,
+                    )));
                 }
 
                 tuix_core::WindowEvent::SetCursor(cursor) => {
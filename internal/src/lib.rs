@@ -15,5 +15,17 @@ pub mod widgets {
     pub use tuix_widgets::*;
 }
 
+/// CPU rendering backend for headless screenshots
+#[cfg(feature = "software")]
+pub mod software {
+    pub use tuix_software::*;
+}
+
+/// Headless rendering of widgets and golden image comparison for tests
+#[cfg(feature = "software")]
+pub mod testing {
+    pub use tuix_software::testing::*;
+}
+
 
 pub use tuix_core::Lens;
\ No newline at end of file
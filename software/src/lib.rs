@@ -0,0 +1,43 @@
+//! # Tuix Software
+//!
+//! A CPU rendering backend for tuix. The [SoftwareRenderer] implements the [Renderer](tuix_core::Renderer) trait
+//! by rasterising into an in-memory RGBA [Pixmap], which can be saved as a PNG file. It needs no window, GPU or
+//! display server, so it can be used to take screenshots of a UI in a headless CI job.
+//!
+//! The [testing] module builds on this to render widgets in tests and compare them to golden images.
+//!
+//! # Example
+//! ```ignore
+//! let mut renderer = SoftwareRenderer::new(800, 600);
+//! event_manager.flush_events(&mut state);
+//! render(&mut state, &mut event_manager, &mut renderer);
+//! renderer.save_png("screenshot.png")?;
+//! ```
+
+mod pixmap;
+pub use pixmap::Pixmap;
+
+mod png_io;
+pub use png_io::{decode_png, save_png, write_png, PngError};
+
+mod raster;
+
+mod renderer;
+pub use renderer::SoftwareRenderer;
+
+mod shader;
+
+mod text;
+
+pub mod testing;
+
+use tuix_core::{apply_clipping, EventManager, State};
+
+/// Draws the widgets of the state with the renderer, as a window backend does when the window is redrawn.
+///
+/// Events should be flushed first so that the styles and layout of the widgets are up to date.
+pub fn render(state: &mut State, event_manager: &mut EventManager, renderer: &mut SoftwareRenderer) {
+    let tree = state.tree.clone();
+    apply_clipping(state, &tree);
+    event_manager.draw(state, renderer);
+}
@@ -0,0 +1,279 @@
+use tuix_core::{BlendFactor, Color, CompositeOperation};
+
+/// A color with premultiplied alpha, with channels between 0.0 and 1.0
+pub(crate) type Rgba = [f32; 4];
+
+/// The factors which the source and destination colors are multiplied by before they are added when drawing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Blend {
+    pub src: BlendFactor,
+    pub dst: BlendFactor,
+}
+
+impl Default for Blend {
+    /// Draws the source over the destination
+    fn default() -> Self {
+        Blend {
+            src: BlendFactor::One,
+            dst: BlendFactor::OneMinusSrcAlpha,
+        }
+    }
+}
+
+impl Blend {
+    /// Returns the blend factors of a Porter-Duff composite operation on premultiplied colors
+    pub(crate) fn from_operation(operation: CompositeOperation) -> Self {
+        use BlendFactor::*;
+
+        let (src, dst) = match operation {
+            CompositeOperation::SourceOver => (One, OneMinusSrcAlpha),
+            CompositeOperation::SourceIn => (DstAlpha, Zero),
+            CompositeOperation::SourceOut => (OneMinusDstAlpha, Zero),
+            CompositeOperation::Atop => (DstAlpha, OneMinusSrcAlpha),
+            CompositeOperation::DestinationOver => (OneMinusDstAlpha, One),
+            CompositeOperation::DestinationIn => (Zero, SrcAlpha),
+            CompositeOperation::DestinationOut => (Zero, OneMinusSrcAlpha),
+            CompositeOperation::DestinationAtop => (OneMinusDstAlpha, SrcAlpha),
+            CompositeOperation::Lighter => (One, One),
+            CompositeOperation::Copy => (One, Zero),
+            CompositeOperation::Xor => (OneMinusDstAlpha, OneMinusSrcAlpha),
+        };
+
+        Blend { src, dst }
+    }
+}
+
+// Returns the factor which each channel is multiplied by
+fn blend_factor(factor: BlendFactor, src: Rgba, dst: Rgba) -> Rgba {
+    let inverse = |color: Rgba| [1.0 - color[0], 1.0 - color[1], 1.0 - color[2], 1.0 - color[3]];
+
+    match factor {
+        BlendFactor::Zero => [0.0; 4],
+        BlendFactor::One => [1.0; 4],
+        BlendFactor::SrcColor => src,
+        BlendFactor::OneMinusSrcColor => inverse(src),
+        BlendFactor::DstColor => dst,
+        BlendFactor::OneMinusDstColor => inverse(dst),
+        BlendFactor::SrcAlpha => [src[3]; 4],
+        BlendFactor::OneMinusSrcAlpha => [1.0 - src[3]; 4],
+        BlendFactor::DstAlpha => [dst[3]; 4],
+        BlendFactor::OneMinusDstAlpha => [1.0 - dst[3]; 4],
+        BlendFactor::SrcAlphaSaturate => {
+            let factor = src[3].min(1.0 - dst[3]);
+            [factor, factor, factor, 1.0]
+        }
+    }
+}
+
+/// Converts a color to a color with premultiplied alpha
+pub(crate) fn premultiply(color: Color) -> Rgba {
+    let alpha = color.a() as f32 / 255.0;
+    [
+        color.r() as f32 / 255.0 * alpha,
+        color.g() as f32 / 255.0 * alpha,
+        color.b() as f32 / 255.0 * alpha,
+        alpha,
+    ]
+}
+
+pub(crate) fn mix(a: Rgba, b: Rgba, t: f32) -> Rgba {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t,
+    ]
+}
+
+/// An image stored in memory with premultiplied alpha.
+///
+/// The screen of a [SoftwareRenderer](crate::SoftwareRenderer) and each of its images are pixmaps.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pixmap {
+    width: usize,
+    height: usize,
+    data: Vec<Rgba>,
+}
+
+impl Pixmap {
+    /// Creates a transparent pixmap
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![[0.0; 4]; width * height],
+        }
+    }
+
+    /// Creates a pixmap from 8-bit RGBA data without premultiplied alpha
+    pub fn from_rgba8(width: usize, height: usize, rgba: &[u8]) -> Self {
+        let data = rgba
+            .chunks_exact(4)
+            .take(width * height)
+            .map(|pixel| {
+                let a = pixel[3] as f32 / 255.0;
+                [
+                    pixel[0] as f32 / 255.0 * a,
+                    pixel[1] as f32 / 255.0 * a,
+                    pixel[2] as f32 / 255.0 * a,
+                    a,
+                ]
+            })
+            .collect();
+
+        Self { width, height, data }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the 8-bit RGBA value of a pixel without premultiplied alpha
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        to_rgba8(self.data[y * self.width + x])
+    }
+
+    /// Returns 8-bit RGBA data without premultiplied alpha, row by row from the top left
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|pixel| to_rgba8(*pixel).to_vec()).collect()
+    }
+
+    pub(crate) fn get(&self, x: usize, y: usize) -> Rgba {
+        self.data[y * self.width + x]
+    }
+
+    /// Replaces the pixels of a rectangle with a color
+    pub(crate) fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgba) {
+        let x1 = (x + width).min(self.width);
+        let y1 = (y + height).min(self.height);
+        for py in y.min(y1)..y1 {
+            for pixel in &mut self.data[py * self.width + x.min(x1)..py * self.width + x1] {
+                *pixel = color;
+            }
+        }
+    }
+
+    /// Draws a color over a pixel, with the coverage of the pixel between 0.0 and 1.0
+    pub(crate) fn blend(&mut self, x: usize, y: usize, color: Rgba, coverage: f32) {
+        let pixel = &mut self.data[y * self.width + x];
+        let inv_alpha = 1.0 - color[3] * coverage;
+        for (channel, source) in pixel.iter_mut().zip(color.iter()) {
+            *channel = source * coverage + *channel * inv_alpha;
+        }
+    }
+
+    /// Combines a color with a pixel using blend factors, with the coverage of the pixel between 0.0 and 1.0
+    pub(crate) fn composite(&mut self, x: usize, y: usize, color: Rgba, coverage: f32, blend: Blend) {
+        let pixel = &mut self.data[y * self.width + x];
+        let src = [color[0] * coverage, color[1] * coverage, color[2] * coverage, color[3] * coverage];
+        let dst = *pixel;
+
+        let src_factor = blend_factor(blend.src, src, dst);
+        let dst_factor = blend_factor(blend.dst, src, dst);
+
+        for i in 0..4 {
+            pixel[i] = (src[i] * src_factor[i] + dst[i] * dst_factor[i]).max(0.0).min(1.0);
+        }
+    }
+
+    /// Samples the pixmap at a position in pixels with bilinear filtering (or nearest-neighbour if `nearest`).
+    /// Positions outside of the pixmap are clamped to the edge, or wrapped if the pixmap repeats in that direction.
+    pub(crate) fn sample(&self, x: f32, y: f32, repeat_x: bool, repeat_y: bool, nearest: bool) -> Rgba {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+
+        let texel = |tx: i64, ty: i64| -> Rgba {
+            let tx = wrap_or_clamp(tx, self.width, repeat_x);
+            let ty = wrap_or_clamp(ty, self.height, repeat_y);
+            self.get(tx, ty)
+        };
+
+        if nearest {
+            return texel(x.floor() as i64, y.floor() as i64);
+        }
+
+        let fx = x - 0.5;
+        let fy = y - 0.5;
+        let x0 = fx.floor();
+        let y0 = fy.floor();
+        let tx = fx - x0;
+        let ty = fy - y0;
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = mix(texel(x0, y0), texel(x0 + 1, y0), tx);
+        let bottom = mix(texel(x0, y0 + 1), texel(x0 + 1, y0 + 1), tx);
+        mix(top, bottom, ty)
+    }
+
+    /// Returns a copy of the pixmap blurred with a gaussian kernel
+    pub(crate) fn gaussian_blur(&self, sigma: f32) -> Pixmap {
+        if sigma <= 0.0 {
+            return self.clone();
+        }
+
+        let radius = (sigma * 3.0).ceil() as i64;
+        let mut kernel: Vec<f32> = (-radius..=radius)
+            .map(|offset| (-((offset * offset) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let total: f32 = kernel.iter().sum();
+        for weight in kernel.iter_mut() {
+            *weight /= total;
+        }
+
+        // Pixels outside of the pixmap are transparent
+        let blur_pass = |source: &Pixmap, horizontal: bool| -> Pixmap {
+            let mut result = Pixmap::new(source.width, source.height);
+            for y in 0..source.height {
+                for x in 0..source.width {
+                    let mut sum = [0.0; 4];
+                    for (index, weight) in kernel.iter().enumerate() {
+                        let offset = index as i64 - radius;
+                        let (sx, sy) = if horizontal {
+                            (x as i64 + offset, y as i64)
+                        } else {
+                            (x as i64, y as i64 + offset)
+                        };
+
+                        if sx < 0 || sy < 0 || sx >= source.width as i64 || sy >= source.height as i64 {
+                            continue;
+                        }
+
+                        let pixel = source.get(sx as usize, sy as usize);
+                        for (total, channel) in sum.iter_mut().zip(pixel.iter()) {
+                            *total += channel * weight;
+                        }
+                    }
+
+                    result.data[y * source.width + x] = sum;
+                }
+            }
+
+            result
+        };
+
+        blur_pass(&blur_pass(self, true), false)
+    }
+}
+
+fn wrap_or_clamp(value: i64, size: usize, repeat: bool) -> usize {
+    if repeat {
+        value.rem_euclid(size as i64) as usize
+    } else {
+        value.max(0).min(size as i64 - 1) as usize
+    }
+}
+
+fn to_rgba8(pixel: Rgba) -> [u8; 4] {
+    let a = pixel[3].max(0.0).min(1.0);
+    if a <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+
+    let channel = |value: f32| ((value / a).max(0.0).min(1.0) * 255.0).round() as u8;
+    [channel(pixel[0]), channel(pixel[1]), channel(pixel[2]), (a * 255.0).round() as u8]
+}
@@ -0,0 +1,101 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::Pixmap;
+
+/// An error from reading or writing a PNG image
+#[derive(Debug)]
+pub enum PngError {
+    Io(std::io::Error),
+    Decoding(png::DecodingError),
+    Encoding(png::EncodingError),
+}
+
+impl std::fmt::Display for PngError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PngError::Io(error) => write!(f, "{}", error),
+            PngError::Decoding(error) => write!(f, "Failed to decode png: {}", error),
+            PngError::Encoding(error) => write!(f, "Failed to encode png: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+impl From<std::io::Error> for PngError {
+    fn from(error: std::io::Error) -> Self {
+        PngError::Io(error)
+    }
+}
+
+impl From<png::DecodingError> for PngError {
+    fn from(error: png::DecodingError) -> Self {
+        PngError::Decoding(error)
+    }
+}
+
+impl From<png::EncodingError> for PngError {
+    fn from(error: png::EncodingError) -> Self {
+        PngError::Encoding(error)
+    }
+}
+
+/// Decodes the data of a PNG file
+pub fn decode_png(data: &[u8]) -> Result<Pixmap, PngError> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+    let (info, mut reader) = decoder.read_info()?;
+    let mut buffer = vec![0; info.buffer_size()];
+    reader.next_frame(&mut buffer)?;
+
+    let width = info.width as usize;
+    let height = info.height as usize;
+
+    let rgba: Vec<u8> = match info.color_type {
+        png::ColorType::RGBA => buffer,
+        png::ColorType::RGB => buffer.chunks_exact(3).flat_map(|p| vec![p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| vec![p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale | png::ColorType::Indexed => {
+            buffer.iter().flat_map(|p| vec![*p, *p, *p, 255]).collect()
+        }
+    };
+
+    Ok(Pixmap::from_rgba8(width, height, &rgba))
+}
+
+/// Encodes a pixmap as a PNG image
+pub fn write_png<W: Write>(writer: W, pixmap: &Pixmap) -> Result<(), PngError> {
+    let mut encoder = png::Encoder::new(writer, pixmap.width() as u32, pixmap.height() as u32);
+    encoder.set_color(png::ColorType::RGBA);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixmap.to_rgba8())?;
+
+    Ok(())
+}
+
+/// Writes a pixmap to a PNG file
+pub fn save_png<P: AsRef<std::path::Path>>(path: P, pixmap: &Pixmap) -> Result<(), PngError> {
+    let file = File::create(path)?;
+    write_png(BufWriter::new(file), pixmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pixmap written as a PNG decodes to the same pixels
+    #[test]
+    fn png_round_trip() {
+        let pixmap = Pixmap::from_rgba8(2, 1, &[255, 0, 0, 255, 0, 0, 255, 128]);
+
+        let mut data = Vec::new();
+        write_png(&mut data, &pixmap).expect("Failed to encode png");
+        let decoded = decode_png(&data).expect("Failed to decode png");
+
+        assert_eq!(decoded.to_rgba8(), pixmap.to_rgba8());
+    }
+}
@@ -0,0 +1,573 @@
+use std::f32::consts::PI;
+
+use tuix_core::{FillRule, LineCap, LineJoin, Path, Solidity, Verb};
+
+pub(crate) type Point = (f32, f32);
+
+// Maximum distance in pixels between a curve and the lines which approximate it
+const TOLERANCE: f32 = 0.25;
+
+// Joins longer than this multiple of half the line width are drawn as bevels
+const MITER_LIMIT: f32 = 10.0;
+
+/// A 2D affine transform [a, b, c, d, e, f], which maps (x, y) to (a*x + c*y + e, b*x + d*y + f)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Transform(pub [f32; 6]);
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+    }
+}
+
+impl Transform {
+    pub fn apply(&self, (x, y): Point) -> Point {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+
+    /// Changes the transform to apply `other` before itself
+    pub fn premultiply(&mut self, other: &Transform) {
+        let [a, b, c, d, e, f] = self.0;
+        let [ta, tb, tc, td, te, tf] = other.0;
+        self.0 = [
+            a * ta + c * tb,
+            b * ta + d * tb,
+            a * tc + c * td,
+            b * tc + d * td,
+            a * te + c * tf + e,
+            b * te + d * tf + f,
+        ];
+    }
+
+    pub fn inverse(&self) -> Transform {
+        let [a, b, c, d, e, f] = self.0;
+        let det = a * d - b * c;
+        if det.abs() < 1e-6 {
+            return Transform::default();
+        }
+
+        let inv_det = 1.0 / det;
+        Transform([
+            d * inv_det,
+            -b * inv_det,
+            -c * inv_det,
+            a * inv_det,
+            (c * f - d * e) * inv_det,
+            (b * e - a * f) * inv_det,
+        ])
+    }
+
+    /// The factor by which the transform scales lengths on average
+    pub fn average_scale(&self) -> f32 {
+        let [a, b, c, d, _, _] = self.0;
+        (a * d - b * c).abs().sqrt()
+    }
+}
+
+/// A flattened sub-path in device pixels
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Contour {
+    pub points: Vec<Point>,
+    pub closed: bool,
+    pub solidity: Solidity,
+}
+
+impl Contour {
+    fn new(start: Point) -> Self {
+        Self {
+            points: vec![start],
+            closed: false,
+            solidity: Solidity::Solid,
+        }
+    }
+
+    // Twice the signed area, which is positive for clockwise contours on screen
+    fn area(&self) -> f32 {
+        let mut area = 0.0;
+        for (index, p0) in self.points.iter().enumerate() {
+            let p1 = self.points[(index + 1) % self.points.len()];
+            area += p0.0 * p1.1 - p1.0 * p0.1;
+        }
+
+        area
+    }
+}
+
+/// Transforms a path to device pixels and approximates its curves with lines
+pub(crate) fn flatten(path: &Path, transform: &Transform) -> Vec<Contour> {
+    let mut contours: Vec<Contour> = Vec::new();
+    let mut last = (0.0, 0.0);
+
+    for verb in path.verbs() {
+        match *verb {
+            Verb::MoveTo(x, y) => {
+                last = transform.apply((x, y));
+                contours.push(Contour::new(last));
+            }
+
+            Verb::LineTo(x, y) => {
+                let point = transform.apply((x, y));
+                match contours.last_mut() {
+                    Some(contour) if !contour.closed => contour.points.push(point),
+                    _ => {
+                        let mut contour = Contour::new(last);
+                        contour.points.push(point);
+                        contours.push(contour);
+                    }
+                }
+
+                last = point;
+            }
+
+            Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                let p0 = last;
+                let p1 = transform.apply((c1x, c1y));
+                let p2 = transform.apply((c2x, c2y));
+                let p3 = transform.apply((x, y));
+
+                if contours.last().map_or(true, |contour| contour.closed) {
+                    contours.push(Contour::new(p0));
+                }
+
+                if let Some(contour) = contours.last_mut() {
+                    flatten_cubic(&mut contour.points, p0, p1, p2, p3);
+                }
+
+                last = p3;
+            }
+
+            Verb::Close => {
+                if let Some(contour) = contours.last_mut() {
+                    contour.closed = true;
+                    last = contour.points[0];
+                }
+            }
+
+            Verb::Solidity(solidity) => {
+                if let Some(contour) = contours.last_mut() {
+                    contour.solidity = solidity;
+                }
+            }
+        }
+    }
+
+    contours
+}
+
+fn flatten_cubic(points: &mut Vec<Point>, p0: Point, p1: Point, p2: Point, p3: Point) {
+    // The number of segments needed depends on how far the control points are from a straight line
+    let ddx = (p0.0 - 2.0 * p1.0 + p2.0).abs().max((p1.0 - 2.0 * p2.0 + p3.0).abs());
+    let ddy = (p0.1 - 2.0 * p1.1 + p2.1).abs().max((p1.1 - 2.0 * p2.1 + p3.1).abs());
+    let dd = (ddx * ddx + ddy * ddy).sqrt();
+    let segments = (0.75 * dd / TOLERANCE).sqrt().ceil().max(1.0).min(100.0) as usize;
+
+    for index in 1..=segments {
+        let t = index as f32 / segments as f32;
+        let mt = 1.0 - t;
+        let a = mt * mt * mt;
+        let b = 3.0 * mt * mt * t;
+        let c = 3.0 * mt * t * t;
+        let d = t * t * t;
+        points.push((
+            a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+            a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+        ));
+    }
+}
+
+/// Reverses contours so that solid contours wind one way and holes wind the other, as femtovg does before filling
+pub(crate) fn enforce_winding(contours: &mut [Contour]) {
+    for contour in contours.iter_mut() {
+        let area = contour.area();
+        let reverse = match contour.solidity {
+            Solidity::Solid => area < 0.0,
+            Solidity::Hole => area > 0.0,
+        };
+
+        if reverse {
+            contour.points.reverse();
+        }
+    }
+}
+
+/// The coverage of each pixel of a rectangular region by a set of contours
+pub(crate) struct Coverage {
+    pub x: i64,
+    pub y: i64,
+    pub width: usize,
+    pub height: usize,
+    stride: usize,
+    accumulation: Vec<f32>,
+}
+
+impl Coverage {
+    /// Accumulates the signed area covered by the contours within the region (x0, y0) to (x1, y1).
+    /// Every contour is treated as closed.
+    pub fn new(contours: &[Contour], x0: i64, y0: i64, x1: i64, y1: i64) -> Self {
+        let width = (x1 - x0).max(0) as usize;
+        let height = (y1 - y0).max(0) as usize;
+        let stride = width + 2;
+
+        let mut coverage = Self {
+            x: x0,
+            y: y0,
+            width,
+            height,
+            stride,
+            accumulation: vec![0.0; stride * height],
+        };
+
+        if width == 0 || height == 0 {
+            return coverage;
+        }
+
+        for contour in contours.iter() {
+            if contour.points.len() < 2 {
+                continue;
+            }
+
+            for (index, p0) in contour.points.iter().enumerate() {
+                let p1 = contour.points[(index + 1) % contour.points.len()];
+                let p0 = (p0.0 - x0 as f32, p0.1 - y0 as f32);
+                let p1 = (p1.0 - x0 as f32, p1.1 - y0 as f32);
+                coverage.clipped_line(p0, p1);
+            }
+        }
+
+        coverage
+    }
+
+    // Splits a line at the left and right edges of the region. Parts outside of the region are moved onto the edge,
+    // which leaves the coverage of the pixels inside the region unchanged.
+    fn clipped_line(&mut self, p0: Point, p1: Point) {
+        let right = self.width as f32;
+        let mut points = vec![p0];
+
+        for edge in [0.0, right].iter() {
+            let t = (edge - p0.0) / (p1.0 - p0.0);
+            if t > 0.0 && t < 1.0 {
+                points.push((*edge, p0.1 + (p1.1 - p0.1) * t));
+            }
+        }
+
+        points.push(p1);
+
+        if points.len() == 4 && (points[1].0 - p0.0).abs() > (points[2].0 - p0.0).abs() {
+            points.swap(1, 2);
+        }
+
+        for pair in points.windows(2) {
+            let clamp = |point: Point| (point.0.max(0.0).min(right), point.1);
+            self.line(clamp(pair[0]), clamp(pair[1]));
+        }
+    }
+
+    // Accumulates the area to the right of a line within each row, after "font-rs" by Raph Levien
+    fn line(&mut self, p0: Point, p1: Point) {
+        if p0.1 == p1.1 {
+            return;
+        }
+
+        let (dir, p0, p1) = if p0.1 < p1.1 { (1.0, p0, p1) } else { (-1.0, p1, p0) };
+        let dxdy = (p1.0 - p0.0) / (p1.1 - p0.1);
+
+        let y_start = p0.1.max(0.0);
+        let y_end = p1.1.min(self.height as f32);
+        if y_start >= y_end {
+            return;
+        }
+
+        let mut x = p0.0 + (y_start - p0.1) * dxdy;
+
+        for y in (y_start as usize)..(y_end.ceil() as usize) {
+            let row = y * self.stride;
+            let dy = ((y + 1) as f32).min(y_end) - (y as f32).max(y_start);
+            let x_next = (x + dxdy * dy).max(0.0).min(self.width as f32);
+            let d = dy * dir;
+
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let x0_floor = x0.floor();
+            let x0i = x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let x1i = x1_ceil as usize;
+
+            if x1i <= x0i + 1 {
+                let xmf = 0.5 * (x + x_next) - x0_floor;
+                self.accumulation[row + x0i] += d - d * xmf;
+                self.accumulation[row + x0i + 1] += d * xmf;
+            } else {
+                let s = (x1 - x0).recip();
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+
+                self.accumulation[row + x0i] += d * a0;
+
+                if x1i == x0i + 2 {
+                    self.accumulation[row + x0i + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.accumulation[row + x0i + 1] += d * (a1 - a0);
+                    for xi in x0i + 2..x1i - 1 {
+                        self.accumulation[row + xi] += d * s;
+                    }
+
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    self.accumulation[row + x1i - 1] += d * (1.0 - a2 - am);
+                }
+
+                self.accumulation[row + x1i] += d * am;
+            }
+
+            x = x_next;
+        }
+    }
+
+    /// Calls the function with the position and coverage of every covered pixel of the region
+    pub fn for_each<F: FnMut(i64, i64, f32)>(&self, fill_rule: FillRule, anti_alias: bool, mut f: F) {
+        for y in 0..self.height {
+            let mut sum = 0.0;
+            for x in 0..self.width {
+                sum += self.accumulation[y * self.stride + x];
+
+                let mut coverage = match fill_rule {
+                    FillRule::NonZero => sum.abs().min(1.0),
+                    FillRule::EvenOdd => {
+                        let value = sum.abs() % 2.0;
+                        if value > 1.0 {
+                            2.0 - value
+                        } else {
+                            value
+                        }
+                    }
+                };
+
+                if !anti_alias {
+                    coverage = if coverage >= 0.5 { 1.0 } else { 0.0 };
+                }
+
+                if coverage > 1.0 / 512.0 {
+                    f(self.x + x as i64, self.y + y as i64, coverage);
+                }
+            }
+        }
+    }
+}
+
+/// Returns polygons which cover the stroke of the contours. The polygons all wind the same way so that they
+/// combine when filled with the non-zero rule.
+pub(crate) fn stroke(contours: &[Contour], half_width: f32, cap: LineCap, join: LineJoin) -> Vec<Contour> {
+    let mut polygons = Vec::new();
+
+    for contour in contours.iter() {
+        let mut points = contour.points.clone();
+        points.dedup_by(|a, b| (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4);
+
+        let closed = contour.closed && points.len() > 2;
+        if closed && points.first() == points.last() {
+            points.pop();
+        }
+
+        if points.len() < 2 {
+            if cap == LineCap::Round {
+                if let Some(point) = points.first() {
+                    polygons.push(circle(*point, half_width));
+                }
+            }
+
+            continue;
+        }
+
+        if !closed && cap == LineCap::Square {
+            let last = points.len() - 1;
+            let start_dir = direction(points[1], points[0]);
+            let end_dir = direction(points[last - 1], points[last]);
+            points[0] = (points[0].0 + start_dir.0 * half_width, points[0].1 + start_dir.1 * half_width);
+            points[last] = (points[last].0 + end_dir.0 * half_width, points[last].1 + end_dir.1 * half_width);
+        }
+
+        let num_segments = if closed { points.len() } else { points.len() - 1 };
+        for index in 0..num_segments {
+            let p0 = points[index];
+            let p1 = points[(index + 1) % points.len()];
+            let dir = direction(p0, p1);
+            let normal = (-dir.1 * half_width, dir.0 * half_width);
+
+            polygons.push(polygon(vec![
+                (p0.0 + normal.0, p0.1 + normal.1),
+                (p1.0 + normal.0, p1.1 + normal.1),
+                (p1.0 - normal.0, p1.1 - normal.1),
+                (p0.0 - normal.0, p0.1 - normal.1),
+            ]));
+        }
+
+        // Joins between segments
+        let (first_join, last_join) = if closed { (0, points.len()) } else { (1, points.len() - 1) };
+        for index in first_join..last_join {
+            let prev = points[(index + points.len() - 1) % points.len()];
+            let point = points[index];
+            let next = points[(index + 1) % points.len()];
+
+            if let Some(polygon) = join_polygon(prev, point, next, half_width, join) {
+                polygons.push(polygon);
+            }
+        }
+
+        if !closed && cap == LineCap::Round {
+            polygons.push(circle(points[0], half_width));
+            polygons.push(circle(points[points.len() - 1], half_width));
+        }
+    }
+
+    polygons
+}
+
+fn join_polygon(prev: Point, point: Point, next: Point, half_width: f32, join: LineJoin) -> Option<Contour> {
+    let d0 = direction(prev, point);
+    let d1 = direction(point, next);
+    let cross = d0.0 * d1.1 - d0.1 * d1.0;
+
+    if cross.abs() < 1e-6 && d0.0 * d1.0 + d0.1 * d1.1 > 0.0 {
+        return None;
+    }
+
+    if join == LineJoin::Round {
+        return Some(circle(point, half_width));
+    }
+
+    // The outer side of the turn
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let n0 = (-d0.1 * side, d0.0 * side);
+    let n1 = (-d1.1 * side, d1.0 * side);
+    let outer0 = (point.0 + n0.0 * half_width, point.1 + n0.1 * half_width);
+    let outer1 = (point.0 + n1.0 * half_width, point.1 + n1.1 * half_width);
+
+    let miter = (0.5 * (n0.0 + n1.0), 0.5 * (n0.1 + n1.1));
+    let miter_length_sq = miter.0 * miter.0 + miter.1 * miter.1;
+
+    if join == LineJoin::Miter && miter_length_sq > 1e-6 && 1.0 / miter_length_sq.sqrt() <= MITER_LIMIT {
+        let tip = (
+            point.0 + miter.0 / miter_length_sq * half_width,
+            point.1 + miter.1 / miter_length_sq * half_width,
+        );
+
+        Some(polygon(vec![point, outer0, tip, outer1]))
+    } else {
+        Some(polygon(vec![point, outer0, outer1]))
+    }
+}
+
+fn direction(from: Point, to: Point) -> Point {
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    let length = (dx * dx + dy * dy).sqrt();
+    if length < 1e-6 {
+        (1.0, 0.0)
+    } else {
+        (dx / length, dy / length)
+    }
+}
+
+fn circle(center: Point, radius: f32) -> Contour {
+    let segments = (radius * 2.0).ceil().max(8.0).min(64.0) as usize;
+    polygon(
+        (0..segments)
+            .map(|index| {
+                let angle = index as f32 / segments as f32 * 2.0 * PI;
+                (center.0 + angle.cos() * radius, center.1 + angle.sin() * radius)
+            })
+            .collect(),
+    )
+}
+
+// Creates a closed solid contour which winds the same way as solid contours after `enforce_winding`
+fn polygon(points: Vec<Point>) -> Contour {
+    let mut contour = Contour {
+        points,
+        closed: true,
+        solidity: Solidity::Solid,
+    };
+
+    enforce_winding(std::slice::from_mut(&mut contour));
+    contour
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage_at(coverage: &Coverage, x: i64, y: i64, fill_rule: FillRule) -> f32 {
+        let mut result = 0.0;
+        coverage.for_each(fill_rule, true, |px, py, value| {
+            if px == x && py == y {
+                result = value;
+            }
+        });
+
+        result
+    }
+
+    /// Pixels half covered by the edge of a rectangle have half coverage
+    #[test]
+    fn rect_coverage() {
+        let mut path = Path::new();
+        path.rect(1.5, 1.0, 2.0, 2.0);
+        let contours = flatten(&path, &Transform::default());
+        let coverage = Coverage::new(&contours, 0, 0, 5, 5);
+
+        assert!((coverage_at(&coverage, 1, 1, FillRule::NonZero) - 0.5).abs() < 1e-4);
+        assert!((coverage_at(&coverage, 2, 1, FillRule::NonZero) - 1.0).abs() < 1e-4);
+        assert!((coverage_at(&coverage, 3, 2, FillRule::NonZero) - 0.5).abs() < 1e-4);
+        assert_eq!(coverage_at(&coverage, 4, 2, FillRule::NonZero), 0.0);
+    }
+
+    /// Parts of a path outside of the region do not change the coverage inside of it
+    #[test]
+    fn clipped_coverage() {
+        let mut path = Path::new();
+        path.move_to(-10.0, 0.0);
+        path.line_to(20.0, 0.0);
+        path.line_to(-10.0, 10.0);
+        path.close();
+        let contours = flatten(&path, &Transform::default());
+
+        let full = Coverage::new(&contours, -10, 0, 20, 10);
+        let clipped = Coverage::new(&contours, 0, 0, 5, 10);
+
+        for y in 0..10 {
+            for x in 0..5 {
+                let expected = coverage_at(&full, x, y, FillRule::NonZero);
+                assert!((coverage_at(&clipped, x, y, FillRule::NonZero) - expected).abs() < 1e-4);
+            }
+        }
+    }
+
+    /// A hole cuts out of a solid shape, whichever way the hole was drawn
+    #[test]
+    fn hole_winding() {
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 10.0, 10.0);
+        path.rect(2.0, 2.0, 6.0, 6.0);
+        path.solidity(Solidity::Hole);
+
+        let mut contours = flatten(&path, &Transform::default());
+        enforce_winding(&mut contours);
+        let coverage = Coverage::new(&contours, 0, 0, 10, 10);
+
+        assert_eq!(coverage_at(&coverage, 5, 5, FillRule::NonZero), 0.0);
+        assert!((coverage_at(&coverage, 1, 5, FillRule::NonZero) - 1.0).abs() < 1e-4);
+    }
+
+    /// The inverse of a transform maps transformed points back
+    #[test]
+    fn transform_inverse() {
+        let mut transform = Transform::default();
+        transform.premultiply(&Transform([2.0, 0.0, 0.0, 3.0, 10.0, 20.0]));
+        transform.premultiply(&Transform([0.0, 1.0, -1.0, 0.0, 0.0, 0.0]));
+
+        let point = transform.inverse().apply(transform.apply((4.0, 5.0)));
+        assert!((point.0 - 4.0).abs() < 1e-4 && (point.1 - 5.0).abs() < 1e-4);
+    }
+}
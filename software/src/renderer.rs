@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use tuix_core::{
+    BlendFactor, Color, CompositeOperation, FillRule, FontId, FontMetrics, ImageFilter, ImageFlags, ImageId, Paint,
+    PaintFlavor, Path, PixelFormat, RenderError, RenderTarget, Renderer, TextMetrics,
+};
+
+use crate::pixmap::{premultiply, Blend, Pixmap};
+use crate::raster::{enforce_winding, flatten, stroke, Contour, Coverage, Transform};
+use crate::shader::Shader;
+use crate::text::Fonts;
+
+// The transform, scissor and blend factors which are saved and restored
+#[derive(Debug, Clone, Copy, Default)]
+struct DrawState {
+    transform: Transform,
+    // The scissor rectangle in device pixels (x0, y0, x1, y1)
+    scissor: Option<(f32, f32, f32, f32)>,
+    blend: Blend,
+}
+
+/// A [Renderer] which rasterises on the CPU into an in-memory [Pixmap].
+///
+/// This renderer does not need a window or a GPU, so it can be used to take screenshots of a UI in tests or CI.
+/// It draws the same shapes, gradients, shadows, images and text as the femtovg renderer, although the
+/// anti-aliasing and text rendering will not match pixel for pixel.
+///
+/// Images are stored the right way up, so `ImageFlags::FLIP_Y` has no effect. Only PNG images can be loaded.
+pub struct SoftwareRenderer {
+    screen: Pixmap,
+    images: HashMap<ImageId, (Pixmap, ImageFlags)>,
+    next_image: usize,
+    fonts: Fonts,
+    target: RenderTarget,
+    state: DrawState,
+    stack: Vec<DrawState>,
+    dpi: f32,
+}
+
+impl SoftwareRenderer {
+    /// Creates a renderer with a transparent screen of the given size in pixels
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            screen: Pixmap::new(width, height),
+            images: HashMap::new(),
+            next_image: 0,
+            fonts: Fonts::default(),
+            target: RenderTarget::Screen,
+            state: DrawState::default(),
+            stack: Vec::new(),
+            dpi: 1.0,
+        }
+    }
+
+    /// Returns the screen which has been drawn to
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.screen
+    }
+
+    /// Returns the scale factor last passed to `set_size`
+    pub fn dpi(&self) -> f32 {
+        self.dpi
+    }
+
+    /// Returns the contents of the screen as 8-bit RGBA data without premultiplied alpha
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.screen.to_rgba8()
+    }
+
+    /// Writes the contents of the screen to a PNG file
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), crate::PngError> {
+        crate::save_png(path, &self.screen)
+    }
+
+    fn add_image(&mut self, pixmap: Pixmap, flags: ImageFlags) -> ImageId {
+        let id = ImageId(self.next_image);
+        self.next_image += 1;
+        self.images.insert(id, (pixmap, flags));
+        id
+    }
+
+    // Takes the pixmap of the render target so that it can be drawn to while images are read.
+    // The pixmap must be returned with `restore_target`.
+    fn take_target(&mut self) -> Pixmap {
+        match self.target {
+            RenderTarget::Screen => std::mem::take(&mut self.screen),
+            RenderTarget::Image(id) => match self.images.get_mut(&id) {
+                Some((pixmap, _)) => std::mem::take(pixmap),
+                None => std::mem::take(&mut self.screen),
+            },
+        }
+    }
+
+    fn restore_target(&mut self, pixmap: Pixmap) {
+        match self.target {
+            RenderTarget::Image(id) if self.images.contains_key(&id) => {
+                if let Some((image, _)) = self.images.get_mut(&id) {
+                    *image = pixmap;
+                }
+            }
+
+            _ => self.screen = pixmap,
+        }
+    }
+
+    // Fills contours in device pixels with a paint, limited to the scissor and the render target
+    fn fill_contours(&mut self, contours: &[Contour], paint: &Paint, fill_rule: FillRule, anti_alias: bool, alpha: f32) {
+        let mut bounds = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for point in contours.iter().flat_map(|contour| contour.points.iter()) {
+            bounds = (bounds.0.min(point.0), bounds.1.min(point.1), bounds.2.max(point.0), bounds.3.max(point.1));
+        }
+
+        let mut target = self.take_target();
+
+        let (mut x0, mut y0, mut x1, mut y1) = (
+            bounds.0.floor().max(0.0),
+            bounds.1.floor().max(0.0),
+            bounds.2.ceil().min(target.width() as f32),
+            bounds.3.ceil().min(target.height() as f32),
+        );
+
+        if let Some((sx0, sy0, sx1, sy1)) = self.state.scissor {
+            x0 = x0.max(sx0.floor());
+            y0 = y0.max(sy0.floor());
+            x1 = x1.min(sx1.ceil());
+            y1 = y1.min(sy1.ceil());
+        }
+
+        if x0 < x1 && y0 < y1 {
+            let coverage = Coverage::new(contours, x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+
+            // An image can't be read while it is drawn to, so draw a copy of it instead
+            let copy;
+            let images = match (paint.flavor, self.target) {
+                (PaintFlavor::Image { id, .. }, RenderTarget::Image(target_id)) if id == target_id => {
+                    let mut images = HashMap::new();
+                    if let Some((_, flags)) = self.images.get(&id) {
+                        images.insert(id, (target.clone(), *flags));
+                    }
+
+                    copy = images;
+                    &copy
+                }
+
+                _ => &self.images,
+            };
+
+            let shader = Shader::new(paint, &self.state.transform, images, alpha);
+            let scissor = self.state.scissor;
+            let blend = self.state.blend;
+
+            coverage.for_each(fill_rule, anti_alias, |x, y, mut value| {
+                // Scissor edges which don't fall on a pixel boundary partially cover the pixel
+                if let Some((sx0, sy0, sx1, sy1)) = scissor {
+                    let (px, py) = (x as f32, y as f32);
+                    let covered_x = (sx1.min(px + 1.0) - sx0.max(px)).max(0.0).min(1.0);
+                    let covered_y = (sy1.min(py + 1.0) - sy0.max(py)).max(0.0).min(1.0);
+                    value *= covered_x * covered_y;
+                }
+
+                if value > 0.0 {
+                    let color = shader.color((x as f32 + 0.5, y as f32 + 0.5));
+                    if blend == Blend::default() {
+                        target.blend(x as usize, y as usize, color, value);
+                    } else {
+                        target.composite(x as usize, y as usize, color, value, blend);
+                    }
+                }
+            });
+        }
+
+        self.restore_target(target);
+    }
+
+    fn scissor_rect(&self, x: f32, y: f32, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        let transform = &self.state.transform;
+        let corners = [
+            transform.apply((x, y)),
+            transform.apply((x + width, y)),
+            transform.apply((x + width, y + height)),
+            transform.apply((x, y + height)),
+        ];
+
+        corners.iter().fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |rect, point| {
+            (rect.0.min(point.0), rect.1.min(point.1), rect.2.max(point.0), rect.3.max(point.1))
+        })
+    }
+}
+
+impl Renderer for SoftwareRenderer {
+    fn set_size(&mut self, width: u32, height: u32, dpi: f32) {
+        if self.screen.width() != width as usize || self.screen.height() != height as usize {
+            self.screen = Pixmap::new(width as usize, height as usize);
+        }
+
+        self.dpi = dpi;
+    }
+
+    fn clear_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        let mut target = self.take_target();
+        target.fill_rect(x as usize, y as usize, width as usize, height as usize, premultiply(color));
+        self.restore_target(target);
+    }
+
+    fn flush(&mut self) {}
+
+    fn save(&mut self) {
+        self.stack.push(self.state);
+    }
+
+    fn restore(&mut self) {
+        if let Some(state) = self.stack.pop() {
+            self.state = state;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = DrawState::default();
+    }
+
+    fn set_transform(&mut self, a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) {
+        self.state.transform.premultiply(&Transform([a, b, c, d, e, f]));
+    }
+
+    fn reset_transform(&mut self) {
+        self.state.transform = Transform::default();
+    }
+
+    fn translate(&mut self, x: f32, y: f32) {
+        self.state.transform.premultiply(&Transform([1.0, 0.0, 0.0, 1.0, x, y]));
+    }
+
+    fn rotate(&mut self, angle: f32) {
+        let (sin, cos) = angle.sin_cos();
+        self.state.transform.premultiply(&Transform([cos, sin, -sin, cos, 0.0, 0.0]));
+    }
+
+    fn scale(&mut self, x: f32, y: f32) {
+        self.state.transform.premultiply(&Transform([x, 0.0, 0.0, y, 0.0, 0.0]));
+    }
+
+    fn global_composite_operation(&mut self, operation: CompositeOperation) {
+        self.state.blend = Blend::from_operation(operation);
+    }
+
+    fn global_composite_blend_func(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor) {
+        self.state.blend = Blend { src: src_factor, dst: dst_factor };
+    }
+
+    fn scissor(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.state.scissor = Some(self.scissor_rect(x, y, width.max(0.0), height.max(0.0)));
+    }
+
+    fn intersect_scissor(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let rect = self.scissor_rect(x, y, width.max(0.0), height.max(0.0));
+        self.state.scissor = Some(match self.state.scissor {
+            Some(current) => (current.0.max(rect.0), current.1.max(rect.1), current.2.min(rect.2), current.3.min(rect.3)),
+            None => rect,
+        });
+    }
+
+    fn reset_scissor(&mut self) {
+        self.state.scissor = None;
+    }
+
+    fn fill_path(&mut self, path: &mut Path, paint: Paint) {
+        let mut contours = flatten(path, &self.state.transform);
+        enforce_winding(&mut contours);
+        self.fill_contours(&contours, &paint, paint.fill_rule, paint.anti_alias, 1.0);
+    }
+
+    fn stroke_path(&mut self, path: &mut Path, paint: Paint) {
+        let contours = flatten(path, &self.state.transform);
+
+        // Lines thinner than a pixel are drawn one pixel wide and faded instead
+        let width = paint.line_width * self.state.transform.average_scale();
+        let (width, alpha) = if width < 1.0 && paint.anti_alias { (1.0, width.max(0.0)) } else { (width, 1.0) };
+
+        let polygons = stroke(&contours, 0.5 * width, paint.line_cap, paint.line_join);
+        self.fill_contours(&polygons, &paint, FillRule::NonZero, paint.anti_alias, alpha);
+    }
+
+    fn add_font_mem(&mut self, data: &[u8]) -> Result<FontId, RenderError> {
+        self.fonts.add(data).map(FontId)
+    }
+
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError> {
+        let (metrics, path) = self.fonts.text_path(x, y, text, &paint)?;
+
+        // Glyph outlines include their own holes, so the winding of the contours is kept
+        let contours = flatten(&path, &self.state.transform);
+        self.fill_contours(&contours, &paint, FillRule::NonZero, true, 1.0);
+
+        Ok(metrics)
+    }
+
+    fn measure_text(&self, x: f32, y: f32, text: &str, paint: Paint) -> Result<TextMetrics, RenderError> {
+        self.fonts.measure_text(x, y, text, &paint)
+    }
+
+    fn measure_font(&self, paint: Paint) -> Result<FontMetrics, RenderError> {
+        self.fonts.measure_font(&paint)
+    }
+
+    fn break_text_vec(&self, max_width: f32, text: &str, paint: Paint) -> Result<Vec<Range<usize>>, RenderError> {
+        self.fonts.break_text_vec(max_width, text, &paint)
+    }
+
+    fn create_image_empty(&mut self, width: usize, height: usize, _format: PixelFormat, flags: ImageFlags) -> Result<ImageId, RenderError> {
+        Ok(self.add_image(Pixmap::new(width, height), flags))
+    }
+
+    fn load_image_mem(&mut self, data: &[u8], flags: ImageFlags) -> Result<ImageId, RenderError> {
+        let pixmap = crate::decode_png(data).map_err(|_| RenderError::InvalidData)?;
+        Ok(self.add_image(pixmap, flags))
+    }
+
+    fn image_size(&self, id: ImageId) -> Result<(usize, usize), RenderError> {
+        self.images
+            .get(&id)
+            .map(|(pixmap, _)| (pixmap.width(), pixmap.height()))
+            .ok_or(RenderError::ImageNotFound)
+    }
+
+    fn delete_image(&mut self, id: ImageId) {
+        self.images.remove(&id);
+    }
+
+    fn filter_image(&mut self, target: ImageId, filter: ImageFilter, source: ImageId) {
+        let filtered = match (self.images.get(&source), filter) {
+            (Some((pixmap, _)), ImageFilter::GaussianBlur { sigma }) => pixmap.gaussian_blur(sigma),
+            _ => return,
+        };
+
+        if let Some((pixmap, _)) = self.images.get_mut(&target) {
+            *pixmap = filtered;
+        }
+    }
+
+    fn set_render_target(&mut self, target: RenderTarget) {
+        self.target = target;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A filled rectangle covers the pixels inside it with the paint color
+    #[test]
+    fn fill_rect() {
+        let mut renderer = SoftwareRenderer::new(10, 10);
+        let mut path = Path::new();
+        path.rect(2.0, 2.0, 4.0, 4.0);
+        renderer.fill_path(&mut path, Paint::color(Color::rgb(255, 0, 0)));
+
+        assert_eq!(renderer.pixmap().pixel(3, 3), [255, 0, 0, 255]);
+        assert_eq!(renderer.pixmap().pixel(7, 7), [0, 0, 0, 0]);
+    }
+
+    /// Blend factors combine the drawn color with the color of the target
+    #[test]
+    fn blend_func() {
+        let mut renderer = SoftwareRenderer::new(10, 10);
+        renderer.clear_rect(0, 0, 10, 10, Color::rgb(255, 128, 0));
+        renderer.global_composite_blend_func(BlendFactor::DstColor, BlendFactor::OneMinusSrcAlpha);
+
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 10.0, 10.0);
+        renderer.fill_path(&mut path, Paint::color(Color::rgb(128, 255, 255)));
+
+        assert_eq!(renderer.pixmap().pixel(5, 5), [128, 128, 0, 255]);
+    }
+
+    /// Drawing is limited to the scissor rectangle, which is moved by the transform
+    #[test]
+    fn scissor_and_transform() {
+        let mut renderer = SoftwareRenderer::new(10, 10);
+        renderer.translate(2.0, 0.0);
+        renderer.scissor(0.0, 0.0, 4.0, 10.0);
+
+        let mut path = Path::new();
+        path.rect(-2.0, 0.0, 10.0, 10.0);
+        renderer.fill_path(&mut path, Paint::color(Color::rgb(0, 0, 255)));
+
+        assert_eq!(renderer.pixmap().pixel(1, 5), [0, 0, 0, 0]);
+        assert_eq!(renderer.pixmap().pixel(3, 5), [0, 0, 255, 255]);
+        assert_eq!(renderer.pixmap().pixel(6, 5), [0, 0, 0, 0]);
+    }
+
+    /// Shapes drawn into an image can be drawn to the screen with an image paint
+    #[test]
+    fn render_to_image() {
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        let image = renderer
+            .create_image_empty(4, 4, PixelFormat::Rgba8, ImageFlags::empty())
+            .expect("Failed to create image");
+
+        renderer.set_render_target(RenderTarget::Image(image));
+        renderer.clear_rect(0, 0, 4, 4, Color::rgb(0, 255, 0));
+        renderer.set_render_target(RenderTarget::Screen);
+
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 4.0, 4.0);
+        renderer.fill_path(&mut path, Paint::image(image, 0.0, 0.0, 4.0, 4.0, 0.0, 1.0));
+
+        assert_eq!(renderer.pixmap().pixel(2, 2), [0, 255, 0, 255]);
+    }
+}
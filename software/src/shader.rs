@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use tuix_core::{Color, ImageFlags, ImageId, Paint, PaintFlavor};
+
+use crate::pixmap::{mix, premultiply, Pixmap, Rgba};
+use crate::raster::{Point, Transform};
+
+/// Computes the color of a paint at a point in device pixels
+pub(crate) struct Shader<'a> {
+    paint: &'a Paint,
+    // Maps device pixels to the coordinates the paint was specified in
+    inverse: Transform,
+    // The image of an image paint and its flags
+    image: Option<(&'a Pixmap, ImageFlags)>,
+    // Multiplies the alpha of the paint, e.g. for thin strokes
+    alpha: f32,
+}
+
+impl<'a> Shader<'a> {
+    pub fn new(
+        paint: &'a Paint,
+        transform: &Transform,
+        images: &'a HashMap<ImageId, (Pixmap, ImageFlags)>,
+        alpha: f32,
+    ) -> Self {
+        let image = match paint.flavor {
+            PaintFlavor::Image { id, .. } => images.get(&id).map(|(pixmap, flags)| (pixmap, *flags)),
+            _ => None,
+        };
+
+        Self {
+            paint,
+            inverse: transform.inverse(),
+            image,
+            alpha,
+        }
+    }
+
+    pub fn color(&self, device_point: Point) -> Rgba {
+        let (x, y) = self.inverse.apply(device_point);
+
+        let color = match self.paint.flavor {
+            PaintFlavor::Color(color) => premultiply(color),
+
+            PaintFlavor::Image { x: ix, y: iy, width, height, angle, alpha, .. } => match self.image {
+                Some((pixmap, flags)) if width != 0.0 && height != 0.0 => {
+                    // Position in the image pattern before it was rotated
+                    let (dx, dy) = (x - ix, y - iy);
+                    let (sin, cos) = (-angle).sin_cos();
+                    let u = (dx * cos - dy * sin) / width * pixmap.width() as f32;
+                    let v = (dx * sin + dy * cos) / height * pixmap.height() as f32;
+
+                    let texel = pixmap.sample(
+                        u,
+                        v,
+                        flags.contains(ImageFlags::REPEAT_X),
+                        flags.contains(ImageFlags::REPEAT_Y),
+                        flags.contains(ImageFlags::NEAREST),
+                    );
+
+                    [texel[0] * alpha, texel[1] * alpha, texel[2] * alpha, texel[3] * alpha]
+                }
+
+                _ => [0.0; 4],
+            },
+
+            PaintFlavor::LinearGradient { start_x, start_y, end_x, end_y, stops, num_stops } => {
+                let (dx, dy) = (end_x - start_x, end_y - start_y);
+                let length_sq = dx * dx + dy * dy;
+                let t = if length_sq > 0.0 {
+                    ((x - start_x) * dx + (y - start_y) * dy) / length_sq
+                } else {
+                    0.0
+                };
+
+                gradient_color(&stops[..num_stops], t.max(0.0).min(1.0))
+            }
+
+            PaintFlavor::BoxGradient { x: bx, y: by, width, height, radius, feather, inner_color, outer_color } => {
+                let feather = feather.max(1.0);
+                let half = (width * 0.5, height * 0.5);
+                let distance = rounded_rect_distance((x - bx - half.0, y - by - half.1), half, radius);
+                let t = ((distance + feather * 0.5) / feather).max(0.0).min(1.0);
+                mix(premultiply(inner_color), premultiply(outer_color), t)
+            }
+        };
+
+        [color[0] * self.alpha, color[1] * self.alpha, color[2] * self.alpha, color[3] * self.alpha]
+    }
+}
+
+// Interpolates between the premultiplied colors of the stops on either side of t
+fn gradient_color(stops: &[(f32, Color)], t: f32) -> Rgba {
+    match stops {
+        [] => [0.0; 4],
+        [(_, color)] => premultiply(*color),
+        _ => {
+            let (first_offset, first_color) = stops[0];
+            if t <= first_offset {
+                return premultiply(first_color);
+            }
+
+            for pair in stops.windows(2) {
+                let (offset0, color0) = pair[0];
+                let (offset1, color1) = pair[1];
+                if t <= offset1 {
+                    let span = offset1 - offset0;
+                    let local = if span > 0.0 { (t - offset0) / span } else { 1.0 };
+                    return mix(premultiply(color0), premultiply(color1), local);
+                }
+            }
+
+            premultiply(stops[stops.len() - 1].1)
+        }
+    }
+}
+
+// Signed distance from a point (relative to the center) to a rounded rectangle with the given half size
+fn rounded_rect_distance(point: Point, half: Point, radius: f32) -> f32 {
+    let radius = radius.min(half.0).min(half.1).max(0.0);
+    let dx = point.0.abs() - (half.0 - radius);
+    let dy = point.1.abs() - (half.1 - radius);
+    let outside = (dx.max(0.0).powi(2) + dy.max(0.0).powi(2)).sqrt();
+    dx.max(dy).min(0.0) + outside - radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gradient colors are interpolated between the nearest stops and clamped at the ends
+    #[test]
+    fn gradient_stops() {
+        let stops = [
+            (0.0, Color::rgb(0, 0, 0)),
+            (0.5, Color::rgb(255, 0, 0)),
+            (1.0, Color::rgb(255, 255, 255)),
+        ];
+
+        assert_eq!(gradient_color(&stops, 0.25), [0.5, 0.0, 0.0, 1.0]);
+        assert_eq!(gradient_color(&stops, 0.75), [1.0, 0.5, 0.5, 1.0]);
+        assert_eq!(gradient_color(&stops, 2.0), [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    /// The distance to a rounded rectangle is negative inside and measured from the corner arcs
+    #[test]
+    fn rounded_rect_distances() {
+        assert_eq!(rounded_rect_distance((0.0, 0.0), (10.0, 5.0), 0.0), -5.0);
+        assert_eq!(rounded_rect_distance((12.0, 0.0), (10.0, 5.0), 0.0), 2.0);
+        let corner = rounded_rect_distance((10.0, 5.0), (10.0, 5.0), 2.0);
+        assert!((corner - (2.0 * 2.0f32.sqrt() - 2.0)).abs() < 1e-4);
+    }
+}
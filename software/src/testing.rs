@@ -0,0 +1,1059 @@
+//! Headless rendering of widgets for tests, with comparison against stored golden images.
+//!
+//! A [TestApp] builds a widget tree into a [State] with a fixed window size and then styles, lays out and draws it
+//! with a [SoftwareRenderer], going through the same event flushing, styling and layout code as the `Application`s.
+//! The result can be compared to a golden PNG image with [assert_golden].
+//!
+//! Golden images are written rather than compared when the `TUIX_UPDATE_GOLDEN` environment variable is set.
+//! When a comparison fails, the rendered image and a diff image are written next to the golden image with
+//! the extensions `.actual.png` and `.diff.png`.
+//!
+//! # Example
+//! ```ignore
+//! #[test]
+//! fn red_box() {
+//!     let mut app = TestApp::new(100, 100, |state, window| {
+//!         Element::new().build(state, window, |builder| {
+//!             builder
+//!                 .set_width(Pixels(50.0))
+//!                 .set_height(Pixels(50.0))
+//!                 .set_background_color(Color::red())
+//!         });
+//!     });
+//!
+//!     app.assert_golden(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/red_box.png"), Tolerance::default());
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use tuix_core::{BoundingBox, Entity, EventManager, InputEvent, InputRecording, InputState, PropSet, State, Units, WindowWidget};
+
+use crate::{decode_png, render, save_png, Pixmap, PngError, SoftwareRenderer};
+
+/// The environment variable which, when set, makes golden comparisons overwrite the golden images
+pub const UPDATE_GOLDEN_VAR: &str = "TUIX_UPDATE_GOLDEN";
+
+/// A headless application for testing the appearance of widgets
+pub struct TestApp {
+    pub state: State,
+    pub event_manager: EventManager,
+    pub input_state: InputState,
+    renderer: SoftwareRenderer,
+}
+
+impl TestApp {
+    /// Creates a window of the given size in pixels and builds the widgets into it.
+    ///
+    /// Like `Application::new`, the closure is given the [State] and the window [Entity], and the default
+    /// styles and fonts are loaded before it is called.
+    pub fn new<F: FnOnce(&mut State, Entity)>(width: u32, height: u32, app: F) -> Self {
+        let mut state = State::new();
+        state.reload_styles().expect("Failed to reload styles");
+
+        let mut event_manager = EventManager::new();
+        let mut renderer = SoftwareRenderer::new(width as usize, height as usize);
+
+        let root = Entity::root();
+
+        let regular_font = include_bytes!("../../resources/Roboto-Regular.ttf");
+        let bold_font = include_bytes!("../../resources/Roboto-Bold.ttf");
+        let icon_font = include_bytes!("../../resources/entypo.ttf");
+        let emoji_font = include_bytes!("../../resources/OpenSansEmoji.ttf");
+        let arabic_font = include_bytes!("../../resources/amiri-regular.ttf");
+
+        state.add_font_mem("roboto", regular_font);
+        state.add_font_mem("roboto-bold", bold_font);
+        state.add_font_mem("icon", icon_font);
+        state.add_font_mem("emoji", emoji_font);
+        state.add_font_mem("arabic", arabic_font);
+
+        event_manager.load_resources(&mut state, &mut renderer);
+
+        WindowWidget::new().build_window(&mut state);
+
+        app(&mut state, root);
+
+        set_window_size(&mut state, width as f32, height as f32);
+        state.data.set_opacity(root, 1.0);
+
+        root.set_element(&mut state, "window");
+
+        root.restyle(&mut state);
+        root.relayout(&mut state);
+
+        TestApp {
+            state,
+            event_manager,
+            input_state: InputState::new(),
+            renderer,
+        }
+    }
+
+    /// Draws the window as it would appear on a display with the given ratio of physical to logical pixels.
+    ///
+    /// The window keeps its size in logical pixels, so the rendered image is `scale_factor` times larger.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        let width = self.state.data.get_width(Entity::root());
+        let height = self.state.data.get_height(Entity::root());
+
+        self.state.set_scale_factor_override(Some(scale_factor));
+        set_window_size(&mut self.state, width, height);
+    }
+
+    /// Flushes the events, and the events they cause, which restyles and relayouts the widgets if needed.
+    ///
+    /// Animations and timers are not advanced, so that the output does not depend on timing.
+    pub fn update(&mut self) {
+        if !self.state.event_queue.is_empty() {
+            self.event_manager.flush_events(&mut self.state);
+        }
+    }
+
+    /// Handles raw input, as a backend would when receiving it from the window, and then updates the widgets.
+    ///
+    /// Positions are in logical pixels. Hovering uses the layout of the last update, so the widgets should be
+    /// rendered or updated before the first pointer event.
+    pub fn input(&mut self, input: InputEvent) {
+        self.input_state.handle(&mut self.state, input);
+        self.update();
+    }
+
+    /// Replays recorded input, e.g. to reproduce a reported bug, updating the widgets after each input.
+    ///
+    /// The inputs keep their recorded times, so double clicks are detected as when they were recorded.
+    pub fn replay(&mut self, recording: &InputRecording) {
+        recording.replay(&mut self.state, &mut self.input_state, &mut self.event_manager);
+    }
+
+    /// Updates the widgets and draws them, returning the rendered image.
+    ///
+    /// Each render is a frame of the [Profiler](tuix_core::Profiler), if it is enabled.
+    pub fn render(&mut self) -> &Pixmap {
+        self.update();
+        render(&mut self.state, &mut self.event_manager, &mut self.renderer);
+        self.state.profiler.end_frame();
+        self.renderer.pixmap()
+    }
+
+    /// Renders the widgets and compares the image to a golden image, panicking if they differ
+    pub fn assert_golden<P: AsRef<Path>>(&mut self, path: P, tolerance: Tolerance) {
+        let actual = self.render().clone();
+        assert_golden(&actual, path, tolerance);
+    }
+}
+
+// Sets the size of the window in logical pixels
+fn set_window_size(state: &mut State, width: f32, height: f32) {
+    let root = Entity::root();
+
+    state.style.width.insert(root, Units::Pixels(width));
+    state.style.height.insert(root, Units::Pixels(height));
+
+    state.data.set_width(root, width);
+    state.data.set_height(root, height);
+
+    let mut bounding_box = BoundingBox::default();
+    bounding_box.w = width;
+    bounding_box.h = height;
+
+    state.data.set_clip_region(root, bounding_box);
+}
+
+/// How much a rendered image may differ from a golden image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// The largest difference in any channel of a pixel, out of 255, for the pixel to count as matching
+    pub channel: u8,
+    /// The number of pixels which may differ by more than `channel`
+    pub pixels: usize,
+}
+
+impl Tolerance {
+    /// Requires the images to be identical
+    pub fn exact() -> Self {
+        Self { channel: 0, pixels: 0 }
+    }
+}
+
+impl Default for Tolerance {
+    /// Allows small differences in anti-aliasing
+    fn default() -> Self {
+        Self { channel: 2, pixels: 0 }
+    }
+}
+
+/// The differences between two images of the same size
+#[derive(Debug, Clone)]
+pub struct ImageDiff {
+    /// The number of pixels which differ by more than the channel tolerance
+    pub differing_pixels: usize,
+    /// The largest difference in any channel of any pixel
+    pub max_difference: u8,
+    /// The expected image faded to grey, with the differing pixels in red
+    pub image: Pixmap,
+}
+
+impl ImageDiff {
+    /// Returns true if the differences are within the tolerance
+    pub fn is_within(&self, tolerance: Tolerance) -> bool {
+        self.differing_pixels <= tolerance.pixels
+    }
+}
+
+/// Compares two images pixel by pixel. Returns `None` if the images are not the same size.
+pub fn diff_images(actual: &Pixmap, expected: &Pixmap, tolerance: Tolerance) -> Option<ImageDiff> {
+    if actual.width() != expected.width() || actual.height() != expected.height() {
+        return None;
+    }
+
+    let actual_data = actual.to_rgba8();
+    let expected_data = expected.to_rgba8();
+
+    let mut differing_pixels = 0;
+    let mut max_difference = 0;
+    let mut diff_data = Vec::with_capacity(expected_data.len());
+
+    for (a, e) in actual_data.chunks_exact(4).zip(expected_data.chunks_exact(4)) {
+        let difference = a.iter().zip(e.iter()).map(|(a, e)| (*a as i16 - *e as i16).abs() as u8).max().unwrap_or(0);
+
+        max_difference = max_difference.max(difference);
+
+        if difference > tolerance.channel {
+            differing_pixels += 1;
+            diff_data.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let grey = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 3 * e[3] as u32 / 255) as u8;
+            let faded = 191 + grey / 4;
+            diff_data.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+
+    Some(ImageDiff {
+        differing_pixels,
+        max_difference,
+        image: Pixmap::from_rgba8(expected.width(), expected.height(), &diff_data),
+    })
+}
+
+/// An error from comparing an image to a golden image
+#[derive(Debug)]
+pub enum GoldenError {
+    /// The golden image could not be read or the output images could not be written
+    Png(PngError),
+    /// There is no golden image at the path
+    Missing(PathBuf),
+    /// The images are different sizes
+    SizeMismatch {
+        actual: (usize, usize),
+        expected: (usize, usize),
+    },
+    /// The images differ by more than the tolerance
+    Mismatch {
+        differing_pixels: usize,
+        max_difference: u8,
+        diff_path: PathBuf,
+    },
+}
+
+impl std::fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::Png(error) => write!(f, "{}", error),
+            GoldenError::Missing(path) => write!(
+                f,
+                "No golden image at {}. Run with {}=1 to create it.",
+                path.display(),
+                UPDATE_GOLDEN_VAR
+            ),
+            GoldenError::SizeMismatch { actual, expected } => write!(
+                f,
+                "Image is {}x{} but the golden image is {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            GoldenError::Mismatch { differing_pixels, max_difference, diff_path } => write!(
+                f,
+                "{} pixels differ from the golden image by up to {}. See {}",
+                differing_pixels,
+                max_difference,
+                diff_path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl From<PngError> for GoldenError {
+    fn from(error: PngError) -> Self {
+        GoldenError::Png(error)
+    }
+}
+
+/// Compares an image to the golden PNG image at the path.
+///
+/// If the `TUIX_UPDATE_GOLDEN` environment variable is set, the golden image is overwritten instead.
+/// If the images differ, the image is written to `<name>.actual.png` and a diff image to `<name>.diff.png`.
+pub fn compare_golden<P: AsRef<Path>>(actual: &Pixmap, path: P, tolerance: Tolerance) -> Result<(), GoldenError> {
+    let path = path.as_ref();
+
+    if std::env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(PngError::from)?;
+        }
+
+        save_png(path, actual)?;
+        return Ok(());
+    }
+
+    if !path.exists() {
+        save_png(path.with_extension("actual.png"), actual)?;
+        return Err(GoldenError::Missing(path.to_path_buf()));
+    }
+
+    let data = std::fs::read(path).map_err(PngError::from)?;
+    let expected = decode_png(&data)?;
+
+    let diff = match diff_images(actual, &expected, tolerance) {
+        Some(diff) => diff,
+        None => {
+            save_png(path.with_extension("actual.png"), actual)?;
+            return Err(GoldenError::SizeMismatch {
+                actual: (actual.width(), actual.height()),
+                expected: (expected.width(), expected.height()),
+            });
+        }
+    };
+
+    if diff.is_within(tolerance) {
+        return Ok(());
+    }
+
+    let diff_path = path.with_extension("diff.png");
+    save_png(path.with_extension("actual.png"), actual)?;
+    save_png(&diff_path, &diff.image)?;
+
+    Err(GoldenError::Mismatch {
+        differing_pixels: diff.differing_pixels,
+        max_difference: diff.max_difference,
+        diff_path,
+    })
+}
+
+/// Compares an image to the golden PNG image at the path, panicking if they differ. See [compare_golden].
+pub fn assert_golden<P: AsRef<Path>>(actual: &Pixmap, path: P, tolerance: Tolerance) {
+    if let Err(error) = compare_golden(actual, path, tolerance) {
+        panic!("{}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tuix_core::{draw_border, ArrowNavigation, BlendMode, Code, Color, DragEvent, Event, ImageOrId, LayerMode, ModifiersState, MouseButton, MouseButtonState, PositionType, ProfiledSystem, PropGet, PseudoClass, Widget, WindowEvent};
+
+    struct Element;
+
+    impl Widget for Element {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+    }
+
+    // Counts the times it is drawn
+    struct DrawCounter(std::rc::Rc<std::cell::Cell<u32>>);
+
+    impl Widget for DrawCounter {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_draw(&mut self, _state: &mut State, _entity: Entity, _canvas: &mut tuix_core::Canvas) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    // Draws only the styled border, leaving out the background
+    struct Frame;
+
+    impl Widget for Frame {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut tuix_core::Canvas) {
+            draw_border(state, entity, canvas);
+        }
+    }
+
+    // Starts a drag of its number when pressed
+    struct DragSource(u32);
+
+    impl Widget for DragSource {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+            if let Some(WindowEvent::MouseDown(MouseButton::Left)) = event.message.downcast::<WindowEvent>() {
+                state.start_drag(entity, self.0);
+            }
+
+            if let Some(DragEvent::DragStart(_)) = event.message.downcast::<DragEvent>() {
+                let ghost = Element.build(state, Entity::root(), |builder| {
+                    builder
+                        .set_width(Units::Pixels(10.0))
+                        .set_height(Units::Pixels(10.0))
+                });
+
+                state.set_drag_ghost(ghost);
+            }
+        }
+    }
+
+    // Accepts numbers and keeps the last one dropped onto it
+    #[derive(Default)]
+    struct DropSlot {
+        dropped: Option<u32>,
+    }
+
+    impl Widget for DropSlot {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+            if let Some(drag_event) = event.message.downcast::<DragEvent>() {
+                match drag_event {
+                    DragEvent::DragEnter(payload) if payload.is::<u32>() => {
+                        state.accept_drag(entity);
+                        event.consume();
+                    }
+
+                    DragEvent::Drop(payload) => {
+                        self.dropped = payload.get::<u32>().cloned();
+                    }
+
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Records the files dragged from other applications which are sent to it
+    #[derive(Default)]
+    struct FileTarget {
+        events: Vec<String>,
+    }
+
+    impl Widget for FileTarget {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_event(&mut self, _state: &mut State, _entity: Entity, event: &mut Event) {
+            match event.message.downcast::<WindowEvent>() {
+                Some(WindowEvent::FileHovered(path)) => self.events.push(format!("hovered {}", path.display())),
+                Some(WindowEvent::FileDropped(path)) => self.events.push(format!("dropped {}", path.display())),
+                Some(WindowEvent::FileHoverCancelled) => self.events.push(String::from("cancelled")),
+                _ => {}
+            }
+        }
+    }
+
+    fn press(app: &mut TestApp, code: Code) {
+        app.input(InputEvent::Key(code, None, MouseButtonState::Pressed));
+        app.input(InputEvent::Key(code, None, MouseButtonState::Released));
+    }
+
+    fn solid(width: usize, height: usize, rgba: [u8; 4]) -> Pixmap {
+        Pixmap::from_rgba8(width, height, &rgba.repeat(width * height))
+    }
+
+    /// Pixels which differ by less than the channel tolerance match, and the others are counted and marked red
+    #[test]
+    fn diff_tolerance() {
+        let expected = solid(2, 1, [100, 100, 100, 255]);
+        let actual = Pixmap::from_rgba8(2, 1, &[102, 100, 100, 255, 200, 100, 100, 255]);
+
+        let diff = diff_images(&actual, &expected, Tolerance::default()).unwrap();
+        assert_eq!(diff.differing_pixels, 1);
+        assert_eq!(diff.max_difference, 100);
+        assert_eq!(diff.image.pixel(1, 0), [255, 0, 0, 255]);
+        assert!(!diff.is_within(Tolerance::default()));
+        assert!(diff.is_within(Tolerance { channel: 2, pixels: 1 }));
+
+        assert!(diff_images(&solid(1, 1, [0; 4]), &expected, Tolerance::default()).is_none());
+    }
+
+    /// A widget is styled, laid out and drawn at the size of the window
+    #[test]
+    fn render_widget() {
+        let mut app = TestApp::new(40, 20, |state, window| {
+            Element.build(state, window, |builder| {
+                builder
+                    .set_left(Units::Pixels(10.0))
+                    .set_top(Units::Pixels(5.0))
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+                    .set_background_color(Color::rgb(255, 0, 0))
+            });
+        });
+
+        let image = app.render();
+        assert_eq!((image.width(), image.height()), (40, 20));
+        assert_eq!(image.pixel(15, 10), [255, 0, 0, 255]);
+        assert_ne!(image.pixel(5, 10), [255, 0, 0, 255]);
+    }
+
+    /// Overlapping children of a translucent entity fade as one instead of showing through each other
+    #[test]
+    fn group_opacity() {
+        let mut app = TestApp::new(40, 20, |state, window| {
+            let group = Element.build(state, window, |builder| {
+                builder
+                    .set_width(Units::Pixels(40.0))
+                    .set_height(Units::Pixels(20.0))
+                    .set_opacity(0.5)
+            });
+
+            for left in [0.0, 10.0].iter() {
+                Element.build(state, group, |builder| {
+                    builder
+                        .set_position_type(PositionType::SelfDirected)
+                        .set_left(Units::Pixels(*left))
+                        .set_width(Units::Pixels(20.0))
+                        .set_height(Units::Pixels(20.0))
+                        .set_background_color(Color::rgb(255, 0, 0))
+                });
+            }
+        });
+
+        let image = app.render();
+        assert_eq!(image.pixel(15, 10), image.pixel(5, 10));
+        assert_eq!(image.pixel(15, 10), image.pixel(25, 10));
+        assert_ne!(image.pixel(15, 10), [255, 0, 0, 255]);
+    }
+
+    /// An entity with a multiply blend mode darkens the entities behind it
+    #[test]
+    fn blend_mode_multiply() {
+        let mut app = TestApp::new(40, 20, |state, window| {
+            Element.build(state, window, |builder| {
+                builder
+                    .set_width(Units::Pixels(20.0))
+                    .set_height(Units::Pixels(20.0))
+                    .set_background_color(Color::rgb(255, 255, 0))
+            });
+
+            Element.build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_left(Units::Pixels(10.0))
+                    .set_width(Units::Pixels(20.0))
+                    .set_height(Units::Pixels(20.0))
+                    .set_background_color(Color::rgb(0, 255, 255))
+                    .set_mix_blend_mode(BlendMode::Multiply)
+            });
+        });
+
+        let image = app.render();
+        assert_eq!(image.pixel(15, 10), [0, 255, 0, 255]);
+        assert_eq!(image.pixel(25, 10), [0, 255, 255, 255]);
+    }
+
+    /// Overriding the scale factor keeps the layout in logical pixels and draws it at twice the resolution
+    #[test]
+    fn scale_factor_override() {
+        let mut app = TestApp::new(40, 20, |state, window| {
+            Element.build(state, window, |builder| {
+                builder
+                    .set_left(Units::Pixels(10.0))
+                    .set_top(Units::Pixels(5.0))
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+                    .set_background_color(Color::rgb(255, 0, 0))
+            });
+        });
+
+        app.set_scale_factor(2.0);
+
+        let image = app.render();
+        assert_eq!((image.width(), image.height()), (80, 40));
+        assert_eq!(image.pixel(21, 11), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(38, 28), [255, 0, 0, 255]);
+        assert_ne!(image.pixel(19, 11), [255, 0, 0, 255]);
+        assert_ne!(image.pixel(41, 11), [255, 0, 0, 255]);
+
+        assert_eq!(app.state.data.get_width(Entity::root()), 40.0);
+        assert_eq!(app.state.scale_factor(), 2.0);
+    }
+
+    /// A widget overriding `on_draw` can draw parts of its style with the drawing helpers
+    #[test]
+    fn draw_helpers() {
+        let mut app = TestApp::new(40, 20, |state, window| {
+            Frame.build(state, window, |builder| {
+                builder
+                    .set_width(Units::Pixels(20.0))
+                    .set_height(Units::Pixels(20.0))
+                    .set_background_color(Color::rgb(0, 0, 255))
+                    .set_border_width(Units::Pixels(4.0))
+                    .set_border_color(Color::rgb(255, 0, 0))
+            });
+        });
+
+        let image = app.render();
+        assert_eq!(image.pixel(10, 1), [255, 0, 0, 255]);
+        assert_ne!(image.pixel(10, 10), [0, 0, 255, 255]);
+    }
+
+    /// Each render stores the work done by the systems in the profiler, once it is enabled
+    #[test]
+    fn profiler_records() {
+        let mut app = TestApp::new(40, 20, |state, window| {
+            state.profiler.set_enabled(true);
+
+            for _ in 0..2 {
+                Element.build(state, window, |builder| {
+                    builder
+                        .set_width(Units::Pixels(10.0))
+                        .set_height(Units::Pixels(10.0))
+                        .set_background_color(Color::rgb(255, 0, 0))
+                });
+            }
+        });
+
+        app.render();
+
+        let record = app.state.profiler.latest().cloned().unwrap();
+        // Every restyle matches the rules of both entities
+        let style = record.get(ProfiledSystem::Style);
+        assert!(style.runs > 0);
+        assert_eq!(style.count, 2 * style.runs);
+        assert_eq!(record.get(ProfiledSystem::Clipping).count, 2);
+        assert!(record.get(ProfiledSystem::Events).count > 0);
+        assert!(record.get(ProfiledSystem::Draw).count >= 2);
+        assert_eq!(app.state.profiler.records().count(), 1);
+    }
+
+    /// Raw pointer input hovers and presses the widget under the cursor
+    #[test]
+    fn pointer_input() {
+        let mut element = Entity::null();
+        let mut app = TestApp::new(40, 20, |state, window| {
+            element = Element.build(state, window, |builder| {
+                builder
+                    .set_left(Units::Pixels(10.0))
+                    .set_top(Units::Pixels(5.0))
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+            });
+        });
+
+        app.render();
+
+        app.input(InputEvent::PointerMoved(15.0, 10.0));
+        assert_eq!(app.state.hovered, element);
+
+        app.input(InputEvent::Button(MouseButton::Right, MouseButtonState::Pressed));
+        assert_eq!(app.state.mouse.right.pressed, element);
+        assert_eq!(app.state.mouse.right.pos_down, (15.0, 10.0));
+        assert_eq!(app.state.mouse.left.pressed, Entity::null());
+
+        app.input(InputEvent::PointerMoved(5.0, 10.0));
+        assert_eq!(app.state.hovered, Entity::root());
+    }
+
+    /// Tab visits entities with a positive tab-index first, skips negative tab-indices and wraps around in both directions
+    #[test]
+    fn tab_order() {
+        let mut entities = Vec::new();
+        let mut app = TestApp::new(40, 20, |state, window| {
+            for tab_index in [0, -1, 1].iter() {
+                entities.push(Element.build(state, window, |builder| {
+                    builder
+                        .set_height(Units::Pixels(5.0))
+                        .set_tab_index(*tab_index)
+                }));
+            }
+        });
+
+        app.render();
+
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, entities[2]);
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, entities[0]);
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, entities[2]);
+
+        app.input(InputEvent::Modifiers(ModifiersState { shift: true, ..Default::default() }));
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, entities[0]);
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, entities[2]);
+    }
+
+    /// Tab stays inside of a focus scope, where a composite widget is a single stop whose members are focused with the arrow keys
+    #[test]
+    fn focus_scope_and_arrows() {
+        let mut field = Entity::null();
+        let mut items = Vec::new();
+        let mut app = TestApp::new(40, 40, |state, window| {
+            Element.build(state, window, |builder| builder.set_height(Units::Pixels(5.0)));
+
+            let dialog = Element.build(state, window, |builder| builder.set_focusable(false).set_focus_scope(true));
+            field = Element.build(state, dialog, |builder| builder.set_height(Units::Pixels(5.0)));
+
+            let list = Element.build(state, dialog, |builder| {
+                builder
+                    .set_focusable(false)
+                    .set_arrow_navigation(ArrowNavigation::Vertical)
+            });
+
+            for _ in 0..3 {
+                items.push(Element.build(state, list, |builder| builder.set_height(Units::Pixels(5.0))));
+            }
+        });
+
+        app.render();
+        app.state.set_focus(field);
+
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, items[0]);
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, field);
+        press(&mut app, Code::Tab);
+
+        press(&mut app, Code::ArrowDown);
+        assert_eq!(app.state.focused, items[1]);
+        // Only up and down move the focus in a vertical list
+        press(&mut app, Code::ArrowRight);
+        assert_eq!(app.state.focused, items[1]);
+        press(&mut app, Code::ArrowUp);
+        press(&mut app, Code::ArrowUp);
+        assert_eq!(app.state.focused, items[2]);
+        press(&mut app, Code::Home);
+        assert_eq!(app.state.focused, items[0]);
+        press(&mut app, Code::End);
+
+        // The focused member is the stop of the list, and otherwise the first member
+        app.input(InputEvent::Modifiers(ModifiersState { shift: true, ..Default::default() }));
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, field);
+        press(&mut app, Code::Tab);
+        assert_eq!(app.state.focused, items[0]);
+    }
+
+    /// A drag past the threshold moves a ghost with the pointer and drops its payload onto the slot which accepted it
+    #[test]
+    fn drag_and_drop() {
+        let mut source = Entity::null();
+        let mut slot = Entity::null();
+        let mut app = TestApp::new(40, 20, |state, window| {
+            source = DragSource(7).build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+            });
+
+            slot = DropSlot::default().build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_left(Units::Pixels(20.0))
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+            });
+        });
+
+        app.render();
+
+        app.input(InputEvent::PointerMoved(5.0, 5.0));
+        app.input(InputEvent::Button(MouseButton::Left, MouseButtonState::Pressed));
+        app.input(InputEvent::PointerMoved(7.0, 5.0));
+        assert!(!app.state.drag.is_dragging());
+
+        app.input(InputEvent::PointerMoved(25.0, 5.0));
+        assert!(app.state.drag.is_dragging());
+        assert_eq!(app.state.drag.target(), slot);
+        assert!(app.state.style.pseudo_classes.get(slot).unwrap().contains(PseudoClass::DRAG_OVER));
+
+        // The ghost keeps the offset of the pointer from the source and doesn't hide the slot from hovering
+        let ghost = app.state.drag.ghost();
+        app.render();
+        app.input(InputEvent::PointerMoved(26.0, 6.0));
+        assert_eq!(app.state.data.get_posx(ghost), 21.0);
+        assert_eq!(app.state.hovered, slot);
+
+        app.input(InputEvent::Button(MouseButton::Left, MouseButtonState::Released));
+        assert!(!app.state.drag.is_dragging());
+        assert!(!app.state.style.pseudo_classes.get(slot).unwrap().contains(PseudoClass::DRAG_OVER));
+        assert_eq!(app.state.query::<DropSlot>(slot).unwrap().dropped, Some(7));
+    }
+
+    /// A saved recording of a drag onto the slot drops the number when replayed
+    #[test]
+    fn replay_recording() {
+        const RECORDING: &str = "\
+0 PointerMoved 5 5
+120000 Button Left Pressed
+250000 PointerMoved 15 5
+380000 PointerMoved 25 5
+510000 Button Left Released
+";
+
+        let mut slot = Entity::null();
+        let mut app = TestApp::new(40, 20, |state, window| {
+            DragSource(3).build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+            });
+
+            slot = DropSlot::default().build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_left(Units::Pixels(20.0))
+                    .set_width(Units::Pixels(10.0))
+                    .set_height(Units::Pixels(10.0))
+            });
+        });
+
+        app.render();
+        app.replay(&RECORDING.parse::<InputRecording>().unwrap());
+
+        assert!(!app.state.drag.is_dragging());
+        assert_eq!(app.state.query::<DropSlot>(slot).unwrap().dropped, Some(3));
+    }
+
+    /// Files dragged over the window follow the hovered entity and are dropped onto the entity under the cursor
+    #[test]
+    fn file_hover() {
+        let mut targets = Vec::new();
+        let mut app = TestApp::new(40, 20, |state, window| {
+            for left in [0.0, 20.0].iter() {
+                targets.push(FileTarget::default().build(state, window, |builder| {
+                    builder
+                        .set_position_type(PositionType::SelfDirected)
+                        .set_left(Units::Pixels(*left))
+                        .set_width(Units::Pixels(10.0))
+                        .set_height(Units::Pixels(10.0))
+                }));
+            }
+        });
+
+        app.render();
+
+        app.input(InputEvent::PointerMoved(5.0, 5.0));
+        app.input(InputEvent::FileHovered(PathBuf::from("kick.wav")));
+        app.input(InputEvent::PointerMoved(25.0, 5.0));
+        app.input(InputEvent::FileDropped(PathBuf::from("kick.wav")));
+
+        assert_eq!(app.state.query::<FileTarget>(targets[0]).unwrap().events, vec!["hovered kick.wav", "cancelled"]);
+        assert_eq!(
+            app.state.query::<FileTarget>(targets[1]).unwrap().events,
+            vec!["hovered kick.wav", "dropped kick.wav"]
+        );
+
+        // Once the hover is cancelled, moving the pointer doesn't send the files to another entity
+        app.input(InputEvent::FileHovered(PathBuf::from("snare.wav")));
+        app.input(InputEvent::FileHoverCancelled);
+        assert_eq!(app.state.query::<FileTarget>(targets[1]).unwrap().events.len(), 4);
+        app.input(InputEvent::PointerMoved(5.0, 5.0));
+        assert_eq!(app.state.query::<FileTarget>(targets[0]).unwrap().events.len(), 2);
+    }
+
+    // Records the names of the images which failed to load
+    struct ImageFailures(std::rc::Rc<std::cell::RefCell<Vec<String>>>);
+
+    impl Widget for ImageFailures {
+        type Ret = Entity;
+        type Data = ();
+        fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+            entity
+        }
+
+        fn on_event(&mut self, _state: &mut State, _entity: Entity, event: &mut Event) {
+            if let Some(WindowEvent::ImageLoadFailed(name, _)) = event.message.downcast::<WindowEvent>() {
+                self.0.borrow_mut().push(name.clone());
+            }
+        }
+    }
+
+    /// An image which can't be decoded is dropped instead of stopping the application, and entities are told
+    #[test]
+    fn undecodable_image() {
+        let failures = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorder = ImageFailures(failures.clone());
+        let mut app = TestApp::new(20, 20, |state, window| {
+            recorder.build(state, window, |builder| builder);
+            state.add_image_mem("broken", b"not an image");
+        });
+
+        app.render();
+        app.update();
+        assert!(app.state.resource_manager.get_image("broken").is_none());
+        assert!(!app.state.resource_manager.images.contains_key("broken"));
+        assert_eq!(*failures.borrow(), vec!["broken".to_string()]);
+    }
+
+    /// Adding an image with the name of an existing image replaces it
+    #[test]
+    fn replace_image() {
+        let mut app = TestApp::new(20, 20, |state, _| {
+            state.add_image_mem("broken", b"not an image");
+        });
+
+        app.state.add_image_mem("broken", b"still not an image");
+        match app.state.resource_manager.images.get("broken") {
+            Some(ImageOrId::Image(data)) => assert_eq!(data.as_slice(), b"still not an image"),
+            _ => panic!("The image was not replaced"),
+        }
+    }
+
+    /// An `Auto` width or height resolves to the content size, without changing the style of the entity
+    #[test]
+    fn content_size() {
+        let mut entity = Entity::null();
+        let mut app = TestApp::new(40, 40, |state, window| {
+            entity = Element.build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_width(Units::Auto)
+                    .set_height(Units::Pixels(5.0))
+            });
+        });
+
+        entity.set_content_size(&mut app.state, Some((12.0, 8.0)));
+        app.render();
+
+        assert_eq!(app.state.data.get_width(entity), 12.0);
+        assert_eq!(app.state.data.get_height(entity), 5.0);
+        assert_eq!(entity.get_width(&mut app.state), Units::Auto);
+    }
+
+    /// An entity clipped by a rounded clip widget inside another rounded clip widget is clipped to both shapes
+    #[test]
+    fn nested_rounded_clips() {
+        let mut child = Entity::null();
+        let mut app = TestApp::new(40, 40, |state, window| {
+            let outer = Element.build(state, window, |builder| {
+                builder
+                    .set_width(Units::Pixels(40.0))
+                    .set_height(Units::Pixels(40.0))
+                    .set_border_radius_top_left(Units::Pixels(20.0))
+            });
+
+            let inner = Element.build(state, outer, |builder| {
+                builder
+                    .set_width(Units::Pixels(40.0))
+                    .set_height(Units::Pixels(40.0))
+                    .set_border_radius_bottom_right(Units::Pixels(20.0))
+                    .set_clip_widget(outer)
+            });
+
+            child = Element.build(state, inner, |builder| {
+                builder
+                    .set_width(Units::Pixels(40.0))
+                    .set_height(Units::Pixels(40.0))
+                    .set_background_color(Color::rgb(255, 0, 0))
+                    .set_clip_widget(inner)
+            });
+        });
+
+        let image = app.render();
+        assert_eq!(image.pixel(20, 20), [255, 0, 0, 255]);
+        assert_ne!(image.pixel(1, 1), [255, 0, 0, 255]);
+        assert_ne!(image.pixel(38, 38), [255, 0, 0, 255]);
+
+        app.input(InputEvent::PointerMoved(20.0, 20.0));
+        assert_eq!(app.state.hovered, child);
+
+        app.input(InputEvent::PointerMoved(1.0, 1.0));
+        assert_ne!(app.state.hovered, child);
+
+        app.input(InputEvent::PointerMoved(38.0, 38.0));
+        assert_ne!(app.state.hovered, child);
+    }
+
+    /// Setting a style property on an entity damages only that entity, not the whole window
+    #[test]
+    fn setter_damage() {
+        let mut first = Entity::null();
+        let mut second = Entity::null();
+        let mut app = TestApp::new(40, 20, |state, window| {
+            first = Element.build(state, window, |builder| {
+                builder
+                    .set_width(Units::Pixels(20.0))
+                    .set_height(Units::Pixels(20.0))
+            });
+
+            second = Element.build(state, window, |builder| {
+                builder
+                    .set_position_type(PositionType::SelfDirected)
+                    .set_left(Units::Pixels(20.0))
+                    .set_width(Units::Pixels(20.0))
+                    .set_height(Units::Pixels(20.0))
+            });
+        });
+
+        app.render();
+
+        second.set_background_color(&mut app.state, Color::rgb(255, 0, 0));
+        app.update();
+
+        assert!(!app.state.damage.is_full());
+        assert_eq!(app.state.damage.entities().cloned().collect::<Vec<_>>(), vec![second]);
+
+        let image = app.render();
+        assert_eq!(image.pixel(30, 10), [255, 0, 0, 255]);
+    }
+
+    /// A retained layer is composited from its image, rather than repainted, when a change in another layer
+    /// damages the whole window
+    #[test]
+    fn retained_layer_full_damage() {
+        let draws = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = DrawCounter(draws.clone());
+        let mut app = TestApp::new(40, 20, |state, window| {
+            counter.build(state, window, |builder| {
+                builder
+                    .set_z_order(-1)
+                    .set_layer(LayerMode::Retained)
+                    .set_width(Units::Pixels(40.0))
+                    .set_height(Units::Pixels(20.0))
+            });
+        });
+
+        app.render();
+        assert_eq!(draws.get(), 1);
+
+        // Adding an entity changes the tree, which damages the whole window
+        Element.build(&mut app.state, Entity::root(), |builder| {
+            builder
+                .set_width(Units::Pixels(10.0))
+                .set_height(Units::Pixels(10.0))
+                .set_background_color(Color::rgb(255, 0, 0))
+        });
+
+        let image = app.render();
+        assert_eq!(image.pixel(5, 5), [255, 0, 0, 255]);
+        assert_eq!(draws.get(), 1);
+    }
+}
@@ -0,0 +1,245 @@
+use std::ops::Range;
+
+use tuix_core::{Align, Baseline, FontMetrics, GlyphPosition, Paint, Path, RenderError, TextMetrics};
+
+/// Fonts added to a [SoftwareRenderer](crate::SoftwareRenderer). Glyphs are drawn by filling their outlines.
+#[derive(Default)]
+pub(crate) struct Fonts {
+    fonts: Vec<Vec<u8>>,
+}
+
+// A glyph placed on a line of text
+struct PlacedGlyph {
+    font: usize,
+    glyph: ttf_parser::GlyphId,
+    position: GlyphPosition,
+}
+
+// Converts glyph outlines to a path, flipping the y axis and scaling from font units to pixels
+struct OutlineBuilder<'a> {
+    path: &'a mut Path,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl<'a> OutlineBuilder<'a> {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.scale, self.y - y * self.scale)
+    }
+}
+
+impl<'a> ttf_parser::OutlineBuilder for OutlineBuilder<'a> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.path.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.path.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.path.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.path.bezier_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
+
+impl Fonts {
+    /// Adds a font and returns its index
+    pub fn add(&mut self, data: &[u8]) -> Result<usize, RenderError> {
+        ttf_parser::Face::from_slice(data, 0).map_err(|_| RenderError::InvalidData)?;
+        self.fonts.push(data.to_vec());
+        Ok(self.fonts.len() - 1)
+    }
+
+    fn face(&self, index: usize) -> Option<ttf_parser::Face<'_>> {
+        self.fonts.get(index).and_then(|data| ttf_parser::Face::from_slice(data, 0).ok())
+    }
+
+    // Returns the fonts of the paint which have been added, in order of preference
+    fn faces(&self, paint: &Paint) -> Vec<(usize, ttf_parser::Face<'_>)> {
+        paint
+            .font_ids()
+            .filter_map(|font_id| self.face(font_id.0).map(|face| (font_id.0, face)))
+            .collect()
+    }
+
+    pub fn measure_font(&self, paint: &Paint) -> Result<FontMetrics, RenderError> {
+        let faces = self.faces(paint);
+        let (_, face) = faces.first().ok_or(RenderError::FontNotFound)?;
+        let scale = paint.font_size / face.units_per_em() as f32;
+
+        Ok(FontMetrics {
+            ascender: face.ascender() as f32 * scale,
+            descender: face.descender() as f32 * scale,
+            height: (face.ascender() as f32 - face.descender() as f32 + face.line_gap() as f32) * scale,
+        })
+    }
+
+    // Places the glyphs of a line of text, aligned to (x, y) by the alignment and baseline of the paint
+    fn layout(&self, x: f32, y: f32, text: &str, paint: &Paint) -> Result<(TextMetrics, Vec<PlacedGlyph>), RenderError> {
+        let faces = self.faces(paint);
+        if faces.is_empty() {
+            return Err(RenderError::FontNotFound);
+        }
+
+        let metrics = self.measure_font(paint)?;
+
+        let mut glyphs = Vec::new();
+        let mut pen_x = 0.0;
+
+        for (byte_index, c) in text.char_indices() {
+            // Use the first font with a glyph for the character
+            let (font, face, glyph) = faces
+                .iter()
+                .find_map(|(font, face)| face.glyph_index(c).map(|glyph| (*font, face, glyph)))
+                .unwrap_or((faces[0].0, &faces[0].1, ttf_parser::GlyphId(0)));
+
+            let scale = paint.font_size / face.units_per_em() as f32;
+            let advance = face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+
+            glyphs.push(PlacedGlyph {
+                font,
+                glyph,
+                position: GlyphPosition {
+                    c,
+                    byte_index,
+                    x: pen_x,
+                    y: 0.0,
+                    width: advance,
+                    height: metrics.ascender - metrics.descender,
+                },
+            });
+
+            pen_x += advance;
+        }
+
+        let width = pen_x;
+
+        let offset_x = match paint.text_align {
+            Align::Left => 0.0,
+            Align::Center => -0.5 * width,
+            Align::Right => -width,
+        };
+
+        let offset_y = match paint.text_baseline {
+            Baseline::Top => metrics.ascender,
+            Baseline::Middle => 0.5 * (metrics.ascender + metrics.descender),
+            Baseline::Alphabetic => 0.0,
+            Baseline::Bottom => metrics.descender,
+        };
+
+        let baseline = y + offset_y;
+        for placed in glyphs.iter_mut() {
+            placed.position.x += x + offset_x;
+            placed.position.y = baseline - metrics.ascender;
+        }
+
+        let text_metrics = TextMetrics {
+            x: x + offset_x,
+            y: baseline - metrics.ascender,
+            width,
+            height: metrics.ascender - metrics.descender,
+            glyphs: glyphs.iter().map(|placed| placed.position).collect(),
+        };
+
+        Ok((text_metrics, glyphs))
+    }
+
+    pub fn measure_text(&self, x: f32, y: f32, text: &str, paint: &Paint) -> Result<TextMetrics, RenderError> {
+        self.layout(x, y, text, paint).map(|(metrics, _)| metrics)
+    }
+
+    /// Returns the outlines of the glyphs of a line of text as a path, along with the text metrics
+    pub fn text_path(&self, x: f32, y: f32, text: &str, paint: &Paint) -> Result<(TextMetrics, Path), RenderError> {
+        let (metrics, glyphs) = self.layout(x, y, text, paint)?;
+        let font_metrics = self.measure_font(paint)?;
+
+        let mut path = Path::new();
+        for placed in glyphs.iter() {
+            if let Some(face) = self.face(placed.font) {
+                let mut builder = OutlineBuilder {
+                    path: &mut path,
+                    x: placed.position.x,
+                    y: placed.position.y + font_metrics.ascender,
+                    scale: paint.font_size / face.units_per_em() as f32,
+                };
+
+                face.outline_glyph(placed.glyph, &mut builder);
+            }
+        }
+
+        Ok((metrics, path))
+    }
+
+    /// Splits text into lines which fit within max_width, breaking at whitespace and newlines
+    pub fn break_text_vec(&self, max_width: f32, text: &str, paint: &Paint) -> Result<Vec<Range<usize>>, RenderError> {
+        let (_, glyphs) = self.layout(0.0, 0.0, text, paint)?;
+
+        let mut lines = Vec::new();
+        let mut line_start = 0;
+        let mut line_x = 0.0;
+        // The end of the last word on the line and the start of the next one
+        let mut break_point: Option<(usize, usize)> = None;
+        let mut in_word = false;
+
+        for placed in glyphs.iter() {
+            let GlyphPosition { c, byte_index, x, width, .. } = placed.position;
+
+            if c == '\n' {
+                lines.push(line_start..byte_index);
+                line_start = byte_index + c.len_utf8();
+                line_x = x + width;
+                break_point = None;
+                in_word = false;
+                continue;
+            }
+
+            if c.is_whitespace() {
+                if in_word {
+                    break_point = Some((byte_index, byte_index));
+                }
+
+                if let Some((end, _)) = break_point {
+                    break_point = Some((end, byte_index + c.len_utf8()));
+                }
+
+                in_word = false;
+                continue;
+            }
+
+            in_word = true;
+
+            if x + width - line_x > max_width {
+                if let Some((end, next_start)) = break_point.take() {
+                    lines.push(line_start..end);
+                    line_start = next_start;
+                    line_x = glyphs
+                        .iter()
+                        .find(|glyph| glyph.position.byte_index >= next_start)
+                        .map_or(x, |glyph| glyph.position.x);
+                }
+            }
+        }
+
+        if line_start < text.len() || lines.is_empty() {
+            lines.push(line_start..text.len());
+        }
+
+        Ok(lines)
+    }
+}
@@ -18,3 +18,197 @@ fn pseudoclass_error() {
 }
 
 
+const BORDER_SIDES: &str = r#"
+
+    button {
+        border: 1px solid #202020;
+        border-top: dashed 2px red;
+        border-left-width: 3px;
+        border-bottom-style: dotted;
+        border-right-color: blue;
+    }
+"#;
+
+// Applies the style rules to the entities, as the restyle of a frame does
+fn restyle(state: &mut State) {
+    let tree = state.tree.clone();
+    apply_styles(state, &tree);
+}
+
+#[test]
+fn border_sides() {
+    let mut state = State::new();
+    state.add_theme(BORDER_SIDES);
+
+    let button = state.add(Entity::root()).set_element(&mut state, "button");
+    restyle(&mut state);
+
+    assert_eq!(state.style.border_width_top.get(button), Some(&Units::Pixels(2.0)));
+    assert_eq!(state.style.border_width_right.get(button), Some(&Units::Pixels(1.0)));
+    assert_eq!(state.style.border_width_left.get(button), Some(&Units::Pixels(3.0)));
+    assert_eq!(state.style.border_style_top.get(button), Some(&BorderStyle::Dashed));
+    assert_eq!(state.style.border_style_bottom.get(button), Some(&BorderStyle::Dotted));
+    assert_eq!(state.style.border_style_left.get(button), Some(&BorderStyle::Solid));
+    assert_eq!(state.style.border_color_right.get(button), Some(&Color::blue()));
+}
+
+
+const BORDER_CASCADE: &str = r#"
+
+    .primary {
+        border-top: 2px dashed red;
+    }
+
+    button.primary {
+        border: 1px solid blue;
+        border-left-width: 4px;
+    }
+
+    .secondary {
+        border-width: 1px;
+    }
+
+    button.secondary {
+        border-bottom-width: 5px;
+    }
+"#;
+
+/// A shorthand and a side conflict by specificity, and by order within a rule
+#[test]
+fn border_cascade() {
+    let mut state = State::new();
+    state.add_theme(BORDER_CASCADE);
+
+    let primary = state.add(Entity::root()).set_element(&mut state, "button").class(&mut state, "primary");
+    let secondary = state.add(Entity::root()).set_element(&mut state, "button").class(&mut state, "secondary");
+    restyle(&mut state);
+
+    assert_eq!(state.style.border_width_top.get(primary), Some(&Units::Pixels(1.0)));
+    assert_eq!(state.style.border_style_top.get(primary), Some(&BorderStyle::Solid));
+    assert_eq!(state.style.border_width_left.get(primary), Some(&Units::Pixels(4.0)));
+
+    assert_eq!(state.style.border_width_top.get(secondary), Some(&Units::Pixels(1.0)));
+    assert_eq!(state.style.border_width_bottom.get(secondary), Some(&Units::Pixels(5.0)));
+}
+
+
+const BORDER_RESET: &str = r#"
+
+    button {
+        border: 2px dashed red;
+        border-bottom: 3px dotted blue;
+    }
+
+    button.plain {
+        border: 1px;
+        border-bottom: dotted;
+    }
+"#;
+
+/// A border shorthand resets the values it omits, rather than keeping the values of a less specific rule
+#[test]
+fn border_reset() {
+    let mut state = State::new();
+    state.add_theme(BORDER_RESET);
+
+    let button = state.add(Entity::root()).set_element(&mut state, "button").class(&mut state, "plain");
+    restyle(&mut state);
+
+    assert_eq!(state.style.border_width.get(button), Some(&Units::Pixels(1.0)));
+    assert_eq!(state.style.border_style.get(button), Some(&BorderStyle::Solid));
+    assert_eq!(state.style.border_color.get(button), Some(&Color::default()));
+    assert_eq!(state.style.border_style_top.get(button), Some(&BorderStyle::Solid));
+    assert_eq!(state.style.border_color_top.get(button), Some(&Color::default()));
+
+    assert_eq!(state.style.border_width_bottom.get(button), Some(&Units::default()));
+    assert_eq!(state.style.border_style_bottom.get(button), Some(&BorderStyle::Dotted));
+    assert_eq!(state.style.border_color_bottom.get(button), Some(&Color::default()));
+}
+
+
+const RETAINED_LAYER: &str = r#"
+
+    .background {
+        z-index: -1;
+        layer: retained;
+    }
+"#;
+
+#[test]
+fn retained_layer() {
+    let mut state = State::new();
+    state.add_theme(RETAINED_LAYER);
+
+    let background = state.add(Entity::root()).class(&mut state, "background");
+    restyle(&mut state);
+
+    assert_eq!(state.style.layer.get(background), Some(&LayerMode::Retained));
+    assert_eq!(state.style.z_order.get(background), Some(&-1));
+}
+
+
+const MIX_BLEND_MODE: &str = r#"
+
+    .overlay {
+        opacity: 0.5;
+        mix-blend-mode: multiply;
+    }
+"#;
+
+#[test]
+fn mix_blend_mode() {
+    let mut state = State::new();
+    state.add_theme(MIX_BLEND_MODE);
+
+    let overlay = state.add(Entity::root()).class(&mut state, "overlay");
+    restyle(&mut state);
+
+    assert_eq!(state.style.mix_blend_mode.get(overlay), Some(&BlendMode::Multiply));
+}
+
+
+const TAB_INDEX: &str = r#"
+
+    .search {
+        tab-index: 1;
+    }
+
+    .decoration {
+        tab-index: -1;
+    }
+"#;
+
+#[test]
+fn tab_index() {
+    let mut state = State::new();
+    state.add_theme(TAB_INDEX);
+
+    let search = state.add(Entity::root()).class(&mut state, "search");
+    let decoration = state.add(Entity::root()).class(&mut state, "decoration");
+    restyle(&mut state);
+
+    assert_eq!(state.style.tab_index.get(search), Some(&1));
+    assert_eq!(state.style.tab_index.get(decoration), Some(&-1));
+}
+
+
+const DRAG_OVER: &str = r#"
+
+    .slot:drag-over {
+        border: 2px solid #4080ff;
+    }
+"#;
+
+#[test]
+fn drag_over() {
+    let mut state = State::new();
+    state.add_theme(DRAG_OVER);
+
+    let slot = state.add(Entity::root()).class(&mut state, "slot");
+    restyle(&mut state);
+    assert_eq!(state.style.border_width_top.get(slot), None);
+
+    state.style.pseudo_classes.insert(slot, PseudoClass::DRAG_OVER).unwrap();
+    restyle(&mut state);
+    assert_eq!(state.style.border_width_top.get(slot), Some(&Units::Pixels(2.0)));
+}
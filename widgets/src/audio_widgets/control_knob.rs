@@ -4,7 +4,7 @@ use crate::common::*;
 
 use crate::slider::SliderEvent;
 
-use femtovg::{renderer::OpenGl, Canvas, LineCap, Paint, Path, Solidity};
+use tuix_core::{LineCap, Paint, Path, Solidity};
 
 use std::sync::{Arc, Mutex};
 
@@ -217,7 +217,7 @@ impl Widget for ControlKnob {
         }
     }
 
-    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas) {
         if state.data.get_visibility(entity) == Visibility::Invisible {
             return;
         }
@@ -1,5 +1,5 @@
 use crate::{SliderEvent, common::*};
-use femtovg::*;
+use tuix_core::{LineCap, Paint, Path, Solidity};
 
 use super::NormalizedMap;
 
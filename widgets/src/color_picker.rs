@@ -1,6 +1,5 @@
 
-use femtovg::{ImageFlags, ImageId, PixelFormat, RenderTarget};
-use tuix_core::Store;
+use tuix_core::{ImageFlags, ImageId, Paint, Path, PixelFormat, RenderTarget, Store};
 
 use crate::{Column, Label, Row, Textbox, TextboxEvent, common::*};
 
@@ -578,7 +577,7 @@ impl Widget for ColorGradient {
                 canvas.set_render_target(RenderTarget::Image(image_id));
                 
     
-                canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, femtovg::Color::rgb(0, 0, 0));
+                canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, Color::rgb(0, 0, 0));
                 for x in 0..64 {
                     for y in 0..64 {
     
@@ -592,30 +591,31 @@ impl Widget for ColorGradient {
                             y as u32,
                             1,
                             1,
-                            femtovg::Color::hsl(self.hue, s as f32, v as f32),
+                            Color::hsl(self.hue, s as f32, v as f32),
                         );
                     }
                 }
             }
             canvas.restore();
-            canvas.set_render_target(RenderTarget::Screen);
+            canvas.set_render_target(state.render_target);
 
             //println!("Draw Picker: {} {:?}", entity, image_id);
             canvas.save();
             canvas.reset();
             canvas.reset_scissor();
             canvas.reset_transform();
+            canvas.scale(state.scale_factor(), state.scale_factor());
             
 
             let bounds = state.data.get_bounds(entity);
 
             //println!("Bounds: {:?}", bounds);
 
-            let mut path = femtovg::Path::new();
+            let mut path = Path::new();
             path.rect(bounds.x, bounds.y, bounds.w, bounds.h);
             canvas.fill_path(
                 &mut path,
-                femtovg::Paint::image(image_id, bounds.x, bounds.y, bounds.w, bounds.h, 0f32, 1f32),
+                Paint::image(image_id, bounds.x, bounds.y, bounds.w, bounds.h, 0f32, 1f32),
             );
             canvas.restore();
         }
@@ -978,7 +978,7 @@ impl Widget for AlphaSlider {
                 canvas.set_render_target(RenderTarget::Image(image_id));
                 
     
-                canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, femtovg::Color::rgb(0, 0, 0));
+                canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, Color::rgb(0, 0, 0));
                 
                 for x in 0..(size.0 / grid_size) {
                     for y in 0..(size.1 / grid_size) {
@@ -990,35 +990,36 @@ impl Widget for AlphaSlider {
                             (grid_size) as u32,
                             
                             match (x % 2, y % 2) {
-                                (0, 0) => femtovg::Color::rgb(125, 125, 125),
-                                (1, 0) => femtovg::Color::rgb(155, 155, 155),
-                                (0, 1) => femtovg::Color::rgb(155, 155, 155),
-                                (1, 1) => femtovg::Color::rgb(125, 125, 125),
-                                _ => femtovg::Color::rgb(255, 0, 255),
+                                (0, 0) => Color::rgb(125, 125, 125),
+                                (1, 0) => Color::rgb(155, 155, 155),
+                                (0, 1) => Color::rgb(155, 155, 155),
+                                (1, 1) => Color::rgb(125, 125, 125),
+                                _ => Color::rgb(255, 0, 255),
                             },
                         );
                     }
                 }
             }
             canvas.restore();
-            canvas.set_render_target(RenderTarget::Screen);
+            canvas.set_render_target(state.render_target);
 
             //println!("Draw Picker: {} {:?}", entity, image_id);
             canvas.save();
             canvas.reset();
             canvas.reset_scissor();
             canvas.reset_transform();
+            canvas.scale(state.scale_factor(), state.scale_factor());
             
 
             let bounds = state.data.get_bounds(self.track);
 
             //println!("Bounds: {:?}", bounds);
 
-            let mut path = femtovg::Path::new();
+            let mut path = Path::new();
             path.rect(bounds.x, bounds.y, bounds.w, bounds.h);
             canvas.fill_path(
                 &mut path,
-                femtovg::Paint::image(image_id, bounds.x, bounds.y, bounds.w, bounds.h, 0f32, 1f32),
+                Paint::image(image_id, bounds.x, bounds.y, bounds.w, bounds.h, 0f32, 1f32),
             );
 
             canvas.restore();
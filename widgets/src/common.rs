@@ -4,7 +4,7 @@ pub(crate) use tuix_core::{MouseButton, MouseButtonState};
 pub(crate) use tuix_core::State;
 pub(crate) use tuix_core::{Animation, PropGet, PropSet, AnimExt, BindExt, TreeExt};
 pub(crate) use tuix_core::{Code, Key};
-pub(crate) type Canvas = femtovg::Canvas<femtovg::renderer::OpenGl>;
+pub(crate) use tuix_core::Canvas;
 pub(crate) use tuix_core::Units::*;
 pub(crate) use tuix_core::style::*;
 pub(crate) use tuix_core::{Model, LensWrap, BindEvent, Node};
@@ -0,0 +1,88 @@
+use crate::common::*;
+
+/// A widget which draws a named SVG resource.
+///
+/// SVGs are added to the application with `state.add_svg()` or `state.add_svg_mem()`.
+/// By default the icon is recoloured with the `color` property of the entity. Use `with_recolor(false)`
+/// to keep the colours of the document, in which case only `currentColor` fills and strokes use the `color` property.
+/// If the width or height of the widget is `Auto` the widget is sized to the SVG dimensions, see
+/// [set_content_size](tuix_core::PropSet::set_content_size).
+///
+/// # Example
+///
+/// ```
+/// state.add_svg("play", "resources/icons/play.svg").unwrap();
+///
+/// Icon::new("play").build(state, parent, |builder|
+///     builder
+///         .set_width(Pixels(16.0))
+///         .set_height(Pixels(16.0))
+///         .set_color(Color::white())
+/// );
+/// ```
+pub struct Icon {
+    name: String,
+    recolor: bool,
+}
+
+impl Icon {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            recolor: true,
+        }
+    }
+
+    /// Set whether all fills and strokes are drawn with the `color` property
+    pub fn with_recolor(mut self, recolor: bool) -> Self {
+        self.recolor = recolor;
+        self
+    }
+}
+
+impl Icon {
+    // Returns the size of the SVG document, or None if there is no SVG with the name
+    fn svg_size(&self, state: &State) -> Option<(f32, f32)> {
+        state.resource_manager.svgs.get(&self.name).map(|svg| (svg.width, svg.height))
+    }
+}
+
+impl Widget for Icon {
+    type Ret = Entity;
+    type Data = String;
+
+    fn widget_name(&self) -> String {
+        "icon".to_string()
+    }
+
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        let svg_size = self.svg_size(state);
+
+        entity.set_element(state, "icon").set_content_size(state, svg_size)
+    }
+
+    fn on_update(&mut self, state: &mut State, entity: Entity, data: &Self::Data) {
+        if &self.name != data {
+            self.name = data.to_owned();
+            let svg_size = self.svg_size(state);
+            entity.set_content_size(state, svg_size);
+            entity.redraw(state);
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut crate::Canvas) {
+        let color = entity.get_font_color(state);
+        let opacity = state.data.get_opacity(entity);
+
+        let bounds = (
+            state.data.get_posx(entity),
+            state.data.get_posy(entity),
+            state.data.get_width(entity),
+            state.data.get_height(entity),
+        );
+
+        if let Some(svg) = state.resource_manager.svgs.get_mut(&self.name) {
+            svg.draw(canvas, bounds, color, self.recolor, opacity);
+        }
+    }
+}
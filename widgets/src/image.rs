@@ -0,0 +1,253 @@
+use crate::common::*;
+use tuix_core::{Paint, Path};
+
+/// Determines how an image is scaled to fit the bounds of an [Image] widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFit {
+    /// Stretch the image to fill the bounds, ignoring the aspect ratio
+    Fill,
+    /// Scale the image to fit within the bounds, preserving the aspect ratio
+    Contain,
+    /// Scale the image to cover the bounds, preserving the aspect ratio and cropping any overflow
+    Cover,
+    /// Draw the image at its natural size, centered and cropped to the bounds
+    None,
+    /// Split the image into nine regions using the insets (in image pixels).
+    /// The corners are drawn unscaled, the edges are stretched along one axis and the center is stretched to fill.
+    NineSlice {
+        left: f32,
+        right: f32,
+        top: f32,
+        bottom: f32,
+    },
+}
+
+impl Default for ImageFit {
+    fn default() -> Self {
+        ImageFit::Fill
+    }
+}
+
+/// A widget which draws a named image resource.
+///
+/// Images are added to the application with `state.add_image()` or `state.add_image_mem()`.
+/// If the width or height of the widget is `Auto` the widget is sized to the image dimensions
+/// once the image has been loaded, see [set_content_size](tuix_core::PropSet::set_content_size).
+///
+/// # Example
+///
+/// ```
+/// state.add_image("panel", "resources/images/panel.png").unwrap();
+///
+/// Image::new("panel")
+///     .with_nine_slice(8.0, 8.0, 8.0, 8.0)
+///     .build(state, parent, |builder|
+///         builder
+///             .set_width(Pixels(200.0))
+///             .set_height(Pixels(120.0))
+///     );
+/// ```
+pub struct Image {
+    name: String,
+    fit: ImageFit,
+}
+
+impl Image {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            fit: ImageFit::default(),
+        }
+    }
+
+    /// Set the fit mode of the image
+    pub fn with_fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Use nine-slice scaling with the given insets in image pixels
+    pub fn with_nine_slice(mut self, left: f32, right: f32, top: f32, bottom: f32) -> Self {
+        self.fit = ImageFit::NineSlice {
+            left,
+            right,
+            top,
+            bottom,
+        };
+        self
+    }
+
+    // Returns the size of the image in pixels, or None if it hasn't been loaded
+    fn image_size(&self, state: &State) -> Option<(f32, f32)> {
+        state
+            .resource_manager
+            .get_image(&self.name)
+            .map(|(_, (width, height))| (width as f32, height as f32))
+    }
+}
+
+impl Widget for Image {
+    type Ret = Entity;
+    type Data = String;
+
+    fn widget_name(&self) -> String {
+        "image".to_string()
+    }
+
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        let image_size = self.image_size(state);
+
+        entity.set_element(state, "image").set_content_size(state, image_size)
+    }
+
+    fn on_update(&mut self, state: &mut State, entity: Entity, data: &Self::Data) {
+        if &self.name != data {
+            self.name = data.to_owned();
+            let image_size = self.image_size(state);
+            entity.set_content_size(state, image_size);
+            entity.redraw(state);
+        }
+    }
+
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        match event.message.downcast::<WindowEvent>() {
+            Some(WindowEvent::ImageLoaded(name)) | Some(WindowEvent::ImageLoadFailed(name, _)) => {
+                if *name == self.name {
+                    let image_size = self.image_size(state);
+                    entity.set_content_size(state, image_size);
+                    entity.redraw(state);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut crate::Canvas) {
+        let (image_id, (image_width, image_height)) =
+            if let Some(image) = state.resource_manager.get_image(&self.name) {
+                image
+            } else {
+                return;
+            };
+
+        let image_width = image_width as f32;
+        let image_height = image_height as f32;
+
+        let opacity = state.data.get_opacity(entity);
+
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        if width <= 0.0 || height <= 0.0 || image_width <= 0.0 || image_height <= 0.0 {
+            return;
+        }
+
+        // Maps the source rectangle (in image pixels) onto the destination rectangle
+        let draw_region = |canvas: &mut crate::Canvas, src: (f32, f32, f32, f32), dst: (f32, f32, f32, f32)| {
+            let (sx, sy, sw, sh) = src;
+            let (dx, dy, dw, dh) = dst;
+            if sw <= 0.0 || sh <= 0.0 || dw <= 0.0 || dh <= 0.0 {
+                return;
+            }
+
+            let scalex = dw / sw;
+            let scaley = dh / sh;
+
+            let paint = Paint::image(
+                image_id,
+                dx - sx * scalex,
+                dy - sy * scaley,
+                image_width * scalex,
+                image_height * scaley,
+                0.0,
+                opacity,
+            );
+
+            let mut path = Path::new();
+            path.rect(dx, dy, dw, dh);
+            canvas.fill_path(&mut path, paint);
+        };
+
+        let full = (0.0, 0.0, image_width, image_height);
+
+        match self.fit {
+            ImageFit::Fill => {
+                draw_region(canvas, full, (posx, posy, width, height));
+            }
+
+            ImageFit::Contain | ImageFit::Cover | ImageFit::None => {
+                let scale = match self.fit {
+                    ImageFit::Contain => (width / image_width).min(height / image_height),
+                    ImageFit::Cover => (width / image_width).max(height / image_height),
+                    _ => 1.0,
+                };
+
+                let w = image_width * scale;
+                let h = image_height * scale;
+                let x = posx + (width - w) / 2.0;
+                let y = posy + (height - h) / 2.0;
+
+                canvas.save();
+                canvas.intersect_scissor(posx, posy, width, height);
+                draw_region(canvas, full, (x, y, w, h));
+                canvas.restore();
+            }
+
+            ImageFit::NineSlice {
+                left,
+                right,
+                top,
+                bottom,
+            } => {
+                // Clamp the source insets to the image
+                let src_left = left.max(0.0).min(image_width);
+                let src_right = right.max(0.0).min(image_width - src_left);
+                let src_top = top.max(0.0).min(image_height);
+                let src_bottom = bottom.max(0.0).min(image_height - src_top);
+
+                // Shrink the destination corners proportionally if the widget is too small to fit them
+                let scalex = (width / (src_left + src_right)).min(1.0);
+                let scaley = (height / (src_top + src_bottom)).min(1.0);
+
+                let dst_left = src_left * scalex;
+                let dst_right = src_right * scalex;
+                let dst_top = src_top * scaley;
+                let dst_bottom = src_bottom * scaley;
+
+                let src_cols = [
+                    (0.0, src_left),
+                    (src_left, image_width - src_left - src_right),
+                    (image_width - src_right, src_right),
+                ];
+                let src_rows = [
+                    (0.0, src_top),
+                    (src_top, image_height - src_top - src_bottom),
+                    (image_height - src_bottom, src_bottom),
+                ];
+                let dst_cols = [
+                    (posx, dst_left),
+                    (posx + dst_left, width - dst_left - dst_right),
+                    (posx + width - dst_right, dst_right),
+                ];
+                let dst_rows = [
+                    (posy, dst_top),
+                    (posy + dst_top, height - dst_top - dst_bottom),
+                    (posy + height - dst_bottom, dst_bottom),
+                ];
+
+                for row in 0..3 {
+                    for col in 0..3 {
+                        let (sx, sw) = src_cols[col];
+                        let (sy, sh) = src_rows[row];
+                        let (dx, dw) = dst_cols[col];
+                        let (dy, dh) = dst_rows[row];
+                        draw_region(canvas, (sx, sy, sw, sh), (dx, dy, dw, dh));
+                    }
+                }
+            }
+        }
+    }
+}
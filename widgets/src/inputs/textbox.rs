@@ -1,7 +1,6 @@
 use crate::common::*;
 
-use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Paint, Path, Solidity};
-use tuix_core::FontOrId;
+use tuix_core::{Align, Baseline, FontOrId, Paint, Path, Solidity};
 
 use crate::Key;
 
@@ -802,7 +801,7 @@ impl Widget for Textbox {
         &mut self,
         state: &mut State,
         entity: Entity,
-        canvas: &mut Canvas<OpenGl>,
+        canvas: &mut Canvas,
         //images: &HashMap<String, nanovg::Image>,
     ) {
         // Skip window
@@ -50,6 +50,12 @@ pub use panel::*;
 mod label;
 pub use label::*;
 
+mod image;
+pub use image::*;
+
+mod icon;
+pub use icon::*;
+
 mod containers;
 pub use containers::*;
 
@@ -63,6 +69,9 @@ pub use containers::*;
 mod audio_widgets;
 pub use audio_widgets::*;
 
+mod profiler_overlay;
+pub use profiler_overlay::ProfilerOverlay;
+
 // pub mod debug_container;
 // pub use debug_container::*;
 
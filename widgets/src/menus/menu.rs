@@ -1,6 +1,7 @@
 
 
 use crate::{Popup, PopupEvent, common::*};
+use tuix_core::Shortcut;
 
 // Notes:
 // When user clicks menu, the container should appear
@@ -141,6 +142,102 @@ impl Widget for Menu {
     }
 }
 
+/// An item of a menu with a label and, optionally, the keyboard shortcut which does the same thing.
+///
+/// The shortcut is shown on the right of the label, formatted like `Ctrl+S`. It's only shown, so the shortcut
+/// itself should be bound with `state.shortcuts`.
+///
+/// # Example
+/// ```
+/// let save = Shortcut::new(Chord::new(Code::KeyS).ctrl());
+/// state.shortcuts.bind(save.clone(), ShortcutScope::Global, editor, EditorEvent::Save).unwrap();
+///
+/// MenuItem::new("Save")
+///     .with_shortcut(save)
+///     .on_press(move |_, state, item| {
+///         item.emit_to(state, editor, EditorEvent::Save);
+///     })
+///     .build(state, menu, |builder| builder.class("item"));
+/// ```
+pub struct MenuItem {
+    text: String,
+    shortcut: Option<Shortcut>,
+    on_press: Option<Box<dyn Fn(&mut Self, &mut State, Entity)>>,
+}
+
+impl MenuItem {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            shortcut: None,
+            on_press: None,
+        }
+    }
+
+    /// Sets the shortcut shown by the item
+    pub fn with_shortcut(mut self, shortcut: Shortcut) -> Self {
+        self.shortcut = Some(shortcut);
+
+        self
+    }
+
+    /// Sets the callback triggered when the item is clicked
+    pub fn on_press<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut Self, &mut State, Entity),
+    {
+        self.on_press = Some(Box::new(callback));
+
+        self
+    }
+}
+
+impl Widget for MenuItem {
+    type Ret = Entity;
+    type Data = ();
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        Element::new().build(state, entity, |builder| {
+            builder
+                .set_text(&self.text)
+                .set_hoverable(false)
+                .set_focusable(false)
+                .class("label")
+        });
+
+        if let Some(shortcut) = &self.shortcut {
+            Element::new().build(state, entity, |builder| {
+                builder
+                    .set_text(&shortcut.to_string())
+                    .set_width(Auto)
+                    .set_hoverable(false)
+                    .set_focusable(false)
+                    .class("shortcut")
+            });
+        }
+
+        entity
+            .set_layout_type(state, LayoutType::Row)
+            .set_element(state, "menu_item")
+    }
+
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseUp(button) if *button == MouseButton::Left => {
+                    if event.target == entity && state.hovered == entity {
+                        if let Some(callback) = self.on_press.take() {
+                            (callback)(self, state, entity);
+                            self.on_press = Some(callback);
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
 pub struct MenuBar {
     open_menu: Entity,
 }
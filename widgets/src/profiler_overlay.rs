@@ -0,0 +1,143 @@
+use crate::common::*;
+use tuix_core::{draw_background, draw_border, Paint, Path, ProfiledSystem};
+
+use std::time::Duration;
+
+// The color of each profiled system, in the order of ProfiledSystem::ALL
+const SYSTEM_COLORS: [(u8, u8, u8); 6] = [
+    (230, 159, 0),
+    (86, 180, 233),
+    (0, 158, 115),
+    (240, 228, 66),
+    (204, 121, 167),
+    (213, 94, 0),
+];
+
+/// A widget which charts the frames recorded by the [Profiler](tuix_core::Profiler).
+///
+/// Each frame is drawn as a bar, with the time spent in each system stacked from the bottom, oldest frame on the left.
+/// The height of the widget represents the frame budget, which defaults to the frame interval of the scheduler, and is
+/// marked with a line if it's exceeded. Building the overlay enables the profiler.
+///
+/// The systems are colored, from the bottom, events (orange), style (light blue), clipping (green), layout (yellow),
+/// animations (pink) and draw (red). Events includes any restyle and relayout triggered by the events.
+///
+/// # Example
+/// ```
+/// ProfilerOverlay::new().build(state, window, |builder| {
+///     builder
+///         .set_right(Pixels(10.0))
+///         .set_top(Pixels(10.0))
+/// });
+/// ```
+pub struct ProfilerOverlay {
+    budget: Option<Duration>,
+}
+
+impl ProfilerOverlay {
+    pub fn new() -> Self {
+        Self { budget: None }
+    }
+
+    /// Sets the frame time represented by the height of the widget
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+
+        self
+    }
+}
+
+impl Widget for ProfilerOverlay {
+    type Ret = Entity;
+    type Data = ();
+
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        state.profiler.set_enabled(true);
+
+        // Redraw the chart after any event except the overlay's own redraw, so the overlay alone doesn't keep the application busy
+        entity.add_listener(state, |_overlay: &mut Self, state, entity, event| {
+            let own_redraw = event.origin == entity && matches!(event.message.downcast::<WindowEvent>(), Some(WindowEvent::Redraw));
+
+            if !own_redraw {
+                entity.redraw(state);
+            }
+        });
+
+        entity
+            .set_position_type(state, PositionType::SelfDirected)
+            .set_width(state, Pixels(240.0))
+            .set_height(state, Pixels(80.0))
+            .set_background_color(state, Color::rgba(0, 0, 0, 160))
+            .set_z_order(state, 1000)
+            .set_element(state, "profiler_overlay")
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas) {
+        draw_background(state, entity, canvas);
+
+        let bounds = state.data.get_bounds(entity);
+        let opacity = state.data.get_opacity(entity);
+
+        let capacity = state.profiler.capacity();
+        if bounds.w <= 0.0 || bounds.h <= 0.0 || capacity == 0 {
+            return;
+        }
+
+        let budget = self.budget.unwrap_or(state.scheduler.frame_interval()).as_secs_f32();
+        if budget <= 0.0 {
+            return;
+        }
+
+        let bar_width = bounds.w / capacity as f32;
+        let records = state.profiler.records().collect::<Vec<_>>();
+        // The newest frame is drawn at the right edge
+        let first_x = bounds.x + bounds.w - records.len() as f32 * bar_width;
+
+        let mut over_budget = false;
+
+        for (system_index, system) in ProfiledSystem::ALL.iter().enumerate() {
+            let (r, g, b) = SYSTEM_COLORS[system_index];
+            let mut color = femtovg::Color::rgb(r, g, b);
+            color.set_alphaf(opacity);
+
+            let mut path = Path::new();
+
+            for (index, record) in records.iter().enumerate() {
+                // The systems below this one in the stack
+                let below = ProfiledSystem::ALL[..system_index]
+                    .iter()
+                    .map(|system| record.get(*system).duration.as_secs_f32())
+                    .sum::<f32>();
+                let duration = record.get(*system).duration.as_secs_f32();
+
+                over_budget |= below + duration > budget;
+
+                let bottom = (below / budget).min(1.0) * bounds.h;
+                let top = ((below + duration) / budget).min(1.0) * bounds.h;
+
+                if top > bottom {
+                    path.rect(
+                        first_x + index as f32 * bar_width,
+                        bounds.y + bounds.h - top,
+                        bar_width,
+                        top - bottom,
+                    );
+                }
+            }
+
+            canvas.fill_path(&mut path, Paint::color(color));
+        }
+
+        // Mark the budget when a frame exceeded it
+        if over_budget {
+            let mut color = femtovg::Color::rgb(255, 0, 0);
+            color.set_alphaf(opacity);
+
+            let mut path = Path::new();
+            path.rect(bounds.x, bounds.y, bounds.w, 1.0);
+            canvas.fill_path(&mut path, Paint::color(color));
+        }
+
+        draw_border(state, entity, canvas);
+    }
+}
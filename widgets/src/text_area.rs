@@ -1,6 +1,6 @@
 use crate::common::*;
 
-use femtovg::{Align, Baseline, Paint, Path};
+use tuix_core::{Align, Baseline, Paint, Path};
 
 #[derive(Default)]
 pub struct TextSpan {